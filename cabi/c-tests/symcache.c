@@ -0,0 +1,63 @@
+#include <stdio.h>
+#include <string.h>
+#include <assert.h>
+#include "symbolic.h"
+
+#define FIXTURE "../testutils/fixtures/linux/dwarf5/dwarf5"
+
+
+static int str_eq(const SymbolicStr *s, const char *expected)
+{
+    return s->len == strlen(expected) && memcmp(s->data, expected, s->len) == 0;
+}
+
+int main()
+{
+    symbolic_init();
+
+    SymbolicFatObject *fo = symbolic_fatobject_open(FIXTURE);
+    assert(fo);
+    SymbolicObject *obj = symbolic_fatobject_get_object(fo, 0);
+    assert(obj);
+    SymbolicSymCache *cache = symbolic_symcache_from_object(obj);
+    assert(cache);
+
+    printf("Lookup by name:\n");
+    SymbolicStr name = symbolic_str_from_cstr("square");
+    SymbolicFunctionLookupResult rv = symbolic_symcache_lookup_name(cache, &name);
+    assert(rv.len == 1);
+    assert(rv.items[0].addr == 0x1180);
+    assert(rv.items[0].size == 5);
+    assert(rv.items[0].parent_id != UINT32_MAX);
+    printf("  square: 0x%llx\n", (unsigned long long)rv.items[0].addr);
+    symbolic_function_lookup_result_free(&rv);
+
+    name = symbolic_str_from_cstr("missing");
+    rv = symbolic_symcache_lookup_name(cache, &name);
+    assert(rv.len == 0);
+    symbolic_function_lookup_result_free(&rv);
+
+    printf("Lookup by range:\n");
+    rv = symbolic_symcache_lookup_range(cache, 0x1184, 0x1190);
+    assert(rv.len == 2);
+    assert(str_eq(&rv.items[0].symbol, "compute"));
+    assert(rv.items[0].parent_id == UINT32_MAX);
+    assert(str_eq(&rv.items[1].symbol, "square"));
+    assert(rv.items[1].parent_id == rv.items[0].id);
+    for (uintptr_t i = 0; i < rv.len; i++) {
+        printf("  ");
+        fwrite(rv.items[i].symbol.data, 1, rv.items[i].symbol.len, stdout);
+        printf(": 0x%llx\n", (unsigned long long)rv.items[i].addr);
+    }
+    symbolic_function_lookup_result_free(&rv);
+
+    rv = symbolic_symcache_lookup_range(cache, 0x1180, 0x1180);
+    assert(rv.len == 0);
+    symbolic_function_lookup_result_free(&rv);
+
+    symbolic_symcache_free(cache);
+    symbolic_object_free(obj);
+    symbolic_fatobject_free(fo);
+
+    return 0;
+}
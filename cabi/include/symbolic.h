@@ -31,6 +31,8 @@ enum SymbolicErrorCode {
   SYMBOLIC_ERROR_CODE_CFI_ERROR_UNSUPPORTED_ARCH = 3004,
   SYMBOLIC_ERROR_CODE_CFI_ERROR_WRITE_ERROR = 3005,
   SYMBOLIC_ERROR_CODE_CFI_ERROR_BAD_FILE_MAGIC = 3006,
+  SYMBOLIC_ERROR_CODE_CFI_ERROR_UNSUPPORTED_VERSION = 3007,
+  SYMBOLIC_ERROR_CODE_CFI_ERROR_BAD_CACHE_FILE = 3008,
   SYMBOLIC_ERROR_CODE_PROCESS_MINIDUMP_ERROR_MINIDUMP_NOT_FOUND = 4001,
   SYMBOLIC_ERROR_CODE_PROCESS_MINIDUMP_ERROR_NO_MINIDUMP_HEADER = 4002,
   SYMBOLIC_ERROR_CODE_PROCESS_MINIDUMP_ERROR_NO_THREAD_LIST = 4003,
@@ -51,6 +53,9 @@ enum SymbolicErrorCode {
   SYMBOLIC_ERROR_CODE_SYM_CACHE_ERROR_VALUE_TOO_LARGE = 6010,
   SYMBOLIC_ERROR_CODE_SYM_CACHE_ERROR_WRITE_FAILED = 6011,
   SYMBOLIC_ERROR_CODE_SYM_CACHE_ERROR_TOO_MANY_VALUES = 6012,
+  SYMBOLIC_ERROR_CODE_SYM_CACHE_ERROR_MISMATCHED_DEBUG_ID = 6013,
+  SYMBOLIC_ERROR_CODE_SYM_CACHE_ERROR_CHECKSUM_MISMATCH = 6014,
+  SYMBOLIC_ERROR_CODE_SYM_CACHE_ERROR_UNSUPPORTED_STREAMING = 6015,
   SYMBOLIC_ERROR_CODE_UNREAL4_ERROR_UNKNOWN_BYTES_FORMAT = 7001,
   SYMBOLIC_ERROR_CODE_UNREAL4_ERROR_EMPTY = 7002,
   SYMBOLIC_ERROR_CODE_UNREAL4_ERROR_OUT_OF_BOUNDS = 7003,
@@ -191,6 +196,33 @@ typedef struct {
   uintptr_t len;
 } SymbolicLookupResult;
 
+/*
+ * Represents a single function after lookup.
+ */
+typedef struct {
+  /*
+   * The ID of the function within the symcache.
+   */
+  uint32_t id;
+  /*
+   * The ID of the parent function, or `UINT32_MAX` if the function is not inlined.
+   */
+  uint32_t parent_id;
+  uint64_t addr;
+  uint64_t size;
+  SymbolicStr lang;
+  SymbolicStr symbol;
+  SymbolicStr comp_dir;
+} SymbolicFunctionInfo;
+
+/*
+ * Represents a function lookup result of one or more items.
+ */
+typedef struct {
+  SymbolicFunctionInfo *items;
+  uintptr_t len;
+} SymbolicFunctionLookupResult;
+
 /*
  * A list of object features.
  */
@@ -437,6 +469,11 @@ void symbolic_frame_info_map_free(SymbolicFrameInfoMap *smap);
  */
 SymbolicFrameInfoMap *symbolic_frame_info_map_new(void);
 
+/*
+ * Frees a function lookup result.
+ */
+void symbolic_function_lookup_result_free(SymbolicFunctionLookupResult *slr);
+
 /*
  * Converts a Breakpad CodeModuleId to DebugId.
  */
@@ -712,6 +749,19 @@ uint32_t symbolic_symcache_latest_file_format_version(void);
  */
 SymbolicLookupResult symbolic_symcache_lookup(const SymbolicSymCache *scache, uint64_t addr);
 
+/*
+ * Looks up all functions with the given demangled name, including inlined instances.
+ */
+SymbolicFunctionLookupResult symbolic_symcache_lookup_name(const SymbolicSymCache *scache,
+                                                           const SymbolicStr *name);
+
+/*
+ * Looks up all functions overlapping the address range `[start, end)`.
+ */
+SymbolicFunctionLookupResult symbolic_symcache_lookup_range(const SymbolicSymCache *scache,
+                                                            uint64_t start,
+                                                            uint64_t end);
+
 /*
  * Free a token match.
  */
@@ -139,6 +139,8 @@ pub enum SymbolicErrorCode {
     CfiErrorUnsupportedArch = 3004,
     CfiErrorWriteError = 3005,
     CfiErrorBadFileMagic = 3006,
+    CfiErrorUnsupportedVersion = 3007,
+    CfiErrorBadCacheFile = 3008,
 
     // symbolic::minidump::processor
     ProcessMinidumpErrorMinidumpNotFound = 4001,
@@ -165,6 +167,9 @@ pub enum SymbolicErrorCode {
     SymCacheErrorValueTooLarge = 6010,
     SymCacheErrorWriteFailed = 6011,
     SymCacheErrorTooManyValues = 6012,
+    SymCacheErrorMismatchedDebugId = 6013,
+    SymCacheErrorChecksumMismatch = 6014,
+    SymCacheErrorUnsupportedStreaming = 6015,
 
     // symbolic::unreal
     Unreal4ErrorUnknownBytesFormat = 7001,
@@ -235,6 +240,10 @@ impl SymbolicErrorCode {
                     CfiErrorKind::UnsupportedArch => SymbolicErrorCode::CfiErrorUnsupportedArch,
                     CfiErrorKind::WriteError => SymbolicErrorCode::CfiErrorWriteError,
                     CfiErrorKind::BadFileMagic => SymbolicErrorCode::CfiErrorBadFileMagic,
+                    CfiErrorKind::UnsupportedVersion => {
+                        SymbolicErrorCode::CfiErrorUnsupportedVersion
+                    }
+                    CfiErrorKind::BadCacheFile => SymbolicErrorCode::CfiErrorBadCacheFile,
                 };
             }
 
@@ -301,6 +310,15 @@ impl SymbolicErrorCode {
                     SymCacheErrorKind::TooManyValues(_) => {
                         SymbolicErrorCode::SymCacheErrorTooManyValues
                     }
+                    SymCacheErrorKind::MismatchedDebugId => {
+                        SymbolicErrorCode::SymCacheErrorMismatchedDebugId
+                    }
+                    SymCacheErrorKind::ChecksumMismatch => {
+                        SymbolicErrorCode::SymCacheErrorChecksumMismatch
+                    }
+                    SymCacheErrorKind::UnsupportedStreaming => {
+                        SymbolicErrorCode::SymCacheErrorUnsupportedStreaming
+                    }
                 };
             }
 
@@ -159,7 +159,7 @@ where
 }
 
 /// Maps a `CodeModule` to its FFI type.
-unsafe fn map_code_module(module: &CodeModule) -> SymbolicCodeModule {
+unsafe fn map_code_module(module: &CodeModule<'_>) -> SymbolicCodeModule {
     SymbolicCodeModule {
         id: module
             .id()
@@ -180,7 +180,7 @@ unsafe fn map_regval(regval: (&str, RegVal)) -> SymbolicRegVal {
 }
 
 /// Maps a `StackFrame` to its FFI type.
-unsafe fn map_stack_frame(frame: &StackFrame, arch: Arch) -> SymbolicStackFrame {
+unsafe fn map_stack_frame(frame: &StackFrame<'_>, arch: Arch) -> SymbolicStackFrame {
     let empty_module = SymbolicCodeModule {
         id: "".into(),
         addr: 0,
@@ -195,15 +195,15 @@ unsafe fn map_stack_frame(frame: &StackFrame, arch: Arch) -> SymbolicStackFrame
         instruction: frame.instruction(),
         return_address: frame.return_address(arch),
         trust: mem::transmute(frame.trust()),
-        module: frame.module().map_or(empty_module, |m| map_code_module(m)),
+        module: frame.module().map_or(empty_module, |m| map_code_module(&m)),
         registers,
         register_count,
     }
 }
 
 /// Maps a `CallStack` to its FFI type.
-unsafe fn map_call_stack(stack: &CallStack, arch: Arch) -> SymbolicCallStack {
-    let (frames, frame_count) = map_slice(stack.frames(), |f| map_stack_frame(f, arch));
+unsafe fn map_call_stack(stack: &CallStack<'_>, arch: Arch) -> SymbolicCallStack {
+    let (frames, frame_count) = map_slice(&stack.frames(), |f| map_stack_frame(f, arch));
     SymbolicCallStack {
         thread_id: stack.thread_id(),
         frames,
@@ -226,7 +226,7 @@ unsafe fn map_system_info(info: &SystemInfo) -> SymbolicSystemInfo {
 /// Maps a `ProcessState` to its FFI type.
 unsafe fn map_process_state(state: &ProcessState<'_>) -> SymbolicProcessState {
     let arch = state.system_info().cpu_arch();
-    let (threads, thread_count) = map_slice(state.threads(), |s| map_call_stack(s, arch));
+    let (threads, thread_count) = map_slice(&state.threads(), |s| map_call_stack(s, arch));
     let (modules, module_count) = map_iter(state.modules().iter(), |m| map_code_module(m));
 
     SymbolicProcessState {
@@ -362,7 +362,7 @@ ffi_fn! {
     /// Returns a pointer to the raw buffer of the CFI cache.
     unsafe fn symbolic_cficache_get_bytes(scache: *const SymbolicCfiCache) -> Result<*const u8> {
         let cache = scache as *const CfiCache<'static>;
-        Ok((*cache).as_slice().as_ptr())
+        Ok((*cache).as_bytes().as_ptr())
     }
 }
 
@@ -370,7 +370,7 @@ ffi_fn! {
     /// Returns the size of the raw buffer of the CFI cache.
     unsafe fn symbolic_cficache_get_size(scache: *const SymbolicCfiCache) -> Result<usize> {
         let cache = scache as *const CfiCache<'static>;
-        Ok((*cache).as_slice().len())
+        Ok((*cache).as_bytes().len())
     }
 }
 
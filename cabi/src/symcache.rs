@@ -5,7 +5,7 @@ use std::slice;
 
 use symbolic::common::{byteview::ByteView, types::Arch};
 use symbolic::debuginfo::Object;
-use symbolic::symcache::{InstructionInfo, SymCache, SYMCACHE_LATEST_VERSION};
+use symbolic::symcache::{Function, InstructionInfo, SymCache, SYMCACHE_LATEST_VERSION};
 
 use crate::core::SymbolicStr;
 use crate::debuginfo::SymbolicObject;
@@ -34,6 +34,27 @@ pub struct SymbolicLookupResult {
     pub len: usize,
 }
 
+/// Represents a single function after lookup.
+#[repr(C)]
+pub struct SymbolicFunctionInfo {
+    /// The ID of the function within the symcache.
+    pub id: u32,
+    /// The ID of the parent function, or `UINT32_MAX` if the function is not inlined.
+    pub parent_id: u32,
+    pub addr: u64,
+    pub size: u64,
+    pub lang: SymbolicStr,
+    pub symbol: SymbolicStr,
+    pub comp_dir: SymbolicStr,
+}
+
+/// Represents a function lookup result of one or more items.
+#[repr(C)]
+pub struct SymbolicFunctionLookupResult {
+    pub items: *mut SymbolicFunctionInfo,
+    pub len: usize,
+}
+
 /// Represents an instruction info.
 #[repr(C)]
 pub struct SymbolicInstructionInfo {
@@ -194,6 +215,64 @@ ffi_fn! {
     }
 }
 
+/// Converts found functions into a lookup result.
+fn make_function_lookup_result(functions: Vec<Function<'_>>) -> SymbolicFunctionLookupResult {
+    let mut items = vec![];
+    for function in functions {
+        items.push(SymbolicFunctionInfo {
+            id: function.id() as u32,
+            parent_id: function.parent_id().map_or(!0, |id| id as u32),
+            addr: function.addr(),
+            size: function.size(),
+            lang: SymbolicStr::new(function.lang().name()),
+            symbol: SymbolicStr::new(function.symbol()),
+            comp_dir: SymbolicStr::new(function.comp_dir()),
+        });
+    }
+
+    items.shrink_to_fit();
+    let rv = SymbolicFunctionLookupResult {
+        items: items.as_mut_ptr(),
+        len: items.len(),
+    };
+    mem::forget(items);
+    rv
+}
+
+ffi_fn! {
+    /// Looks up all functions with the given demangled name, including inlined instances.
+    unsafe fn symbolic_symcache_lookup_name(
+        scache: *const SymbolicSymCache,
+        name: *const SymbolicStr,
+    ) -> Result<SymbolicFunctionLookupResult> {
+        let cache = scache as *const SymCache<'static>;
+        let functions = (*cache).lookup_name((*name).as_str())?;
+        Ok(make_function_lookup_result(functions))
+    }
+}
+
+ffi_fn! {
+    /// Looks up all functions overlapping the address range `[start, end)`.
+    unsafe fn symbolic_symcache_lookup_range(
+        scache: *const SymbolicSymCache,
+        start: u64,
+        end: u64,
+    ) -> Result<SymbolicFunctionLookupResult> {
+        let cache = scache as *const SymCache<'static>;
+        let functions = (*cache).lookup_range(start, end)?;
+        Ok(make_function_lookup_result(functions))
+    }
+}
+
+ffi_fn! {
+    /// Frees a function lookup result.
+    unsafe fn symbolic_function_lookup_result_free(slr: *mut SymbolicFunctionLookupResult) {
+        if !slr.is_null() {
+            Vec::from_raw_parts((*slr).items, (*slr).len, (*slr).len);
+        }
+    }
+}
+
 ffi_fn! {
     /// Return the best instruction for an isntruction info.
     unsafe fn symbolic_find_best_instruction(ii: *const SymbolicInstructionInfo) -> Result<u64> {
@@ -173,6 +173,23 @@ impl Arch {
         })
     }
 
+    /// Constructs an architecture from PE/COFF machine types.
+    #[cfg(feature = "with_objects")]
+    pub fn from_pe(machine: u16) -> Result<Arch, UnknownArchError> {
+        use goblin::pe::header::*;
+        // Not defined in goblin
+        const COFF_MACHINE_ARM: u16 = 0x1c0;
+        const COFF_MACHINE_ARMNT: u16 = 0x1c4;
+        const COFF_MACHINE_ARM64: u16 = 0xaa64;
+        Ok(match machine {
+            COFF_MACHINE_X86 => Arch::X86,
+            COFF_MACHINE_X86_64 => Arch::X86_64,
+            COFF_MACHINE_ARM | COFF_MACHINE_ARMNT => Arch::Arm,
+            COFF_MACHINE_ARM64 => Arch::Arm64,
+            _ => return Err(UnknownArchError),
+        })
+    }
+
     /// Constructs an architecture from ELF flags.
     #[cfg(feature = "with_objects")]
     pub fn from_breakpad(string: &str) -> Result<Arch, UnknownArchError> {
@@ -539,6 +556,8 @@ pub enum ObjectKind {
     Breakpad,
     Elf,
     MachO,
+    Pe,
+    Pdb,
 }
 
 impl ObjectKind {
@@ -548,6 +567,8 @@ impl ObjectKind {
             ObjectKind::Breakpad => "breakpad",
             ObjectKind::Elf => "elf",
             ObjectKind::MachO => "macho",
+            ObjectKind::Pe => "pe",
+            ObjectKind::Pdb => "pdb",
         }
     }
 }
@@ -566,6 +587,8 @@ impl str::FromStr for ObjectKind {
             "breakpad" => ObjectKind::Breakpad,
             "elf" => ObjectKind::Elf,
             "macho" => ObjectKind::MachO,
+            "pe" => ObjectKind::Pe,
+            "pdb" => ObjectKind::Pdb,
             _ => return Err(UnknownObjectKindError),
         })
     }
@@ -669,6 +692,19 @@ impl ObjectClass {
         }
     }
 
+    #[cfg(feature = "with_objects")]
+    pub fn from_pe(characteristics: u16) -> ObjectClass {
+        use goblin::pe::characteristic::*;
+
+        if characteristics & IMAGE_FILE_DLL != 0 {
+            ObjectClass::Library
+        } else if characteristics & IMAGE_FILE_EXECUTABLE_IMAGE != 0 {
+            ObjectClass::Executable
+        } else {
+            ObjectClass::Other
+        }
+    }
+
     #[cfg(feature = "with_objects")]
     pub fn from_elf_full(elf_type: u16, has_interpreter: bool) -> ObjectClass {
         let class = ObjectClass::from_elf(elf_type);
@@ -722,6 +758,7 @@ pub struct UnknownDebugKindError;
 pub enum DebugKind {
     Dwarf,
     Breakpad,
+    Pdb,
 }
 
 impl DebugKind {
@@ -730,6 +767,7 @@ impl DebugKind {
         match self {
             DebugKind::Dwarf => "dwarf",
             DebugKind::Breakpad => "breakpad",
+            DebugKind::Pdb => "pdb",
         }
     }
 }
@@ -747,6 +785,7 @@ impl str::FromStr for DebugKind {
         Ok(match string {
             "dwarf" => DebugKind::Dwarf,
             "breakpad" => DebugKind::Breakpad,
+            "pdb" => DebugKind::Pdb,
             _ => return Err(UnknownDebugKindError),
         })
     }
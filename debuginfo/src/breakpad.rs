@@ -33,8 +33,12 @@ pub enum BreakpadRecord<'input> {
     Public(BreakpadPublicRecord<'input>),
     /// Meta data record (e.g. Build ID)
     Info(&'input [u8]),
-    /// Call Frame Information (CFI) record.
-    Stack,
+    /// Call Frame Information (CFI) record for a function.
+    StackCfi(BreakpadStackCfiRecord<'input>),
+    /// Call Frame Information (CFI) changes within a function.
+    StackCfiDelta(BreakpadStackCfiDeltaRecord<'input>),
+    /// Windows stack frame information (FPO or frame data).
+    StackWin(BreakpadStackWinRecord<'input>),
 }
 
 /// Breakpad module record containing general information on the file.
@@ -118,6 +122,346 @@ impl<'input> fmt::Debug for BreakpadPublicRecord<'input> {
     }
 }
 
+/// A register rule in a Breakpad STACK CFI record.
+///
+/// The expression is a postfix program that computes the value of the register in the caller's
+/// frame, e.g. `.cfa: $rsp 8 +` or `.ra: .cfa -8 + ^`.
+#[derive(Clone, PartialEq)]
+pub struct BreakpadCfiRule<'input> {
+    pub register: &'input [u8],
+    pub expression: &'input [u8],
+}
+
+impl<'input> fmt::Debug for BreakpadCfiRule<'input> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BreakpadCfiRule")
+            .field("register", &String::from_utf8_lossy(self.register))
+            .field("expression", &String::from_utf8_lossy(self.expression))
+            .finish()
+    }
+}
+
+impl<'input> fmt::Display for BreakpadCfiRule<'input> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}",
+            String::from_utf8_lossy(self.register),
+            String::from_utf8_lossy(self.expression)
+        )
+    }
+}
+
+/// Writes a list of CFI rules separated by spaces.
+fn write_cfi_rules(f: &mut fmt::Formatter<'_>, rules: &[BreakpadCfiRule<'_>]) -> fmt::Result {
+    for rule in rules {
+        write!(f, " {}", rule)?;
+    }
+
+    Ok(())
+}
+
+/// Breakpad STACK CFI delta record declaring changed register rules starting at an address.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreakpadStackCfiDeltaRecord<'input> {
+    pub address: u64,
+    pub rules: Vec<BreakpadCfiRule<'input>>,
+}
+
+impl<'input> fmt::Display for BreakpadStackCfiDeltaRecord<'input> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "STACK CFI {:x}", self.address)?;
+        write_cfi_rules(f, &self.rules)
+    }
+}
+
+/// Breakpad STACK CFI INIT record declaring the initial register rules of a function.
+///
+/// Delta records following the INIT record in the symbol file are not attached by the parser.
+/// Consumers can collect them into `deltas`, similar to line records of functions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreakpadStackCfiRecord<'input> {
+    pub address: u64,
+    pub size: u64,
+    pub rules: Vec<BreakpadCfiRule<'input>>,
+    pub deltas: Vec<BreakpadStackCfiDeltaRecord<'input>>,
+}
+
+impl<'input> BreakpadStackCfiRecord<'input> {
+    /// The end address of the covered code, exclusive.
+    pub fn end(&self) -> u64 {
+        self.address.saturating_add(self.size)
+    }
+
+    /// Checks that the rules of this record can be evaluated by a stack walker.
+    ///
+    /// The INIT rules must define the canonical frame address (`.cfa`) and the return address
+    /// (`.ra`). Delta records must be ordered by address and lie within the covered code.
+    pub fn validate(&self) -> Result<(), ParseBreakpadError> {
+        let has_rule = |name: &[u8]| self.rules.iter().any(|rule| rule.register == name);
+        if !has_rule(b".cfa") {
+            return Err(ParseBreakpadError("missing cfa rule"));
+        }
+        if !has_rule(b".ra") {
+            return Err(ParseBreakpadError("missing return address rule"));
+        }
+
+        let mut last_address = self.address;
+        for delta in &self.deltas {
+            if delta.address < last_address || delta.address >= self.end() {
+                return Err(ParseBreakpadError("stack cfi delta out of range"));
+            }
+            last_address = delta.address;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'input> fmt::Display for BreakpadStackCfiRecord<'input> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "STACK CFI INIT {:x} {:x}", self.address, self.size)?;
+        write_cfi_rules(f, &self.rules)?;
+
+        for delta in &self.deltas {
+            write!(f, "\n{}", delta)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The type of frame data in a Breakpad STACK WIN record.
+///
+/// This corresponds to `StackFrameTypeEnum` in the Microsoft DIA SDK.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum BreakpadStackWinType {
+    /// Frame pointer omitted, FPO info available.
+    Fpo = 0,
+    /// Kernel trap frame.
+    Trap = 1,
+    /// Kernel task state segment.
+    Tss = 2,
+    /// Standard EBP stack frame.
+    Standard = 3,
+    /// Frame pointer omitted, frame data info available.
+    FrameData = 4,
+}
+
+/// Breakpad STACK WIN record declaring how to unwind a function on 32-bit Windows.
+#[derive(Clone, PartialEq)]
+pub struct BreakpadStackWinRecord<'input> {
+    pub ty: BreakpadStackWinType,
+    pub rva: u32,
+    pub code_size: u32,
+    pub prolog_size: u32,
+    pub epilog_size: u32,
+    pub params_size: u32,
+    pub saved_regs_size: u32,
+    pub locals_size: u32,
+    pub max_stack_size: u32,
+    pub program_string: Option<&'input [u8]>,
+    pub allocates_base_pointer: bool,
+}
+
+impl<'input> BreakpadStackWinRecord<'input> {
+    /// The start address of the covered code.
+    pub fn address(&self) -> u64 {
+        u64::from(self.rva)
+    }
+
+    /// The end address of the covered code, exclusive.
+    pub fn end(&self) -> u64 {
+        self.address() + u64::from(self.code_size)
+    }
+
+    /// Checks that this record can be evaluated by a stack walker.
+    ///
+    /// Frame data records are evaluated with their program string, which must be present.
+    pub fn validate(&self) -> Result<(), ParseBreakpadError> {
+        if self.ty == BreakpadStackWinType::FrameData && self.program_string.is_none() {
+            return Err(ParseBreakpadError("missing stack win program string"));
+        }
+
+        if u64::from(self.prolog_size) + u64::from(self.epilog_size) > u64::from(self.code_size) {
+            return Err(ParseBreakpadError("stack win prolog exceeds code size"));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'input> fmt::Debug for BreakpadStackWinRecord<'input> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BreakpadStackWinRecord")
+            .field("ty", &self.ty)
+            .field("rva", &self.rva)
+            .field("code_size", &self.code_size)
+            .field("prolog_size", &self.prolog_size)
+            .field("epilog_size", &self.epilog_size)
+            .field("params_size", &self.params_size)
+            .field("saved_regs_size", &self.saved_regs_size)
+            .field("locals_size", &self.locals_size)
+            .field("max_stack_size", &self.max_stack_size)
+            .field(
+                "program_string",
+                &self.program_string.map(String::from_utf8_lossy),
+            )
+            .field("allocates_base_pointer", &self.allocates_base_pointer)
+            .finish()
+    }
+}
+
+impl<'input> fmt::Display for BreakpadStackWinRecord<'input> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "STACK WIN {} {:x} {:x} {:x} {:x} {:x} {:x} {:x} {:x} ",
+            self.ty as u8,
+            self.rva,
+            self.code_size,
+            self.prolog_size,
+            self.epilog_size,
+            self.params_size,
+            self.saved_regs_size,
+            self.locals_size,
+            self.max_stack_size,
+        )?;
+
+        match self.program_string {
+            Some(program_string) => write!(f, "1 {}", String::from_utf8_lossy(program_string)),
+            None => write!(f, "0 {}", self.allocates_base_pointer as u8),
+        }
+    }
+}
+
+/// A collection of unwind information from a Breakpad symbol file.
+///
+/// STACK CFI records are stored with their delta records attached. Both lists are sorted by
+/// address. Use the `Display` implementation to write the records back in Breakpad format.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BreakpadStackRecords<'input> {
+    cfi: Vec<BreakpadStackCfiRecord<'input>>,
+    win: Vec<BreakpadStackWinRecord<'input>>,
+}
+
+impl<'input> BreakpadStackRecords<'input> {
+    /// Creates an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects all STACK records from a Breakpad symbol file.
+    pub fn from_records(records: BreakpadRecords<'input>) -> Result<Self, ParseBreakpadError> {
+        let mut stack_records = Self::new();
+
+        for record in records {
+            match record? {
+                BreakpadRecord::StackCfi(cfi) => stack_records.cfi.push(cfi),
+                BreakpadRecord::StackCfiDelta(delta) => match stack_records.cfi.last_mut() {
+                    Some(cfi) => cfi.deltas.push(delta),
+                    None => return Err(ParseBreakpadError("unexpected stack cfi delta record")),
+                },
+                BreakpadRecord::StackWin(win) => stack_records.win.push(win),
+                _ => (),
+            }
+        }
+
+        stack_records.cfi.sort_by_key(|cfi| cfi.address);
+        stack_records.win.sort_by_key(|win| win.rva);
+        Ok(stack_records)
+    }
+
+    /// Returns all STACK CFI records, ordered by address.
+    pub fn cfi_records(&self) -> &[BreakpadStackCfiRecord<'input>] {
+        &self.cfi
+    }
+
+    /// Returns all STACK WIN records, ordered by address.
+    pub fn win_records(&self) -> &[BreakpadStackWinRecord<'input>] {
+        &self.win
+    }
+
+    /// Indicates whether this collection contains any records.
+    pub fn is_empty(&self) -> bool {
+        self.cfi.is_empty() && self.win.is_empty()
+    }
+
+    /// Validates all records in this collection.
+    ///
+    /// In addition to the checks of each record, records of the same kind must not overlap.
+    pub fn validate(&self) -> Result<(), ParseBreakpadError> {
+        for cfi in &self.cfi {
+            cfi.validate()?;
+        }
+
+        for pair in self.cfi.windows(2) {
+            if pair[0].end() > pair[1].address {
+                return Err(ParseBreakpadError("overlapping stack cfi records"));
+            }
+        }
+
+        for win in &self.win {
+            win.validate()?;
+        }
+
+        Ok(())
+    }
+
+    /// Merges records from another collection into this one.
+    ///
+    /// Records in `self` take precedence. Records from `other` are only added if they do not
+    /// overlap with an existing record of the same kind.
+    pub fn merge(&mut self, other: BreakpadStackRecords<'input>) {
+        for cfi in other.cfi {
+            let index = match self.cfi.binary_search_by_key(&cfi.address, |c| c.address) {
+                Ok(_) => continue,
+                Err(index) => index,
+            };
+
+            let overlaps_prev = index > 0 && self.cfi[index - 1].end() > cfi.address;
+            let overlaps_next = index < self.cfi.len() && cfi.end() > self.cfi[index].address;
+            if !overlaps_prev && !overlaps_next {
+                self.cfi.insert(index, cfi);
+            }
+        }
+
+        for win in other.win {
+            let index = match self.win.binary_search_by_key(&win.rva, |w| w.rva) {
+                Ok(_) => continue,
+                Err(index) => index,
+            };
+
+            let overlaps_prev = index > 0 && self.win[index - 1].end() > win.address();
+            let overlaps_next = index < self.win.len() && win.end() > self.win[index].address();
+            if !overlaps_prev && !overlaps_next {
+                self.win.insert(index, win);
+            }
+        }
+    }
+
+    /// Removes all records that do not cover any address in the range `start..end`.
+    pub fn retain_range(&mut self, start: u64, end: u64) {
+        let overlaps = |address: u64, record_end: u64| address < end && record_end > start;
+        self.cfi.retain(|cfi| overlaps(cfi.address, cfi.end()));
+        self.win.retain(|win| overlaps(win.address(), win.end()));
+    }
+}
+
+impl<'input> fmt::Display for BreakpadStackRecords<'input> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for cfi in &self.cfi {
+            writeln!(f, "{}", cfi)?;
+        }
+
+        for win in &self.win {
+            writeln!(f, "{}", win)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Provides access to information in a breakpad file.
 #[derive(Debug)]
 pub(crate) struct BreakpadSym {
@@ -175,22 +519,38 @@ enum IterState {
     Started,
     Reading,
     Function,
+    StackCfi,
 }
 
 /// An iterator over records in a Breakpad symbol file.
 pub struct BreakpadRecords<'data> {
     lines: Box<dyn Iterator<Item = &'data [u8]> + 'data>,
     state: IterState,
+    skip_stack: bool,
 }
 
 impl<'data> BreakpadRecords<'data> {
-    fn from_bytes(bytes: &'data [u8]) -> BreakpadRecords<'data> {
+    /// Creates an iterator over records in raw Breakpad data.
+    ///
+    /// The data does not need to start with a MODULE record. This allows to parse partial files,
+    /// such as the STACK records written by a CFI writer.
+    pub fn from_bytes(bytes: &'data [u8]) -> BreakpadRecords<'data> {
         BreakpadRecords {
             lines: Box::new(bytes.split(|b| *b == b'\n')),
             state: IterState::Started,
+            skip_stack: false,
         }
     }
 
+    /// Skips all STACK records without parsing them.
+    ///
+    /// This is useful for consumers that only need symbols and line information, which should not
+    /// fail on malformed unwind information.
+    pub fn skip_stack_records(mut self) -> Self {
+        self.skip_stack = true;
+        self
+    }
+
     fn parse(&mut self, line: &'data [u8]) -> Result<BreakpadRecord<'data>, ParseBreakpadError> {
         let mut words = line.splitn(2, |b| *b == b' ');
         let magic = words.next().unwrap_or(b"");
@@ -214,8 +574,18 @@ impl<'data> BreakpadRecords<'data> {
                 parse_func(record)
             }
             b"STACK" => {
-                self.state = IterState::Reading;
-                parse_stack(record)
+                let record = parse_stack(record)?;
+                match record {
+                    BreakpadRecord::StackCfi(_) => self.state = IterState::StackCfi,
+                    BreakpadRecord::StackCfiDelta(_) => {
+                        if self.state != IterState::StackCfi {
+                            return Err(ParseBreakpadError("unexpected stack cfi delta record"));
+                        }
+                    }
+                    _ => self.state = IterState::Reading,
+                }
+
+                Ok(record)
             }
             b"PUBLIC" => {
                 self.state = IterState::Reading;
@@ -248,9 +618,20 @@ impl<'data> Iterator for BreakpadRecords<'data> {
                 len -= 1;
             }
 
-            if len > 0 {
-                return Some(self.parse(&next[0..len]));
+            if len == 0 {
+                continue;
+            }
+
+            let line = &next[0..len];
+            if self.skip_stack && line.starts_with(b"STACK ") {
+                // Line records cannot follow STACK records
+                if self.state != IterState::Started {
+                    self.state = IterState::Reading;
+                }
+                continue;
             }
+
+            return Some(self.parse(line));
         }
 
         None
@@ -396,9 +777,161 @@ fn parse_func(line: &[u8]) -> Result<BreakpadRecord<'_>, ParseBreakpadError> {
 /// Syntax: "STACK CFI INIT address size register1: expression1 register2: expression2 ..."
 /// Example: "STACK CFI INIT 804c4b0 40 .cfa: $esp 4 + $eip: .cfa 4 - ^"
 /// see <https://github.com/google/breakpad/blob/master/docs/symbol_files.md#stack-cfi-records>
-fn parse_stack(_line: &[u8]) -> Result<BreakpadRecord<'_>, ParseBreakpadError> {
-    // Ignored
-    Ok(BreakpadRecord::Stack)
+///
+/// CFI INIT records may be followed by delta records, which only contain changed rules.
+/// Syntax: "STACK CFI address register1: expression1 register2: expression2 ..."
+/// Example: "STACK CFI 804c4b1 .cfa: $esp 8 + $ebp: .cfa 8 - ^"
+fn parse_stack(line: &[u8]) -> Result<BreakpadRecord<'_>, ParseBreakpadError> {
+    let mut record = line.splitn(2, |b| *b == b' ');
+    let kind = record.next().unwrap_or(b"");
+    let record = record.next().unwrap_or(b"");
+
+    match kind {
+        b"CFI" => parse_stack_cfi(record),
+        b"WIN" => parse_stack_win(record),
+        _ => Err(ParseBreakpadError("unknown stack record")),
+    }
+}
+
+/// Parses a hexadecimal number in a breakpad record.
+fn parse_hex(word: Option<&[u8]>, message: &'static str) -> Result<u64, ParseBreakpadError> {
+    let word = word.map(String::from_utf8_lossy).unwrap_or_default();
+    u64::from_str_radix(&word, 16).map_err(|_| ParseBreakpadError(message))
+}
+
+/// Parses the body of a breakpad STACK CFI record, either INIT or delta.
+fn parse_stack_cfi(line: &[u8]) -> Result<BreakpadRecord<'_>, ParseBreakpadError> {
+    if line.starts_with(b"INIT ") {
+        let mut record = line[5..].splitn(3, |b| *b == b' ');
+        let address = parse_hex(record.next(), "invalid stack cfi address")?;
+        let size = parse_hex(record.next(), "invalid stack cfi size")?;
+        let rules = parse_cfi_rules(record.next().unwrap_or(b""))?;
+        if rules.is_empty() {
+            return Err(ParseBreakpadError("missing cfi rules"));
+        }
+
+        Ok(BreakpadRecord::StackCfi(BreakpadStackCfiRecord {
+            address,
+            size,
+            rules,
+            deltas: vec![],
+        }))
+    } else {
+        let mut record = line.splitn(2, |b| *b == b' ');
+        let address = parse_hex(record.next(), "invalid stack cfi address")?;
+        let rules = parse_cfi_rules(record.next().unwrap_or(b""))?;
+
+        Ok(BreakpadRecord::StackCfiDelta(BreakpadStackCfiDeltaRecord {
+            address,
+            rules,
+        }))
+    }
+}
+
+/// Parses a list of register rules in a breakpad STACK CFI record.
+///
+/// Each rule starts with a register name followed by a colon. All words up to the next register
+/// name form the postfix expression of the rule. Delta records may not contain any rules, if the
+/// writer was unable to express changed rules.
+fn parse_cfi_rules(line: &[u8]) -> Result<Vec<BreakpadCfiRule<'_>>, ParseBreakpadError> {
+    let mut rules = Vec::new();
+    let mut register = None;
+    let mut expression: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    let mut push_rule = |register, expression: Option<(usize, usize)>| match expression {
+        Some((start, end)) => {
+            rules.push(BreakpadCfiRule {
+                register,
+                expression: &line[start..end],
+            });
+            Ok(())
+        }
+        None => Err(ParseBreakpadError("missing cfi rule expression")),
+    };
+
+    for word in line.split(|b| *b == b' ') {
+        let start = offset;
+        offset += word.len() + 1;
+
+        if word.is_empty() {
+            continue;
+        }
+
+        if word.ends_with(b":") {
+            if let Some(register) = register {
+                push_rule(register, expression.take())?;
+            }
+            register = Some(&word[..word.len() - 1]);
+        } else if register.is_none() {
+            return Err(ParseBreakpadError("missing cfi rule register"));
+        } else {
+            let end = start + word.len();
+            expression = Some(expression.map_or((start, end), |(start, _)| (start, end)));
+        }
+    }
+
+    if let Some(register) = register {
+        push_rule(register, expression)?;
+    }
+
+    Ok(rules)
+}
+
+/// Parses the body of a breakpad STACK WIN record.
+fn parse_stack_win(line: &[u8]) -> Result<BreakpadRecord<'_>, ParseBreakpadError> {
+    let mut record = line.splitn(11, |b| *b == b' ');
+
+    let ty = match record.next() {
+        Some(b"0") => BreakpadStackWinType::Fpo,
+        Some(b"1") => BreakpadStackWinType::Trap,
+        Some(b"2") => BreakpadStackWinType::Tss,
+        Some(b"3") => BreakpadStackWinType::Standard,
+        Some(b"4") => BreakpadStackWinType::FrameData,
+        _ => return Err(ParseBreakpadError("invalid stack win type")),
+    };
+
+    let mut parse_field = |message| -> Result<u32, ParseBreakpadError> {
+        let value = parse_hex(record.next(), message)?;
+        if value > u64::from(u32::MAX) {
+            return Err(ParseBreakpadError(message));
+        }
+        Ok(value as u32)
+    };
+
+    let rva = parse_field("invalid stack win rva")?;
+    let code_size = parse_field("invalid stack win code size")?;
+    let prolog_size = parse_field("invalid stack win prolog size")?;
+    let epilog_size = parse_field("invalid stack win epilog size")?;
+    let params_size = parse_field("invalid stack win parameter size")?;
+    let saved_regs_size = parse_field("invalid stack win saved register size")?;
+    let locals_size = parse_field("invalid stack win local size")?;
+    let max_stack_size = parse_field("invalid stack win max stack size")?;
+
+    let has_program_string = record.next() == Some(b"1");
+    let remainder = record
+        .next()
+        .ok_or(ParseBreakpadError("missing stack win program string"))?;
+
+    let (program_string, allocates_base_pointer) = if has_program_string {
+        (Some(remainder), false)
+    } else {
+        (None, remainder == b"1")
+    };
+
+    Ok(BreakpadRecord::StackWin(BreakpadStackWinRecord {
+        ty,
+        rva,
+        code_size,
+        prolog_size,
+        epilog_size,
+        params_size,
+        saved_regs_size,
+        locals_size,
+        max_stack_size,
+        program_string,
+        allocates_base_pointer,
+    }))
 }
 
 /// Parses a breakpad PUBLIC record.
@@ -501,3 +1034,24 @@ fn test_parse_line() {
         lines: vec![],
     }));
 }
+
+#[test]
+fn test_parse_empty_cfi_delta() {
+    let iter = BreakpadRecords::from_bytes(
+        &b"\
+        STACK CFI INIT 1580 370 .cfa: $rsp 16 + .ra: .cfa -8 + ^\n\
+        STACK CFI 1590\
+    "[..],
+    );
+    let records: Vec<_> = iter.map(|x| x.unwrap()).collect();
+    assert_eq!(
+        records[1],
+        BreakpadRecord::StackCfiDelta(BreakpadStackCfiDeltaRecord {
+            address: 0x1590,
+            rules: vec![],
+        })
+    );
+
+    let mut iter = BreakpadRecords::from_bytes(&b"STACK CFI INIT 1580 370"[..]);
+    assert!(iter.next().unwrap().is_err());
+}
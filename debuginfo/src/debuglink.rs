@@ -0,0 +1,256 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use flate2::Crc;
+
+use symbolic_common::byteview::ByteView;
+
+use crate::object::{FatObject, Object};
+
+/// The default global debug directory used by GDB and most Linux distributions.
+const DEFAULT_DEBUG_DIR: &str = "/usr/lib/debug";
+
+/// A reference to a separate debug file from the `.gnu_debuglink` section.
+///
+/// The debug link contains the file name of the debug companion file, which is usually located
+/// next to the executable or in a global debug directory, and a CRC32 checksum over the entire
+/// contents of that file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DebugLink {
+    filename: String,
+    crc: u32,
+}
+
+impl DebugLink {
+    /// Creates a new debug link from its components.
+    pub fn new<S: Into<String>>(filename: S, crc: u32) -> DebugLink {
+        DebugLink {
+            filename: filename.into(),
+            crc,
+        }
+    }
+
+    /// Parses the contents of a `.gnu_debuglink` section.
+    ///
+    /// The section contains a NUL-terminated file name, padded to a four byte boundary, followed
+    /// by the CRC32 checksum of the debug file in the object's byte order.
+    pub fn parse(data: &[u8], little_endian: bool) -> Option<DebugLink> {
+        let nul = data.iter().position(|b| *b == 0)?;
+        let offset = (nul + 4) & !3;
+        if data.len() < offset + 4 {
+            return None;
+        }
+
+        let mut crc_bytes = [0; 4];
+        crc_bytes.copy_from_slice(&data[offset..offset + 4]);
+        let crc = if little_endian {
+            u32::from_le_bytes(crc_bytes)
+        } else {
+            u32::from_be_bytes(crc_bytes)
+        };
+
+        Some(DebugLink::new(String::from_utf8_lossy(&data[..nul]), crc))
+    }
+
+    /// The file name of the debug companion file.
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// The CRC32 checksum of the debug companion file.
+    pub fn crc(&self) -> u32 {
+        self.crc
+    }
+
+    /// Checks whether the given file contents match the checksum of this link.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        self.crc == crc32(data)
+    }
+}
+
+/// A reference to a supplementary debug file from the `.gnu_debugaltlink` section.
+///
+/// Supplementary files are created by `dwz`, which moves DWARF data shared between multiple
+/// debug files into a common file. The link contains the path to that file along with its build
+/// identifier.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DebugAltLink {
+    filename: String,
+    build_id: Vec<u8>,
+}
+
+impl DebugAltLink {
+    /// Creates a new supplementary debug link from its components.
+    pub fn new<S: Into<String>, B: Into<Vec<u8>>>(filename: S, build_id: B) -> DebugAltLink {
+        DebugAltLink {
+            filename: filename.into(),
+            build_id: build_id.into(),
+        }
+    }
+
+    /// Parses the contents of a `.gnu_debugaltlink` section.
+    ///
+    /// The section contains a NUL-terminated path followed by the raw build identifier of the
+    /// supplementary file.
+    pub fn parse(data: &[u8]) -> Option<DebugAltLink> {
+        let nul = data.iter().position(|b| *b == 0)?;
+        let build_id = &data[nul + 1..];
+        if build_id.is_empty() {
+            return None;
+        }
+
+        Some(DebugAltLink::new(
+            String::from_utf8_lossy(&data[..nul]),
+            build_id,
+        ))
+    }
+
+    /// The path to the supplementary debug file.
+    ///
+    /// This is usually an absolute path, but it may be relative to the debug file.
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// The raw build identifier of the supplementary debug file.
+    pub fn build_id(&self) -> &[u8] {
+        &self.build_id
+    }
+}
+
+/// Computes the CRC32 checksum used by `.gnu_debuglink`.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc::new();
+    crc.update(data);
+    crc.sum()
+}
+
+/// Formats a build identifier as lowercase hex string.
+fn format_build_id(build_id: &[u8]) -> String {
+    let mut hex = String::with_capacity(build_id.len() * 2);
+    for byte in build_id {
+        write!(hex, "{:02x}", byte).ok();
+    }
+    hex
+}
+
+/// Locates separate debug files for stripped ELF objects.
+///
+/// The resolver implements the search order of GDB for separate debug files:
+///
+///  1. `<debug-dir>/.build-id/xx/yyyy.debug` for every global debug directory, where `xxyyyy` is
+///     the hex encoded GNU build identifier of the object.
+///  2. `<exe-dir>/<debuglink>`, next to the object.
+///  3. `<exe-dir>/.debug/<debuglink>`
+///  4. `<debug-dir>/<exe-dir>/<debuglink>` for every global debug directory.
+///
+/// Candidates are only accepted if their CRC32 matches the `.gnu_debuglink` checksum and their
+/// build identifier matches the one of the object, whichever of the two are available.
+#[derive(Clone, Debug)]
+pub struct DebugFileResolver {
+    debug_dirs: Vec<PathBuf>,
+}
+
+impl Default for DebugFileResolver {
+    fn default() -> DebugFileResolver {
+        DebugFileResolver::with_debug_dirs(&[DEFAULT_DEBUG_DIR])
+    }
+}
+
+impl DebugFileResolver {
+    /// Creates a resolver searching the default global debug directory `/usr/lib/debug`.
+    pub fn new() -> DebugFileResolver {
+        DebugFileResolver::default()
+    }
+
+    /// Creates a resolver searching the given global debug directories.
+    pub fn with_debug_dirs<I>(dirs: I) -> DebugFileResolver
+    where
+        I: IntoIterator,
+        I::Item: AsRef<Path>,
+    {
+        DebugFileResolver {
+            debug_dirs: dirs.into_iter().map(|p| p.as_ref().to_path_buf()).collect(),
+        }
+    }
+
+    /// Returns the global debug directories searched by this resolver.
+    pub fn debug_dirs(&self) -> &[PathBuf] {
+        &self.debug_dirs
+    }
+
+    /// Returns all candidate paths for the debug file of an object in search order.
+    ///
+    /// The `path` is the location of the object on the file system. It is used to resolve the
+    /// file name of the `.gnu_debuglink` section. The candidates are not checked for existence.
+    pub fn candidates<P: AsRef<Path>>(&self, object: &Object<'_>, path: P) -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+
+        if let Some(build_id) = object.build_id() {
+            if build_id.len() > 1 {
+                let hex = format_build_id(build_id);
+                for dir in &self.debug_dirs {
+                    let mut candidate = dir.join(".build-id").join(&hex[..2]);
+                    candidate.push(format!("{}.debug", &hex[2..]));
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        if let Some(link) = object.debug_link() {
+            let exe_dir = path.as_ref().parent().unwrap_or_else(|| Path::new(""));
+            candidates.push(exe_dir.join(link.filename()));
+            candidates.push(exe_dir.join(".debug").join(link.filename()));
+
+            // Global debug directories mirror the absolute directory of the object.
+            let relative_dir = exe_dir.strip_prefix("/").unwrap_or(exe_dir);
+            for dir in &self.debug_dirs {
+                candidates.push(dir.join(relative_dir).join(link.filename()));
+            }
+        }
+
+        candidates
+    }
+
+    /// Checks whether the file at the given path is a valid debug file for the object.
+    pub fn verify<P: AsRef<Path>>(&self, object: &Object<'_>, candidate: P) -> bool {
+        let buffer = match ByteView::from_path(candidate) {
+            Ok(buffer) => buffer,
+            Err(_) => return false,
+        };
+
+        if let Some(link) = object.debug_link() {
+            if !link.matches(&buffer) {
+                return false;
+            }
+        }
+
+        if let Some(build_id) = object.build_id() {
+            let fat = match FatObject::parse(buffer) {
+                Ok(fat) => fat,
+                Err(_) => return false,
+            };
+
+            match fat.get_object(0) {
+                Ok(Some(debug_object)) => {
+                    if debug_object.build_id() != Some(build_id) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Searches for the debug file of an object located at the given path.
+    ///
+    /// Returns the path of the first candidate that exists and passes verification, or `None` if
+    /// no debug file could be found.
+    pub fn resolve<P: AsRef<Path>>(&self, object: &Object<'_>, path: P) -> Option<PathBuf> {
+        self.candidates(object, path)
+            .into_iter()
+            .find(|candidate| candidate.is_file() && self.verify(object, candidate))
+    }
+}
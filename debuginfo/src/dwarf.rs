@@ -46,7 +46,11 @@ impl<'input> DwarfData for Object<'input> {
     fn has_dwarf_section(&self, section: DwarfSection) -> bool {
         match self.target {
             ObjectTarget::Elf(ref elf) => {
-                has_elf_section(elf, elf::section_header::SHT_PROGBITS, section.elf_name())
+                let sh_type = elf::section_header::SHT_PROGBITS;
+                has_elf_section(elf, sh_type, section.elf_name())
+                    || section
+                        .elf_dwo_name()
+                        .map_or(false, |name| has_elf_section(elf, sh_type, name))
             }
             ObjectTarget::MachOSingle(ref macho) => has_mach_section(macho, section.macho_name()),
             ObjectTarget::MachOFat(_, ref macho) => has_mach_section(macho, section.macho_name()),
@@ -72,13 +76,19 @@ pub enum DwarfSection {
     DebugAbbrev,
     DebugAranges,
     DebugLine,
+    DebugLineStr,
     DebugLoc,
+    DebugLocLists,
     DebugPubNames,
     DebugRanges,
     DebugRngLists,
     DebugStr,
     DebugInfo,
     DebugTypes,
+    DebugAddr,
+    DebugStrOffsets,
+    DebugCuIndex,
+    DebugTuIndex,
 }
 
 impl DwarfSection {
@@ -90,13 +100,19 @@ impl DwarfSection {
             DwarfSection::DebugAbbrev => ".debug_abbrev",
             DwarfSection::DebugAranges => ".debug_aranges",
             DwarfSection::DebugLine => ".debug_line",
+            DwarfSection::DebugLineStr => ".debug_line_str",
             DwarfSection::DebugLoc => ".debug_loc",
+            DwarfSection::DebugLocLists => ".debug_loclists",
             DwarfSection::DebugPubNames => ".debug_pubnames",
             DwarfSection::DebugRanges => ".debug_ranges",
             DwarfSection::DebugRngLists => ".debug_rnglists",
             DwarfSection::DebugStr => ".debug_str",
             DwarfSection::DebugInfo => ".debug_info",
             DwarfSection::DebugTypes => ".debug_types",
+            DwarfSection::DebugAddr => ".debug_addr",
+            DwarfSection::DebugStrOffsets => ".debug_str_offsets",
+            DwarfSection::DebugCuIndex => ".debug_cu_index",
+            DwarfSection::DebugTuIndex => ".debug_tu_index",
         }
     }
 
@@ -108,13 +124,19 @@ impl DwarfSection {
             DwarfSection::DebugAbbrev => "__debug_abbrev",
             DwarfSection::DebugAranges => "__debug_aranges",
             DwarfSection::DebugLine => "__debug_line",
+            DwarfSection::DebugLineStr => "__debug_line_str",
             DwarfSection::DebugLoc => "__debug_loc",
+            DwarfSection::DebugLocLists => "__debug_loclists",
             DwarfSection::DebugPubNames => "__debug_pubnames",
             DwarfSection::DebugRanges => "__debug_ranges",
             DwarfSection::DebugRngLists => "__debug_rnglists",
             DwarfSection::DebugStr => "__debug_str",
             DwarfSection::DebugInfo => "__debug_info",
             DwarfSection::DebugTypes => "__debug_types",
+            DwarfSection::DebugAddr => "__debug_addr",
+            DwarfSection::DebugStrOffsets => "__debug_str_offs",
+            DwarfSection::DebugCuIndex => "__debug_cu_index",
+            DwarfSection::DebugTuIndex => "__debug_tu_index",
         }
     }
 
@@ -126,15 +148,41 @@ impl DwarfSection {
             DwarfSection::DebugAbbrev => "debug_abbrev",
             DwarfSection::DebugAranges => "debug_aranges",
             DwarfSection::DebugLine => "debug_line",
+            DwarfSection::DebugLineStr => "debug_line_str",
             DwarfSection::DebugLoc => "debug_loc",
+            DwarfSection::DebugLocLists => "debug_loclists",
             DwarfSection::DebugPubNames => "debug_pubnames",
             DwarfSection::DebugRanges => "debug_ranges",
             DwarfSection::DebugRngLists => "debug_rnglists",
             DwarfSection::DebugStr => "debug_str",
             DwarfSection::DebugInfo => "debug_info",
             DwarfSection::DebugTypes => "debug_types",
+            DwarfSection::DebugAddr => "debug_addr",
+            DwarfSection::DebugStrOffsets => "debug_str_offsets",
+            DwarfSection::DebugCuIndex => "debug_cu_index",
+            DwarfSection::DebugTuIndex => "debug_tu_index",
         }
     }
+
+    /// Return the name of the split DWARF (`.dwo`) variant for ELF.
+    ///
+    /// Split DWARF objects and packages store their sections with a `.dwo` suffix. Sections
+    /// that always remain in the main executable, such as `.debug_addr`, do not have a split
+    /// variant.
+    pub fn elf_dwo_name(self) -> Option<&'static str> {
+        Some(match self {
+            DwarfSection::DebugAbbrev => ".debug_abbrev.dwo",
+            DwarfSection::DebugLine => ".debug_line.dwo",
+            DwarfSection::DebugLoc => ".debug_loc.dwo",
+            DwarfSection::DebugLocLists => ".debug_loclists.dwo",
+            DwarfSection::DebugRngLists => ".debug_rnglists.dwo",
+            DwarfSection::DebugStr => ".debug_str.dwo",
+            DwarfSection::DebugInfo => ".debug_info.dwo",
+            DwarfSection::DebugTypes => ".debug_types.dwo",
+            DwarfSection::DebugStrOffsets => ".debug_str_offsets.dwo",
+            _ => return None,
+        })
+    }
 }
 
 impl std::fmt::Display for DwarfSection {
@@ -190,6 +238,10 @@ fn read_elf_dwarf_section<'data>(
 ) -> Option<DwarfSectionData<'data>> {
     let sh_type = elf::section_header::SHT_PROGBITS;
     find_elf_section(elf, data, sh_type, sect.elf_name())
+        .or_else(|| {
+            let name = sect.elf_dwo_name()?;
+            find_elf_section(elf, data, sh_type, name)
+        })
         .map(|section| DwarfSectionData::new(sect, section.data, section.header.sh_offset))
 }
 
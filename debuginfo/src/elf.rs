@@ -8,6 +8,8 @@ use uuid::Uuid;
 
 use symbolic_common::types::DebugId;
 
+use crate::debuglink::{DebugAltLink, DebugLink};
+
 const UUID_SIZE: usize = 16;
 const PAGE_SIZE: usize = 4096;
 
@@ -109,7 +111,7 @@ pub fn has_elf_section(elf: &elf::Elf<'_>, sh_type: u32, name: &str) -> bool {
 /// Depending on the compiler and linker, the build ID can be declared in a
 /// PT_NOTE program header entry, the ".note.gnu.build-id" section, or even
 /// both.
-fn find_build_id<'data>(elf: &elf::Elf<'data>, data: &'data [u8]) -> Option<&'data [u8]> {
+pub fn find_build_id<'data>(elf: &elf::Elf<'data>, data: &'data [u8]) -> Option<&'data [u8]> {
     // First, search the note program headers (PT_NOTE) for a NT_GNU_BUILD_ID.
     // We swallow all errors during this process and simply fall back to the
     // next method below.
@@ -210,3 +212,27 @@ pub fn get_elf_vmaddr(elf: &elf::Elf<'_>) -> u64 {
 
     0
 }
+
+/// Reads the separate debug file reference from the `.gnu_debuglink` section.
+pub fn get_elf_debug_link(elf: &elf::Elf<'_>, data: &[u8]) -> Option<DebugLink> {
+    let section = find_elf_section(
+        elf,
+        data,
+        elf::section_header::SHT_PROGBITS,
+        ".gnu_debuglink",
+    )?;
+
+    DebugLink::parse(&section.data, elf.little_endian)
+}
+
+/// Reads the supplementary debug file reference from the `.gnu_debugaltlink` section.
+pub fn get_elf_debug_alt_link(elf: &elf::Elf<'_>, data: &[u8]) -> Option<DebugAltLink> {
+    let section = find_elf_section(
+        elf,
+        data,
+        elf::section_header::SHT_PROGBITS,
+        ".gnu_debugaltlink",
+    )?;
+
+    DebugAltLink::parse(&section.data)
+}
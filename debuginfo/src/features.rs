@@ -3,6 +3,7 @@ use std::fmt;
 
 use crate::dwarf::{DwarfData, DwarfSection};
 use crate::object::{Object, ObjectTarget};
+use crate::pe::has_pe_unwind_info;
 use crate::symbols::SymbolTable;
 
 fn has_dwarf_unwind_info(object: &Object<'_>) -> bool {
@@ -91,6 +92,9 @@ impl<'a> DebugFeatures for Object<'a> {
             ObjectTarget::MachOSingle(..) => self.has_dwarf_data(),
             ObjectTarget::MachOFat(..) => self.has_dwarf_data(),
             ObjectTarget::Breakpad(..) => has_breakpad_record(self, b"FUNC"),
+            // Debug information for PE files is stored in separate PDBs
+            ObjectTarget::Pe(..) => false,
+            ObjectTarget::Pdb(pdb) => pdb.has_debug_info(),
         }
     }
 
@@ -100,6 +104,8 @@ impl<'a> DebugFeatures for Object<'a> {
             ObjectTarget::MachOSingle(..) => has_dwarf_unwind_info(self),
             ObjectTarget::MachOFat(..) => has_dwarf_unwind_info(self),
             ObjectTarget::Breakpad(..) => has_breakpad_record(self, b"STACK"),
+            ObjectTarget::Pe(pe) => has_pe_unwind_info(pe),
+            ObjectTarget::Pdb(pdb) => pdb.has_unwind_info(),
         }
     }
 
@@ -1,14 +1,18 @@
 //! Abstraction for reading debug info files.
 
 mod breakpad;
+mod debuglink;
 mod dwarf;
 mod elf;
 mod features;
 mod mach;
 mod object;
+mod pdb;
+mod pe;
 mod symbols;
 
 pub use crate::breakpad::*;
+pub use crate::debuglink::*;
 pub use crate::dwarf::*;
 pub use crate::features::*;
 pub use crate::object::*;
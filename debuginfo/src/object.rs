@@ -2,15 +2,20 @@ use std::fmt;
 use std::io::Cursor;
 
 use failure::{Backtrace, Context, Fail, ResultExt};
-use goblin::{elf, mach, Hint};
+use goblin::{elf, mach, pe, Hint};
 
 use symbolic_common::byteview::{ByteView, ByteViewHandle};
 use symbolic_common::types::{Arch, DebugId, DebugKind, Endianness, ObjectClass, ObjectKind};
 
 use crate::breakpad::BreakpadSym;
+use crate::debuglink::{DebugAltLink, DebugLink};
 use crate::dwarf::DwarfData;
-use crate::elf::{get_elf_id, get_elf_vmaddr};
+use crate::elf::{
+    find_build_id, get_elf_debug_alt_link, get_elf_debug_link, get_elf_id, get_elf_vmaddr,
+};
 use crate::mach::{get_mach_id, get_mach_vmaddr};
+use crate::pdb::{is_pdb, PdbFile};
+use crate::pe::{get_pe_id, get_pe_vmaddr};
 
 /// Contains type specific data of `Object`s.
 #[allow(clippy::large_enum_variant)]
@@ -19,6 +24,8 @@ pub(crate) enum ObjectTarget<'bytes> {
     Elf(&'bytes elf::Elf<'bytes>),
     MachOSingle(&'bytes mach::MachO<'bytes>),
     MachOFat(mach::fat::FatArch, mach::MachO<'bytes>),
+    Pe(&'bytes pe::PE<'bytes>),
+    Pdb(&'bytes PdbFile),
 }
 
 /// The kind of an `ObjectError`.
@@ -95,6 +102,36 @@ impl<'bytes> Object<'bytes> {
             ObjectTarget::Elf(ref elf) => get_elf_id(elf, self.fat_bytes),
             ObjectTarget::MachOSingle(macho) => get_mach_id(macho),
             ObjectTarget::MachOFat(_, ref macho) => get_mach_id(macho),
+            ObjectTarget::Pe(pe) => get_pe_id(pe),
+            ObjectTarget::Pdb(pdb) => pdb.id(),
+        }
+    }
+
+    /// Returns the raw GNU build identifier of an ELF object.
+    ///
+    /// As opposed to `id`, this is the unmodified content of the `NT_GNU_BUILD_ID` note, which
+    /// is used to locate separate debug files. Returns `None` for other object kinds or if the
+    /// object does not contain a build identifier.
+    pub fn build_id(&self) -> Option<&'bytes [u8]> {
+        match self.target {
+            ObjectTarget::Elf(elf) => find_build_id(elf, self.fat_bytes),
+            _ => None,
+        }
+    }
+
+    /// Returns the reference to a separate debug file declared in `.gnu_debuglink`.
+    pub fn debug_link(&self) -> Option<DebugLink> {
+        match self.target {
+            ObjectTarget::Elf(elf) => get_elf_debug_link(elf, self.fat_bytes),
+            _ => None,
+        }
+    }
+
+    /// Returns the reference to a supplementary debug file declared in `.gnu_debugaltlink`.
+    pub fn debug_alt_link(&self) -> Option<DebugAltLink> {
+        match self.target {
+            ObjectTarget::Elf(elf) => get_elf_debug_alt_link(elf, self.fat_bytes),
+            _ => None,
         }
     }
 
@@ -105,6 +142,8 @@ impl<'bytes> Object<'bytes> {
             ObjectTarget::Elf(..) => ObjectKind::Elf,
             ObjectTarget::MachOSingle(..) => ObjectKind::MachO,
             ObjectTarget::MachOFat(..) => ObjectKind::MachO,
+            ObjectTarget::Pe(..) => ObjectKind::Pe,
+            ObjectTarget::Pdb(..) => ObjectKind::Pdb,
         }
     }
 
@@ -123,6 +162,9 @@ impl<'bytes> Object<'bytes> {
                 Arch::from_mach(mach.header.cputype(), mach.header.cpusubtype())
                     .context(ObjectErrorKind::UnsupportedObject)?
             }
+            ObjectTarget::Pe(pe) => Arch::from_pe(pe.header.coff_header.machine)
+                .context(ObjectErrorKind::UnsupportedObject)?,
+            ObjectTarget::Pdb(pdb) => pdb.arch(),
         })
     }
 
@@ -134,6 +176,9 @@ impl<'bytes> Object<'bytes> {
             ObjectTarget::Elf(elf) => get_elf_vmaddr(elf),
             ObjectTarget::MachOSingle(macho) => get_mach_vmaddr(macho),
             ObjectTarget::MachOFat(_, ref macho) => get_mach_vmaddr(macho),
+            ObjectTarget::Pe(pe) => get_pe_vmaddr(pe),
+            // PDBs declare all addresses relative to the image base
+            ObjectTarget::Pdb(..) => 0,
         }
     }
 
@@ -144,6 +189,9 @@ impl<'bytes> Object<'bytes> {
             ObjectTarget::Elf(ref elf) => elf.little_endian,
             ObjectTarget::MachOSingle(macho) => macho.little_endian,
             ObjectTarget::MachOFat(_, ref macho) => macho.little_endian,
+            // PE files are always little endian
+            ObjectTarget::Pe(..) => true,
+            ObjectTarget::Pdb(..) => true,
         };
 
         if little {
@@ -159,6 +207,8 @@ impl<'bytes> Object<'bytes> {
             ObjectTarget::Breakpad(..) => self.fat_bytes,
             ObjectTarget::Elf(..) => self.fat_bytes,
             ObjectTarget::MachOSingle(_) => self.fat_bytes,
+            ObjectTarget::Pe(..) => self.fat_bytes,
+            ObjectTarget::Pdb(..) => self.fat_bytes,
             ObjectTarget::MachOFat(ref arch, _) => {
                 let bytes = self.fat_bytes;
                 &bytes[arch.offset as usize..(arch.offset + arch.size) as usize]
@@ -175,6 +225,8 @@ impl<'bytes> Object<'bytes> {
             }
             ObjectTarget::MachOSingle(macho) => ObjectClass::from_mach(macho.header.filetype),
             ObjectTarget::MachOFat(_, ref macho) => ObjectClass::from_mach(macho.header.filetype),
+            ObjectTarget::Pe(pe) => ObjectClass::from_pe(pe.header.coff_header.characteristics),
+            ObjectTarget::Pdb(..) => ObjectClass::Debug,
         }
     }
 
@@ -182,6 +234,7 @@ impl<'bytes> Object<'bytes> {
     pub fn debug_kind(&self) -> Option<DebugKind> {
         match self.target {
             ObjectTarget::Breakpad(..) => Some(DebugKind::Breakpad),
+            ObjectTarget::Pdb(pdb) if pdb.has_debug_info() => Some(DebugKind::Pdb),
             ObjectTarget::Elf(..) | ObjectTarget::MachOSingle(..) | ObjectTarget::MachOFat(..)
                 if self.has_dwarf_data() =>
             {
@@ -251,6 +304,8 @@ pub(crate) enum FatObjectKind<'bytes> {
     Breakpad(BreakpadSym),
     Elf(elf::Elf<'bytes>),
     MachO(mach::Mach<'bytes>),
+    Pe(pe::PE<'bytes>),
+    Pdb(PdbFile),
 }
 
 /// Represents a potentially fat object containing one or more objects.
@@ -271,6 +326,7 @@ impl<'bytes> FatObject<'bytes> {
             Hint::Elf(_) => return Ok(Some(ObjectKind::Elf)),
             Hint::Mach(_) => return Ok(Some(ObjectKind::MachO)),
             Hint::MachFat(_) => return Ok(Some(ObjectKind::MachO)),
+            Hint::PE => return Ok(Some(ObjectKind::Pe)),
             _ => (),
         };
 
@@ -278,6 +334,10 @@ impl<'bytes> FatObject<'bytes> {
             return Ok(Some(ObjectKind::Breakpad));
         }
 
+        if is_pdb(bytes) {
+            return Ok(Some(ObjectKind::Pdb));
+        }
+
         Ok(None)
     }
 
@@ -293,6 +353,14 @@ impl<'bytes> FatObject<'bytes> {
                     let inner = mach::Mach::parse(bytes).context(ObjectErrorKind::BadObject)?;
                     FatObjectKind::MachO(inner)
                 }
+                Some(ObjectKind::Pe) => {
+                    let inner = pe::PE::parse(bytes).context(ObjectErrorKind::BadObject)?;
+                    FatObjectKind::Pe(inner)
+                }
+                Some(ObjectKind::Pdb) => {
+                    let inner = PdbFile::parse(bytes).context(ObjectErrorKind::BadObject)?;
+                    FatObjectKind::Pdb(inner)
+                }
                 Some(ObjectKind::Breakpad) => {
                     let inner = BreakpadSym::parse(bytes).context(ObjectErrorKind::BadObject)?;
                     FatObjectKind::Breakpad(inner)
@@ -310,6 +378,8 @@ impl<'bytes> FatObject<'bytes> {
             FatObjectKind::Breakpad(_) => ObjectKind::Breakpad,
             FatObjectKind::Elf(..) => ObjectKind::Elf,
             FatObjectKind::MachO(..) => ObjectKind::MachO,
+            FatObjectKind::Pe(..) => ObjectKind::Pe,
+            FatObjectKind::Pdb(..) => ObjectKind::Pdb,
         }
     }
 
@@ -323,6 +393,8 @@ impl<'bytes> FatObject<'bytes> {
         match *self.handle {
             FatObjectKind::Breakpad(_) => 1,
             FatObjectKind::Elf(..) => 1,
+            FatObjectKind::Pe(..) => 1,
+            FatObjectKind::Pdb(..) => 1,
             FatObjectKind::MachO(ref mach) => match *mach {
                 mach::Mach::Fat(ref fat) => fat.narches,
                 mach::Mach::Binary(..) => 1,
@@ -339,6 +411,8 @@ impl<'bytes> FatObject<'bytes> {
         let target = match *self.handle {
             FatObjectKind::Breakpad(ref breakpad) => ObjectTarget::Breakpad(breakpad),
             FatObjectKind::Elf(ref elf) => ObjectTarget::Elf(elf),
+            FatObjectKind::Pe(ref pe) => ObjectTarget::Pe(pe),
+            FatObjectKind::Pdb(ref pdb) => ObjectTarget::Pdb(pdb),
             FatObjectKind::MachO(ref mach) => match *mach {
                 mach::Mach::Binary(ref bin) => ObjectTarget::MachOSingle(bin),
                 mach::Mach::Fat(ref fat) => {
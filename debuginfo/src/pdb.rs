@@ -0,0 +1,93 @@
+use std::io::Cursor;
+
+use ::pdb::{FallibleIterator, MachineType, PDB};
+use uuid::Uuid;
+
+use symbolic_common::types::{Arch, DebugId};
+
+/// Magic bytes at the start of every PDB 7.0 file (the MSF container header).
+const PDB_MAGIC: &[u8] = b"Microsoft C/C++ MSF 7.00\r\n\x1a\x44\x53\x00\x00\x00";
+
+/// Checks whether the given bytes look like a Microsoft PDB file.
+pub fn is_pdb(data: &[u8]) -> bool {
+    data.starts_with(PDB_MAGIC)
+}
+
+/// Opens the PDB contained in the given buffer.
+pub fn open_pdb(data: &[u8]) -> Result<PDB<'_, Cursor<&[u8]>>, ::pdb::Error> {
+    PDB::open(Cursor::new(data))
+}
+
+/// Summary information on a PDB file.
+///
+/// The PDB format is an MSF container consisting of many individual streams. Reading them requires
+/// mutable access to the file, which is why the information required by `Object` is read eagerly
+/// while parsing. Debug information is read from the streams directly by its consumers.
+pub(crate) struct PdbFile {
+    id: Option<DebugId>,
+    arch: Arch,
+    has_debug_info: bool,
+    has_unwind_info: bool,
+}
+
+impl PdbFile {
+    /// Parses the PDB headers from the given buffer.
+    pub fn parse(data: &[u8]) -> Result<PdbFile, ::pdb::Error> {
+        let mut pdb = open_pdb(data)?;
+        let info = pdb.pdb_information()?;
+        let dbi = pdb.debug_information()?;
+
+        // The DBI stream contains the original age written by the linker, which matches the age
+        // in the PE's CodeView record. The age in the PDBI stream might have been bumped by other
+        // tools and is only used as fallback.
+        let age = dbi.age().unwrap_or(info.age);
+        let id = Uuid::from_slice(info.guid.as_bytes())
+            .ok()
+            .map(|uuid| DebugId::from_parts(uuid, age));
+
+        let arch = match dbi.machine_type()? {
+            MachineType::X86 => Arch::X86,
+            MachineType::Amd64 => Arch::X86_64,
+            MachineType::Arm | MachineType::ArmNT => Arch::Arm,
+            MachineType::Arm64 => Arch::Arm64,
+            MachineType::PowerPC | MachineType::PowerPCFP => Arch::Ppc,
+            _ => Arch::Unknown,
+        };
+
+        let has_debug_info = dbi.modules()?.count()? > 0;
+
+        // Frame data (FPO) is only written for 32-bit x86. Other architectures store unwind
+        // information in the executable.
+        let has_unwind_info = match pdb.frame_table() {
+            Ok(table) => table.iter().next()?.is_some(),
+            Err(_) => false,
+        };
+
+        Ok(PdbFile {
+            id,
+            arch,
+            has_debug_info,
+            has_unwind_info,
+        })
+    }
+
+    /// The code identifier of this PDB, composed of its GUID and age.
+    pub fn id(&self) -> Option<DebugId> {
+        self.id
+    }
+
+    /// The CPU architecture of the linked image.
+    pub fn arch(&self) -> Arch {
+        self.arch
+    }
+
+    /// Indicates whether this PDB contains any modules with debug information.
+    pub fn has_debug_info(&self) -> bool {
+        self.has_debug_info
+    }
+
+    /// Indicates whether this PDB contains frame data for stack unwinding.
+    pub fn has_unwind_info(&self) -> bool {
+        self.has_unwind_info
+    }
+}
@@ -0,0 +1,37 @@
+use goblin::pe;
+
+use symbolic_common::types::DebugId;
+
+/// Checks whether a PE object file contains unwind information.
+///
+/// On x86_64, ARM and ARM64, the exception directory (`IMAGE_DIRECTORY_ENTRY_EXCEPTION`) holds the
+/// function table, which is usually stored in `.pdata` and points to unwind codes in `.xdata`.
+/// Linkers may merge these sections into `.rdata`, so the data directory in the optional header
+/// is checked rather than section names. 32-bit x86 does not use table-based unwinding; its frame
+/// data lives in the PDB.
+pub fn has_pe_unwind_info(pe: &pe::PE<'_>) -> bool {
+    let exception_table = pe
+        .header
+        .optional_header
+        .and_then(|header| *header.data_directories.get_exception_table());
+
+    matches!(exception_table, Some(table) if table.size > 0)
+}
+
+/// Tries to obtain the object identifier of a PE object.
+///
+/// The identifier is read from the CodeView record in the debug directory, which links the
+/// executable to its PDB. The PDB signature GUID becomes the UUID of the `DebugId`, and the PDB
+/// age is stored in its appendix. If the object was linked without debug information, the
+/// identifier is `None`.
+pub fn get_pe_id(pe: &pe::PE<'_>) -> Option<DebugId> {
+    let debug_info = pe.debug_data.as_ref()?.codeview_pdb70_debug_info.as_ref()?;
+    DebugId::from_guid_age(&debug_info.signature, debug_info.age).ok()
+}
+
+/// Gets the preferred load address of the image.
+///
+/// All addresses in PE objects are relative to this image base.
+pub fn get_pe_vmaddr(pe: &pe::PE<'_>) -> u64 {
+    pe.image_base as u64
+}
@@ -4,12 +4,13 @@ use std::iter::{IntoIterator, Peekable};
 use std::slice;
 
 use failure::ResultExt;
-use goblin::mach;
+use goblin::{elf, mach, pe};
 use lazy_static::lazy_static;
 use regex::Regex;
 
 use symbolic_common::types::Name;
 
+use crate::elf::has_elf_section;
 use crate::object::{Object, ObjectError, ObjectErrorKind, ObjectTarget};
 
 lazy_static! {
@@ -73,6 +74,8 @@ impl<'data> Into<String> for Symbol<'data> {
 #[derive(Clone, Debug)]
 enum SymbolsInternal<'data> {
     MachO(&'data mach::symbols::Symbols<'data>),
+    Elf(&'data elf::Elf<'data>, bool),
+    Pe(&'data pe::PE<'data>),
 }
 
 impl<'data> SymbolsInternal<'data> {
@@ -107,10 +110,77 @@ impl<'data> SymbolsInternal<'data> {
                     len,
                 }
             }
+            SymbolsInternal::Elf(elf, dynamic) => {
+                let (sym, name) = match get_elf_symbol(elf, dynamic, index) {
+                    Some((sym, strtab)) => {
+                        let name = strtab
+                            .get(sym.st_name)
+                            .ok_or(ObjectErrorKind::BadObject)?
+                            .context(ObjectErrorKind::BadObject)?;
+                        (sym, name)
+                    }
+                    None => return Ok(None),
+                };
+
+                // ELF symbols carry their own size. Only if it is missing, fall back to the
+                // address of the next symbol, just like for MachO.
+                let addr = sym.st_value;
+                let len = if sym.st_size != 0 {
+                    Some(sym.st_size)
+                } else {
+                    next.and_then(|index| get_elf_symbol(elf, dynamic, index))
+                        .map(|(next, _)| next.st_value - addr)
+                };
+
+                Symbol {
+                    name: Cow::Borrowed(name),
+                    addr,
+                    len,
+                }
+            }
+            SymbolsInternal::Pe(pe) => {
+                let export = match pe.exports.get(index) {
+                    Some(export) => export,
+                    None => return Ok(None),
+                };
+
+                // Exports are declared relative to the image base. Convert them to absolute
+                // addresses to match the other symbol table implementations.
+                let addr = pe.image_base as u64 + export.rva as u64;
+                let len = next
+                    .and_then(|index| pe.exports.get(index))
+                    .map(|next| (next.rva - export.rva) as u64);
+
+                Symbol {
+                    name: Cow::Borrowed(export.name.unwrap_or_default()),
+                    addr,
+                    len,
+                }
+            }
         }))
     }
 }
 
+/// Resolves a symbol index in the concatenation of `.symtab` and `.dynsym`.
+///
+/// Returns the symbol along with the string table that contains its name. Symbols in `.dynsym`
+/// are only considered if `dynamic` is set, since debug companion files retain phantom dynamic
+/// sections without data.
+fn get_elf_symbol<'data>(
+    elf: &'data elf::Elf<'data>,
+    dynamic: bool,
+    index: usize,
+) -> Option<(elf::Sym, &'data goblin::strtab::Strtab<'data>)> {
+    if index < elf.syms.len() {
+        elf.syms.get(index).map(|sym| (sym, &elf.strtab))
+    } else if dynamic {
+        let index = index - elf.syms.len();
+        elf.dynsyms.get(index).map(|sym| (sym, &elf.dynstrtab))
+    } else {
+        None
+    }
+}
+
 /// Internal type used to map addresses to symbol indices.
 ///
 ///  - `mapping.0`: The address of a symbol
@@ -208,6 +278,65 @@ impl<'data> Symbols<'data> {
         }))
     }
 
+    /// Creates a `Symbols` wrapper for ELF.
+    ///
+    /// This reads function symbols from both `.symtab` and `.dynsym`. If a symbol is declared in
+    /// both tables, the entry from `.symtab` takes precedence.
+    fn from_elf(elf: &'data elf::Elf<'data>) -> Result<Option<Symbols<'data>>, ObjectError> {
+        let dynamic = has_elf_section(elf, elf::section_header::SHT_DYNSYM, ".dynsym");
+        if elf.syms.len() == 0 && !dynamic {
+            return Ok(None);
+        }
+
+        let dynsyms = if dynamic {
+            Some(elf.dynsyms.iter())
+        } else {
+            None
+        };
+
+        // Build an ordered map of only function symbols. Since `.symtab` is iterated first, its
+        // entries win over `.dynsym` when deduplicating by address.
+        let mut symbol_map = BTreeMap::new();
+        let symbols = elf.syms.iter().chain(dynsyms.into_iter().flatten());
+        for (symbol_index, sym) in symbols.enumerate() {
+            let is_defined_function = sym.st_type() == elf::sym::STT_FUNC
+                && sym.st_value != 0
+                && sym.st_shndx != elf::section_header::SHN_UNDEF as usize;
+
+            if is_defined_function {
+                symbol_map.entry(sym.st_value).or_insert(symbol_index);
+            }
+        }
+
+        Ok(Some(Symbols {
+            internal: SymbolsInternal::Elf(elf, dynamic),
+            mappings: symbol_map.into_iter().collect(),
+        }))
+    }
+
+    /// Creates a `Symbols` wrapper for PE.
+    ///
+    /// PE files only declare exported symbols. Forwarded exports and exports without a name are
+    /// skipped, since they do not point to code in this image.
+    fn from_pe(pe: &'data pe::PE<'data>) -> Result<Option<Symbols<'data>>, ObjectError> {
+        if pe.export_data.is_none() {
+            return Ok(None);
+        }
+
+        let mut symbol_map = BTreeMap::new();
+        for (symbol_index, export) in pe.exports.iter().enumerate() {
+            if export.name.is_some() && export.reexport.is_none() && export.rva != 0 {
+                let addr = pe.image_base as u64 + export.rva as u64;
+                symbol_map.entry(addr).or_insert(symbol_index);
+            }
+        }
+
+        Ok(Some(Symbols {
+            internal: SymbolsInternal::Pe(pe),
+            mappings: symbol_map.into_iter().collect(),
+        }))
+    }
+
     /// Searches for a single `Symbol` inside the symbol table.
     pub fn lookup(&self, addr: u64) -> Result<Option<Symbol<'data>>, ObjectError> {
         let found = match self.mappings.binary_search_by_key(&addr, |&x| x.0) {
@@ -229,6 +358,7 @@ impl<'data> Symbols<'data> {
         // Hidden symbols can only ever occur in Apple's dSYM
         match self.internal {
             SymbolsInternal::MachO(..) => (),
+            SymbolsInternal::Elf(..) | SymbolsInternal::Pe(..) => return false,
         };
 
         for symbol in self.iter() {
@@ -269,9 +399,14 @@ impl<'data> SymbolTable for Object<'data> {
         match self.target {
             ObjectTarget::MachOSingle(macho) => macho.symbols.is_some(),
             ObjectTarget::MachOFat(_, ref macho) => macho.symbols.is_some(),
+            ObjectTarget::Elf(elf) => {
+                elf.syms.len() > 0
+                    || has_elf_section(elf, elf::section_header::SHT_DYNSYM, ".dynsym")
+            }
+            ObjectTarget::Pe(pe) => pe.export_data.is_some(),
             // We don't support symbols for these yet
-            ObjectTarget::Elf(..) => false,
             ObjectTarget::Breakpad(..) => false,
+            ObjectTarget::Pdb(..) => false,
         }
     }
 
@@ -279,6 +414,8 @@ impl<'data> SymbolTable for Object<'data> {
         match self.target {
             ObjectTarget::MachOSingle(macho) => Symbols::from_macho(macho),
             ObjectTarget::MachOFat(_, ref macho) => Symbols::from_macho(macho),
+            ObjectTarget::Elf(elf) => Symbols::from_elf(elf),
+            ObjectTarget::Pe(pe) => Symbols::from_pe(pe),
             _ => Err(ObjectErrorKind::UnsupportedSymbolTable.into()),
         }
     }
@@ -0,0 +1,130 @@
+use symbolic_common::byteview::ByteView;
+use symbolic_debuginfo::{
+    BreakpadData, BreakpadRecord, BreakpadStackRecords, BreakpadStackWinType, FatObject,
+};
+use symbolic_testutils::fixture_path;
+
+fn stack_lines(bytes: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .filter(|line| line.starts_with("STACK"))
+        .map(|line| line.trim_end().to_string())
+        .collect()
+}
+
+fn check_roundtrip(path: &str) {
+    let buffer = ByteView::from_path(fixture_path(path)).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+
+    let mut emitted = vec![];
+    for record in fat.breakpad_records() {
+        match record.expect("Could not parse record") {
+            BreakpadRecord::StackCfi(cfi) => emitted.push(cfi.to_string()),
+            BreakpadRecord::StackCfiDelta(delta) => emitted.push(delta.to_string()),
+            BreakpadRecord::StackWin(win) => emitted.push(win.to_string()),
+            _ => (),
+        }
+    }
+
+    assert_eq!(emitted, stack_lines(fat.as_bytes()));
+}
+
+#[test]
+fn test_stack_roundtrip_linux() {
+    check_roundtrip("linux/crash.sym");
+}
+
+#[test]
+fn test_stack_roundtrip_macos() {
+    check_roundtrip("macos/crash.sym");
+}
+
+#[test]
+fn test_stack_roundtrip_windows() {
+    check_roundtrip("windows/crash.sym");
+}
+
+#[test]
+fn test_stack_cfi_records() {
+    let buffer = ByteView::from_path(fixture_path("linux/crash.sym")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+
+    let records =
+        BreakpadStackRecords::from_records(fat.breakpad_records()).expect("Could not parse");
+    assert!(records.win_records().is_empty());
+    records.validate().expect("Invalid stack records");
+
+    let cfi = records
+        .cfi_records()
+        .iter()
+        .find(|cfi| cfi.address == 0x1580)
+        .expect("Missing CFI record");
+
+    assert_eq!(cfi.size, 0x370);
+    assert_eq!(cfi.rules.len(), 2);
+    assert_eq!(cfi.rules[0].register, b".cfa");
+    assert_eq!(cfi.rules[0].expression, b"$rsp 16 +");
+    assert_eq!(cfi.rules[1].register, b".ra");
+    assert_eq!(cfi.rules[1].expression, b".cfa -8 + ^");
+
+    let delta = &cfi.deltas[0];
+    assert_eq!(delta.address, 0x1586);
+    assert_eq!(delta.rules.len(), 1);
+    assert_eq!(delta.rules[0].expression, b"$rsp 24 +");
+}
+
+#[test]
+fn test_stack_win_records() {
+    let buffer =
+        ByteView::from_path(fixture_path("windows/crash.sym")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+
+    let records =
+        BreakpadStackRecords::from_records(fat.breakpad_records()).expect("Could not parse");
+    assert!(records.cfi_records().is_empty());
+    records.validate().expect("Invalid stack records");
+
+    let win = &records.win_records()[0];
+    assert_eq!(win.ty, BreakpadStackWinType::FrameData);
+    assert_eq!(win.rva, 0x1000);
+    assert_eq!(win.code_size, 0x114);
+    assert_eq!(win.prolog_size, 0x11);
+    assert_eq!(win.params_size, 0x8);
+    assert_eq!(win.locals_size, 0);
+    assert_eq!(
+        win.program_string,
+        Some(&b"$T0 .raSearch = $eip $T0 ^ = $esp $T0 4 + ="[..])
+    );
+}
+
+#[test]
+fn test_stack_merge_and_filter() {
+    let buffer = ByteView::from_path(fixture_path("linux/crash.sym")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+
+    let all = BreakpadStackRecords::from_records(fat.breakpad_records()).expect("Could not parse");
+    let count = all.cfi_records().len();
+
+    let mut lower = all.clone();
+    lower.retain_range(0, 0x2000);
+    let mut upper = all.clone();
+    upper.retain_range(0x2000, u64::MAX);
+    assert!(!lower.is_empty());
+    assert!(!upper.is_empty());
+    assert!(lower
+        .cfi_records()
+        .iter()
+        .all(|cfi| cfi.address < 0x2000 && cfi.end() > 0));
+
+    lower.merge(upper);
+    assert_eq!(lower.cfi_records().len(), count);
+    assert_eq!(lower, all);
+
+    // Merging records that are already present must not create duplicates.
+    lower.merge(all.clone());
+    assert_eq!(lower, all);
+    assert_eq!(
+        all.to_string().lines().count(),
+        stack_lines(fat.as_bytes()).len()
+    );
+}
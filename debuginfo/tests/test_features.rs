@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use symbolic_common::byteview::ByteView;
 use symbolic_debuginfo::{DebugFeatures, FatObject, ObjectFeature};
 use symbolic_testutils::fixture_path;
@@ -13,7 +15,10 @@ fn test_features_elf_bin() {
 
     assert_eq!(
         object.features(),
-        [ObjectFeature::UnwindInfo].iter().cloned().collect()
+        [ObjectFeature::SymbolTable, ObjectFeature::UnwindInfo]
+            .iter()
+            .cloned()
+            .collect()
     );
 }
 
@@ -29,7 +34,10 @@ fn test_features_elf_dbg() {
 
     assert_eq!(
         object.features(),
-        [ObjectFeature::DebugInfo].iter().cloned().collect()
+        [ObjectFeature::SymbolTable, ObjectFeature::DebugInfo]
+            .iter()
+            .cloned()
+            .collect()
     );
 }
 
@@ -89,3 +97,56 @@ fn test_features_breakpad() {
             .collect()
     );
 }
+
+#[test]
+fn test_features_pe_bin() {
+    let buffer =
+        ByteView::from_path(fixture_path("windows/crash.exe")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    // 32-bit PE files neither export symbols, nor contain unwind tables.
+    assert_eq!(object.features(), BTreeSet::new());
+}
+
+#[test]
+fn test_features_pe_dll() {
+    let buffer =
+        ByteView::from_path(fixture_path("windows/exports64.dll")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    // 64-bit PE files export symbols and contain unwind tables in the exception directory.
+    assert_eq!(
+        object.features(),
+        [ObjectFeature::SymbolTable, ObjectFeature::UnwindInfo]
+            .iter()
+            .cloned()
+            .collect()
+    );
+}
+
+#[test]
+fn test_features_pdb() {
+    let buffer =
+        ByteView::from_path(fixture_path("windows/crash.pdb")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    assert_eq!(
+        object.features(),
+        [ObjectFeature::DebugInfo, ObjectFeature::UnwindInfo]
+            .iter()
+            .cloned()
+            .collect()
+    );
+}
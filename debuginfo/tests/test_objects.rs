@@ -0,0 +1,159 @@
+use symbolic_common::byteview::ByteView;
+use symbolic_common::types::{Arch, DebugId, DebugKind, ObjectClass, ObjectKind};
+use symbolic_debuginfo::{DebugFileResolver, DebugLink, FatObject};
+use symbolic_testutils::fixture_path;
+
+#[test]
+fn test_peek_pe() {
+    let buffer =
+        ByteView::from_path(fixture_path("windows/crash.exe")).expect("Could not open file");
+    let kind = FatObject::peek(&buffer).expect("Could not peek the object");
+    assert_eq!(kind, Some(ObjectKind::Pe));
+}
+
+#[test]
+fn test_object_pe() {
+    let buffer =
+        ByteView::from_path(fixture_path("windows/crash.exe")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    assert_eq!(fat.kind(), ObjectKind::Pe);
+    assert_eq!(fat.object_count(), 1);
+
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    assert_eq!(object.kind(), ObjectKind::Pe);
+    assert_eq!(object.arch().expect("Unknown architecture"), Arch::X86);
+    assert_eq!(object.class(), ObjectClass::Executable);
+    assert_eq!(object.vmaddr(), 0x40_0000);
+    assert_eq!(
+        object.id(),
+        Some(DebugId::from_breakpad("3249D99D0C4049318610F4E4FB0B69361").unwrap())
+    );
+}
+
+#[test]
+fn test_object_pdb() {
+    let buffer =
+        ByteView::from_path(fixture_path("windows/crash.pdb")).expect("Could not open file");
+    assert_eq!(
+        FatObject::peek(&buffer).expect("Could not peek the object"),
+        Some(ObjectKind::Pdb)
+    );
+
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    assert_eq!(object.kind(), ObjectKind::Pdb);
+    assert_eq!(object.arch().expect("Unknown architecture"), Arch::X86);
+    assert_eq!(object.class(), ObjectClass::Debug);
+    assert_eq!(object.debug_kind(), Some(DebugKind::Pdb));
+    assert_eq!(
+        object.id(),
+        Some(DebugId::from_breakpad("3249D99D0C4049318610F4E4FB0B69361").unwrap())
+    );
+}
+
+#[test]
+fn test_debug_link() {
+    let buffer =
+        ByteView::from_path(fixture_path("linux/debuglink/hello")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    assert_eq!(
+        object.build_id(),
+        Some(
+            &[
+                0xd1, 0xf1, 0x9a, 0x1e, 0xba, 0x23, 0xed, 0x96, 0xc3, 0xfc, 0xd2, 0x03, 0x29, 0xe8,
+                0x9d, 0xb0, 0x32, 0xf3, 0x5f, 0x30
+            ][..]
+        )
+    );
+    assert_eq!(
+        object.debug_link(),
+        Some(DebugLink::new("hello.debug", 0x78af_8fc5))
+    );
+    assert_eq!(object.debug_alt_link(), None);
+}
+
+#[test]
+fn test_debug_alt_link() {
+    let buffer = ByteView::from_path(fixture_path("linux/debuglink/.debug/hello.debug"))
+        .expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let alt_link = object.debug_alt_link().expect("Missing debug alt link");
+    assert_eq!(
+        alt_link.filename(),
+        "/usr/lib/debug/.dwz/x86_64-linux-gnu/hello.debug"
+    );
+    assert_eq!(
+        alt_link.build_id(),
+        &[
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab,
+            0xcd, 0xef, 0x01, 0x23, 0x45, 0x67
+        ][..]
+    );
+    assert_eq!(object.debug_link(), None);
+}
+
+#[test]
+fn test_resolve_debug_file() {
+    let path = fixture_path("linux/debuglink/hello");
+    let buffer = ByteView::from_path(&path).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    // Next to the executable, `hello.debug` has a mismatching CRC and is skipped.
+    let resolver = DebugFileResolver::with_debug_dirs(&[] as &[&str]);
+    assert!(!resolver.verify(&object, fixture_path("linux/debuglink/hello.debug")));
+    assert_eq!(
+        resolver.resolve(&object, &path),
+        Some(fixture_path("linux/debuglink/.debug/hello.debug"))
+    );
+
+    // The build-id layout in global debug directories takes precedence.
+    let resolver = DebugFileResolver::with_debug_dirs(&[fixture_path("linux/debuglink/debug")]);
+    assert_eq!(
+        resolver.resolve(&object, &path),
+        Some(fixture_path(
+            "linux/debuglink/debug/.build-id/d1/f19a1eba23ed96c3fcd20329e89db032f35f30.debug"
+        ))
+    );
+}
+
+#[test]
+fn test_object_pe64() {
+    let buffer =
+        ByteView::from_path(fixture_path("windows/exports64.dll")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    assert_eq!(object.kind(), ObjectKind::Pe);
+    assert_eq!(object.arch().expect("Unknown architecture"), Arch::X86_64);
+    assert_eq!(object.class(), ObjectClass::Library);
+    assert_eq!(object.vmaddr(), 0x1_8000_0000);
+    assert_eq!(
+        object.id(),
+        Some(DebugId::from_breakpad("574F0B3CD2A17C4E9B3E5D6A7F8E9D0C1").unwrap())
+    );
+}
@@ -0,0 +1,92 @@
+use symbolic_common::byteview::ByteView;
+use symbolic_debuginfo::{FatObject, SymbolTable};
+use symbolic_testutils::fixture_path;
+
+#[test]
+fn test_symbols_elf_bin() {
+    let buffer = ByteView::from_path(fixture_path("linux/crash")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    assert!(object.has_symbols());
+    let symbols = object
+        .symbols()
+        .expect("Could not read symbols")
+        .expect("Missing symbol table");
+
+    assert_eq!(symbols.iter().count(), 167);
+    assert!(!symbols.requires_symbolmap());
+
+    let symbol = symbols
+        .lookup(0x41_3eb0)
+        .expect("Could not lookup symbol")
+        .expect("Missing symbol");
+    assert_eq!(symbol.as_str(), "ConvertUTF16toUTF32");
+    assert_eq!(symbol.addr(), 0x41_3eb0);
+    assert_eq!(symbol.len(), Some(412));
+}
+
+#[test]
+fn test_symbols_elf_dbg() {
+    let buffer =
+        ByteView::from_path(fixture_path("linux/crash.debug")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symbols = object
+        .symbols()
+        .expect("Could not read symbols")
+        .expect("Missing symbol table");
+
+    let symbol = symbols
+        .lookup(0x41_2c48)
+        .expect("Could not lookup symbol")
+        .expect("Missing symbol");
+    assert_eq!(symbol.as_str(), "_Z10CreateGUIDP6MDGUID");
+    assert_eq!(symbol.addr(), 0x41_2c40);
+}
+
+#[test]
+fn test_symbols_pe() {
+    let buffer =
+        ByteView::from_path(fixture_path("windows/exports64.dll")).expect("Could not open file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    assert!(object.has_symbols());
+    let symbols = object
+        .symbols()
+        .expect("Could not read symbols")
+        .expect("Missing symbol table");
+
+    let names: Vec<_> = symbols
+        .iter()
+        .map(|symbol| {
+            let symbol = symbol.expect("Could not read symbol");
+            (symbol.as_str().to_string(), symbol.addr())
+        })
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("hello".to_string(), 0x1_8000_1000),
+            ("twice".to_string(), 0x1_8000_1010),
+        ]
+    );
+
+    let symbol = symbols
+        .lookup(0x1_8000_1004)
+        .expect("Could not lookup symbol")
+        .expect("Missing symbol");
+    assert_eq!(symbol.as_str(), "hello");
+    assert_eq!(symbol.len(), Some(0x10));
+}
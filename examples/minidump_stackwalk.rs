@@ -116,6 +116,7 @@ fn symbolize<'a>(
     frame: &StackFrame,
     arch: Arch,
     crashing: bool,
+    signal: Option<u32>,
 ) -> Result<Option<Vec<LineInfo<'a>>>, Error> {
     let module = match frame.module() {
         Some(module) => module,
@@ -127,12 +128,12 @@ fn symbolize<'a>(
         None => return Ok(None),
     };
 
-    // TODO: Extract and supply signal and IP register
+    // TODO: Extract and supply IP register
     let instruction = InstructionInfo {
         addr: frame.return_address(arch),
         arch,
         crashing_frame: crashing,
-        signal: None,
+        signal,
         ip_reg: None,
     };
 
@@ -186,10 +187,14 @@ fn print_state(
             println!("\nThread {} (crashed)", ti);
         }
 
+        // Only the top frame of the crashing thread was interrupted by the signal.
+        let crash_signal = state.crash_signal(thread);
+
         let mut index = 0;
         for (fi, frame) in thread.frames().iter().enumerate() {
+            let signal = if fi == 0 { crash_signal } else { None };
             if let Some(module) = frame.module() {
-                if let Some(line_infos) = symbolize(&symcaches, frame, arch, fi == 0)? {
+                if let Some(line_infos) = symbolize(&symcaches, frame, arch, fi == 0, signal)? {
                     for (i, info) in line_infos.iter().enumerate() {
                         println!(
                             "{:>3}  {}!{} [{} : {} + 0x{:x}]",
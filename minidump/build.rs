@@ -1,4 +1,12 @@
+use std::env;
+
 fn main() {
+    // The Breakpad processor is only required for `ProcessState`. All other modules, including the
+    // minidump parser, are implemented in Rust.
+    if env::var_os("CARGO_FEATURE_PROCESSOR").is_none() {
+        return;
+    }
+
     cc::Build::new()
         .warnings(false)
         .file("third_party/breakpad/src/third_party/libdisasm/ia32_implicit.c")
@@ -541,6 +541,14 @@ char *code_module_debug_identifier(const code_module_t *module) {
     return string_from(code_module_t::cast(module)->debug_identifier());
 }
 
+char *code_module_version(const code_module_t *module) {
+    if (module == nullptr) {
+        return nullptr;
+    }
+
+    return string_from(code_module_t::cast(module)->version());
+}
+
 void code_modules_delete(code_module_t **modules) {
     if (modules != nullptr) {
         delete[] modules;
@@ -232,6 +232,13 @@ char *code_module_debug_file(const code_module_t *module);
 /// The return value is an owning pointer. Release memory with string_delete.
 char *code_module_debug_identifier(const code_module_t *module);
 
+/// A human-readable representation of the code module's version, such as
+/// "6.2.14393.1715". This is only available for Windows modules that carry
+/// version information and empty otherwise.
+///
+/// The return value is an owning pointer. Release memory with string_delete.
+char *code_module_version(const code_module_t *module);
+
 /// Releases memory of a code modules list. Assumes ownership of the pointer.
 void code_modules_delete(code_module_t **modules);
 
@@ -1,25 +1,34 @@
+use std::borrow::Cow;
+use std::cmp;
 use std::collections::HashMap;
 use std::fmt;
 use std::io::{self, Write};
+use std::mem;
+use std::ops::Range;
+use std::slice;
+use std::str;
 
 use failure::{Backtrace, Context, Fail, ResultExt};
 use gimli::{
-    BaseAddresses, CfaRule, CieOrFde, DebugFrame, EhFrame, FrameDescriptionEntry, Reader,
-    ReaderOffset, RegisterRule, UninitializedUnwindContext, UnwindOffset, UnwindSection,
+    BaseAddresses, CfaRule, CieOrFde, DebugFrame, EhFrame, Encoding, Expression, Format,
+    FrameDescriptionEntry, Operation, Reader, Register, RegisterRule, UnwindContext, UnwindSection,
     UnwindTable,
 };
 
 use symbolic_common::byteview::ByteView;
 use symbolic_common::types::{Arch, DebugKind, UnknownArchError};
-use symbolic_debuginfo::{DwarfData, DwarfSection, Object};
+use symbolic_debuginfo::{
+    BreakpadCfiRule, BreakpadRecord, BreakpadRecords, BreakpadStackCfiRecord, BreakpadStackRecords,
+    BreakpadStackWinRecord, BreakpadStackWinType, DwarfData, DwarfSection, Object,
+};
 
-use crate::registers::get_register_name;
+use crate::registers::{get_register_name, RegisterNameError};
 
 /// The latest version of the file format.
-pub const CFICACHE_LATEST_VERSION: u32 = 1;
+pub const CFICACHE_LATEST_VERSION: u32 = 2;
 
 /// Possible error kinds of `CfiError`.
-#[derive(Debug, Fail, Copy, Clone)]
+#[derive(Debug, Fail, Copy, Clone, Eq, PartialEq)]
 pub enum CfiErrorKind {
     /// Required debug sections are missing in the `Object` file.
     #[fail(display = "missing cfi debug sections")]
@@ -44,6 +53,14 @@ pub enum CfiErrorKind {
     /// Invalid magic bytes in the cfi cache header.
     #[fail(display = "bad cfi cache magic")]
     BadFileMagic,
+
+    /// The cfi cache version is not known.
+    #[fail(display = "unsupported cfi cache version")]
+    UnsupportedVersion,
+
+    /// Contents in the cfi cache file are malformed.
+    #[fail(display = "malformed cfi cache file")]
+    BadCacheFile,
 }
 
 /// An error returned by `AsciiCfiWriter`.
@@ -96,6 +113,59 @@ impl From<UnknownArchError> for CfiError {
     }
 }
 
+/// The reason why an unwind rule cannot be converted to Breakpad format.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CfiDiagnosticKind {
+    /// The rule is defined by the ABI and cannot be expressed in Breakpad format.
+    Architectural,
+    /// The DWARF expression contains an operation without Breakpad equivalent.
+    UnsupportedOperation(String),
+    /// The DWARF expression is malformed or does not compute a single value.
+    InvalidExpression,
+    /// The rule refers to a register number that does not exist for the architecture.
+    InvalidRegister(u16),
+}
+
+impl fmt::Display for CfiDiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CfiDiagnosticKind::Architectural => write!(f, "architectural rule"),
+            CfiDiagnosticKind::UnsupportedOperation(ref op) => {
+                write!(f, "unsupported operation {}", op)
+            }
+            CfiDiagnosticKind::InvalidExpression => write!(f, "invalid expression"),
+            CfiDiagnosticKind::InvalidRegister(register) => {
+                write!(f, "invalid register {}", register)
+            }
+        }
+    }
+}
+
+/// An unwind rule that was omitted by `AsciiCfiWriter` because it cannot be converted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CfiDiagnostic {
+    /// Start address of the unwind table row containing the rule.
+    pub address: u64,
+    /// Breakpad name of the register defined by the rule, `.cfa`, or `?` if the register number
+    /// is invalid.
+    pub register: &'static str,
+    /// The reason why the rule was omitted.
+    pub kind: CfiDiagnosticKind,
+}
+
+impl fmt::Display for CfiDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot convert {} rule at {:#x}: {}",
+            self.register, self.address, self.kind
+        )
+    }
+}
+
+/// A callback receiving unwind rules that cannot be converted.
+type DiagnosticsCallback = Box<dyn FnMut(&CfiDiagnostic)>;
+
 /// A service that converts call frame information (CFI) from an object file to Breakpad ASCII
 /// format and writes it to the given writer.
 ///
@@ -140,12 +210,37 @@ impl From<UnknownArchError> for CfiError {
 /// ```
 pub struct AsciiCfiWriter<W: Write> {
     inner: W,
+    diagnostics: Option<DiagnosticsCallback>,
 }
 
 impl<W: Write> AsciiCfiWriter<W> {
     /// Creates a new `AsciiCfiWriter` that outputs to a writer.
     pub fn new(inner: W) -> Self {
-        AsciiCfiWriter { inner }
+        AsciiCfiWriter {
+            inner,
+            diagnostics: None,
+        }
+    }
+
+    /// Sets a callback that is invoked for every unwind rule that cannot be converted.
+    ///
+    /// DWARF expressions are translated to Breakpad postfix expressions where possible. Rules that
+    /// cannot be converted are omitted from the output and reported to this callback.
+    pub fn set_diagnostics<F>(&mut self, callback: F)
+    where
+        F: FnMut(&CfiDiagnostic) + 'static,
+    {
+        self.diagnostics = Some(Box::new(callback));
+    }
+
+    fn report(&mut self, address: u64, register: &'static str, kind: CfiDiagnosticKind) {
+        if let Some(ref mut callback) = self.diagnostics {
+            callback(&CfiDiagnostic {
+                address,
+                register,
+                kind,
+            });
+        }
     }
 
     /// Extracts CFI from the given object file.
@@ -168,15 +263,26 @@ impl<W: Write> AsciiCfiWriter<W> {
     }
 
     fn process_breakpad(&mut self, object: &Object<'_>) -> Result<(), CfiError> {
+        // Only STACK records are relevant for CFI. Parse them in isolation, so that malformed
+        // FUNC, line or PUBLIC records do not prevent writing otherwise valid unwind info.
+        let mut stack_data = Vec::new();
         for line in object.as_bytes().split(|b| *b == b'\n') {
-            if line.starts_with(b"STACK") {
-                self.inner
-                    .write_all(line)
-                    .context(CfiErrorKind::WriteError)?;
-                self.inner.write(b"\n").context(CfiErrorKind::WriteError)?;
+            if line.starts_with(b"STACK ") {
+                stack_data.extend_from_slice(line);
+                stack_data.push(b'\n');
             }
         }
 
+        for record in BreakpadRecords::from_bytes(&stack_data) {
+            match record.context(CfiErrorKind::BadDebugInfo)? {
+                BreakpadRecord::StackCfi(cfi) => writeln!(self.inner, "{}", cfi),
+                BreakpadRecord::StackCfiDelta(delta) => writeln!(self.inner, "{}", delta),
+                BreakpadRecord::StackWin(win) => writeln!(self.inner, "{}", win),
+                _ => continue,
+            }
+            .context(CfiErrorKind::WriteError)?;
+        }
+
         Ok(())
     }
 
@@ -204,7 +310,7 @@ impl<W: Write> AsciiCfiWriter<W> {
         // CFI information can have relative offsets to the base address of thir respective debug
         // section (either `.eh_frame` or `.debug_frame`). We need to supply this offset to the
         // entries iterator before starting to interpret instructions.
-        let bases = BaseAddresses::default().set_cfi(base);
+        let bases = BaseAddresses::default().set_eh_frame(base);
 
         let mut entries = frame.entries(&bases);
         while let Some(entry) = entries.next().context(CfiErrorKind::BadDebugInfo)? {
@@ -213,8 +319,8 @@ impl<W: Write> AsciiCfiWriter<W> {
             // callback. This function is provided by the UnwindSection (frame), which then parses
             // the CIE and returns it for the FDE.
             if let CieOrFde::Fde(partial_fde) = entry {
-                if let Ok(fde) = partial_fde.parse(|off| frame.cie_from_offset(&bases, off)) {
-                    self.process_fde(arch, &fde)?
+                if let Ok(fde) = partial_fde.parse(U::cie_from_offset) {
+                    self.process_fde(arch, frame, &bases, &fde)?
                 }
             }
         }
@@ -222,16 +328,16 @@ impl<W: Write> AsciiCfiWriter<W> {
         Ok(())
     }
 
-    fn process_fde<S, R, O>(
+    fn process_fde<U, R>(
         &mut self,
         arch: Arch,
-        fde: &FrameDescriptionEntry<S, R, O>,
+        frame: &U,
+        bases: &BaseAddresses,
+        fde: &FrameDescriptionEntry<R>,
     ) -> Result<(), CfiError>
     where
-        R: Reader<Offset = O> + Eq,
-        O: ReaderOffset,
-        S: UnwindSection<R>,
-        S::Offset: UnwindOffset<R::Offset>,
+        R: Reader + Eq,
+        U: UnwindSection<R>,
     {
         // Retrieves the register that specifies the return address. We need to assign a special
         // format to this register for Breakpad.
@@ -240,12 +346,9 @@ impl<W: Write> AsciiCfiWriter<W> {
         // Interpret all DWARF instructions of this Frame Description Entry. This gives us an unwind
         // table that contains rules for retrieving registers at every instruction address. These
         // rules can directly be transcribed to breakpad STACK CFI records.
-        let ctx = UninitializedUnwindContext::new();
-        let mut ctx = ctx
-            .initialize(fde.cie())
-            .map_err(|(e, _)| e)
-            .context(CfiErrorKind::BadDebugInfo)?;
-        let mut table = UnwindTable::new(&mut ctx, &fde);
+        let mut ctx = UnwindContext::new();
+        let mut table =
+            UnwindTable::new(frame, bases, &mut ctx, fde).context(CfiErrorKind::BadDebugInfo)?;
 
         // Collect all rows first, as we need to know the final end address in order to write the
         // CFI INIT record describing the extent of the whole unwind table.
@@ -283,7 +386,7 @@ impl<W: Write> AsciiCfiWriter<W> {
 
                 // Write the mandatory CFA rule for this row, followed by optional register rules.
                 // The actual formatting of the rules depends on their rule type.
-                self.write_cfa_rule(arch, row.cfa())?;
+                self.write_cfa_rule(arch, row.start_address(), row.cfa())?;
 
                 // Print only registers that have changed rules to their previous occurrence to
                 // reduce the number of rules per row. Then, cache the new occurrence for the next
@@ -291,7 +394,7 @@ impl<W: Write> AsciiCfiWriter<W> {
                 for &(register, ref rule) in row.registers() {
                     if !rule_cache.get(&register).map_or(false, |c| c == &rule) {
                         rule_cache.insert(register, rule);
-                        self.write_register_rule(arch, register, rule, ra)?;
+                        self.write_register_rule(arch, row.start_address(), register, rule, ra)?;
                     }
                 }
 
@@ -305,14 +408,25 @@ impl<W: Write> AsciiCfiWriter<W> {
     fn write_cfa_rule<R: Reader>(
         &mut self,
         arch: Arch,
+        address: u64,
         rule: &CfaRule<R>,
     ) -> Result<bool, CfiError> {
         use gimli::CfaRule::*;
         let formatted = match rule {
-            RegisterAndOffset { register, offset } => {
-                format!("{} {} +", get_register_name(arch, *register)?, *offset)
-            }
-            Expression(_) => return Ok(false),
+            RegisterAndOffset { register, offset } => match resolve_register(arch, *register)? {
+                Ok(name) => format!("{} {} +", name, *offset),
+                Err(kind) => {
+                    self.report(address, ".cfa", kind);
+                    return Ok(false);
+                }
+            },
+            Expression(expression) => match translate_expression(arch, expression, false) {
+                Ok(formatted) => formatted,
+                Err(kind) => {
+                    self.report(address, ".cfa", kind);
+                    return Ok(false);
+                }
+            },
         };
 
         write!(self.inner, " .cfa: {}", formatted).context(CfiErrorKind::WriteError)?;
@@ -322,28 +436,47 @@ impl<W: Write> AsciiCfiWriter<W> {
     fn write_register_rule<R: Reader>(
         &mut self,
         arch: Arch,
-        register: u8,
+        address: u64,
+        register: Register,
         rule: &RegisterRule<R>,
-        ra: u64,
+        ra: Register,
     ) -> Result<bool, CfiError> {
-        use gimli::RegisterRule::*;
-        let formatted = match rule {
-            Undefined => return Ok(false),
-            SameValue => get_register_name(arch, register)?.into(),
-            Offset(offset) => format!(".cfa {} + ^", offset),
-            ValOffset(offset) => format!(".cfa {} +", offset),
-            Register(register) => get_register_name(arch, *register)?.into(),
-            Expression(_) => return Ok(false),
-            ValExpression(_) => return Ok(false),
-            Architectural => return Ok(false),
-        };
-
         // Breakpad requires an explicit name for the return address register. In all other cases,
         // we use platform specific names for each register as specified by Breakpad.
-        let register_name = if u64::from(register) == ra {
+        let register_name = if register == ra {
             ".ra"
         } else {
-            get_register_name(arch, register)?
+            match resolve_register(arch, register)? {
+                Ok(name) => name,
+                Err(kind) => {
+                    self.report(address, "?", kind);
+                    return Ok(false);
+                }
+            }
+        };
+
+        // Expressions of register rules are evaluated with the CFA pushed on the stack. Their
+        // result is either the address where the register is saved, or its value directly.
+        use gimli::RegisterRule::*;
+        let translated = match rule {
+            Undefined => return Ok(false),
+            SameValue => resolve_register(arch, register)?.map(Into::into),
+            Offset(offset) => Ok(format!(".cfa {} + ^", offset)),
+            ValOffset(offset) => Ok(format!(".cfa {} +", offset)),
+            Register(register) => resolve_register(arch, *register)?.map(Into::into),
+            Expression(expression) => {
+                translate_expression(arch, expression, true).map(|expr| format!("{} ^", expr))
+            }
+            ValExpression(expression) => translate_expression(arch, expression, true),
+            Architectural => Err(CfiDiagnosticKind::Architectural),
+        };
+
+        let formatted = match translated {
+            Ok(formatted) => formatted,
+            Err(kind) => {
+                self.report(address, register_name, kind);
+                return Ok(false);
+            }
         };
 
         write!(self.inner, " {}: {}", register_name, formatted)
@@ -352,6 +485,160 @@ impl<W: Write> AsciiCfiWriter<W> {
     }
 }
 
+/// Resolves the Breakpad name of a register referenced by an unwind rule.
+///
+/// Register numbers that do not exist for the architecture come from malformed unwind information
+/// and only invalidate the rule, while an unsupported architecture fails the entire conversion.
+fn resolve_register(
+    arch: Arch,
+    register: Register,
+) -> Result<Result<&'static str, CfiDiagnosticKind>, CfiError> {
+    match get_register_name(arch, register) {
+        Ok(name) => Ok(Ok(name)),
+        Err(RegisterNameError::InvalidRegister) => {
+            Ok(Err(CfiDiagnosticKind::InvalidRegister(register.0)))
+        }
+        Err(RegisterNameError::UnknownArch) => Err(CfiErrorKind::UnsupportedArch.into()),
+    }
+}
+
+/// Converts a DWARF expression from CFI into a Breakpad postfix expression.
+///
+/// Both formats describe programs for a stack machine, so most operations map one to one. Only the
+/// subset used in practice for unwinding is supported: register values with offsets, constants,
+/// dereferences and basic arithmetic. Breakpad has no bitwise operators, so `DW_OP_and` is only
+/// supported with a constant mask that can be expressed as alignment (`@`) or modulus (`%`).
+///
+/// If `push_cfa` is set, the CFA is pushed on the stack before evaluating the expression, as
+/// required for register rules. The expression must leave exactly one value on the stack.
+fn translate_expression<R: Reader>(
+    arch: Arch,
+    expression: &Expression<R>,
+    push_cfa: bool,
+) -> Result<String, CfiDiagnosticKind> {
+    let address_size = arch.pointer_size().unwrap_or(8) as u8;
+    let address_mask = if address_size >= 8 {
+        !0
+    } else {
+        (1u64 << (u32::from(address_size) * 8)) - 1
+    };
+
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    if push_cfa {
+        tokens.push(".cfa".to_string());
+        depth += 1;
+    }
+
+    // Pops `count` operands and pushes a single result.
+    fn apply(depth: &mut usize, count: usize) -> Result<(), CfiDiagnosticKind> {
+        if *depth < count {
+            return Err(CfiDiagnosticKind::InvalidExpression);
+        }
+        *depth = *depth - count + 1;
+        Ok(())
+    }
+
+    let mut bytes = expression.0.clone();
+    let mut last_literal: Option<u64> = None;
+    while !bytes.is_empty() {
+        let opcode = bytes
+            .clone()
+            .read_u8()
+            .map_err(|_| CfiDiagnosticKind::InvalidExpression)?;
+        let unsupported =
+            || CfiDiagnosticKind::UnsupportedOperation(gimli::DwOp(opcode).to_string());
+
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size,
+        };
+        let operation = Operation::parse(&mut bytes, encoding)
+            .map_err(|_| CfiDiagnosticKind::InvalidExpression)?;
+
+        let mut literal = None;
+        match operation {
+            Operation::UnsignedConstant { value } => {
+                tokens.push((value as i64).to_string());
+                literal = Some(value & address_mask);
+                apply(&mut depth, 0)?;
+            }
+            Operation::SignedConstant { value } => {
+                tokens.push(value.to_string());
+                literal = Some(value as u64 & address_mask);
+                apply(&mut depth, 0)?;
+            }
+            Operation::RegisterOffset {
+                register, offset, ..
+            } => {
+                let name = get_register_name(arch, register)
+                    .map_err(|_| CfiDiagnosticKind::InvalidExpression)?;
+                tokens.push(name.to_string());
+                if offset != 0 {
+                    tokens.push(offset.to_string());
+                    tokens.push("+".to_string());
+                }
+                apply(&mut depth, 0)?;
+            }
+            Operation::CallFrameCFA if push_cfa => {
+                tokens.push(".cfa".to_string());
+                apply(&mut depth, 0)?;
+            }
+            Operation::Deref {
+                size, space: false, ..
+            } if size == address_size => {
+                tokens.push("^".to_string());
+                apply(&mut depth, 1)?;
+            }
+            Operation::PlusConstant { value } => {
+                tokens.push((value as i64).to_string());
+                tokens.push("+".to_string());
+                apply(&mut depth, 1)?;
+            }
+            Operation::Plus => {
+                tokens.push("+".to_string());
+                apply(&mut depth, 2)?;
+            }
+            Operation::Minus => {
+                tokens.push("-".to_string());
+                apply(&mut depth, 2)?;
+            }
+            Operation::Mul => {
+                tokens.push("*".to_string());
+                apply(&mut depth, 2)?;
+            }
+            Operation::And => {
+                let mask = last_literal.ok_or_else(unsupported)?;
+                let (operand, operator) = if mask.wrapping_add(1).is_power_of_two() {
+                    // `x & (2^n - 1)` is the remainder of a division by `2^n`.
+                    (mask.wrapping_add(1), "%")
+                } else if ((!mask & address_mask) + 1).is_power_of_two() {
+                    // `x & ~(2^n - 1)` aligns down to a multiple of `2^n`.
+                    ((!mask & address_mask) + 1, "@")
+                } else {
+                    return Err(unsupported());
+                };
+
+                tokens.pop();
+                tokens.push(operand.to_string());
+                tokens.push(operator.to_string());
+                apply(&mut depth, 2)?;
+            }
+            Operation::Nop => (),
+            _ => return Err(unsupported()),
+        }
+
+        last_literal = literal;
+    }
+
+    if depth != 1 {
+        return Err(CfiDiagnosticKind::InvalidExpression);
+    }
+
+    Ok(tokens.join(" "))
+}
+
 impl<W: Write + Default> AsciiCfiWriter<W> {
     /// Extracts CFI from the given object and pipes it to a new writer instance.
     pub fn transform(object: &Object<'_>) -> Result<W, CfiError> {
@@ -369,10 +656,797 @@ impl<'a> CfiCacheV1<'a> {
     pub fn raw(&self) -> &[u8] {
         &self.byteview
     }
+
+    /// Looks up unwind information by parsing the ASCII records.
+    ///
+    /// This has to scan the entire file on every lookup.
+    pub fn lookup(&self, address: u64) -> Option<UnwindRule<'_>> {
+        let records =
+            BreakpadStackRecords::from_records(BreakpadRecords::from_bytes(self.raw())).ok()?;
+
+        let row = records
+            .cfi_records()
+            .iter()
+            .filter(|cfi| cfi.address <= address && address < cfi.end())
+            .flat_map(resolve_cfi_rows)
+            .find(|row| row.address <= address && address < row.end());
+
+        if let Some(row) = row {
+            return Some(UnwindRule::Cfi(row));
+        }
+
+        records
+            .win_records()
+            .iter()
+            .find(|win| win.address() <= address && address < win.end())
+            .map(|win| UnwindRule::Win(win.clone()))
+    }
+}
+
+/// Magic bytes of a binary CFI cache file.
+const CFICACHE_MAGIC: [u8; 4] = *b"CFIC";
+
+/// A segment of records or bytes in a binary CFI cache file.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+struct CfiSeg {
+    offset: u32,
+    len: u32,
+}
+
+/// The header of a version 2 CFI cache file.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, Default)]
+struct CfiCacheHeaderV2 {
+    magic: [u8; 4],
+    version: u32,
+    rows: CfiSeg,
+    rules: CfiSeg,
+    ops: CfiSeg,
+    win: CfiSeg,
+    strings: CfiSeg,
+}
+
+/// Flag of a `CfiRowRecord` starting a new CFI INIT record.
+const CFI_ROW_INIT: u32 = 0x1;
+
+/// A row of a CFI table with all register rules resolved.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, Default)]
+struct CfiRowRecord {
+    addr: u64,
+    size: u64,
+    flags: u32,
+    rules: CfiSeg,
+}
+
+/// A register rule referencing its register name in the string table and its compiled
+/// expression in the operation table.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, Default)]
+struct CfiRuleRecord {
+    register: CfiSeg,
+    expression: CfiSeg,
+}
+
+/// Kinds of `CfiOpRecord`s, corresponding to the variants of `CfiOp`.
+const CFI_OP_CONST: u32 = 0;
+const CFI_OP_VARIABLE: u32 = 1;
+const CFI_OP_ADD: u32 = 2;
+const CFI_OP_SUB: u32 = 3;
+const CFI_OP_MUL: u32 = 4;
+const CFI_OP_DIV: u32 = 5;
+const CFI_OP_REM: u32 = 6;
+const CFI_OP_ALIGN: u32 = 7;
+const CFI_OP_DEREF: u32 = 8;
+const CFI_OP_ASSIGN: u32 = 9;
+
+/// An operation of a compiled postfix expression.
+///
+/// Variables reference their name in the string table, constants store their value.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+struct CfiOpRecord {
+    kind: u32,
+    name: CfiSeg,
+    value: u64,
+}
+
+/// Flag of a `WinRecord` indicating that the record has a program string.
+const WIN_HAS_PROGRAM_STRING: u32 = 0x1;
+/// Flag of a `WinRecord` indicating that the function allocates a base pointer.
+const WIN_ALLOCATES_BASE_POINTER: u32 = 0x2;
+
+/// A STACK WIN record of a Windows x86 function.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug, Default)]
+struct WinRecord {
+    ty: u32,
+    rva: u32,
+    code_size: u32,
+    prolog_size: u32,
+    epilog_size: u32,
+    params_size: u32,
+    saved_regs_size: u32,
+    locals_size: u32,
+    max_stack_size: u32,
+    flags: u32,
+    program_string: CfiSeg,
+}
+
+/// Returns the raw bytes of a record.
+fn record_bytes<T>(record: &T) -> &[u8] {
+    unsafe { slice::from_raw_parts(record as *const T as *const u8, mem::size_of::<T>()) }
+}
+
+/// Expands a CFI INIT record and its deltas into rows with fully resolved and compiled rules.
+///
+/// Each row covers the range up to the next row, or the end of the INIT record. Rows that do not
+/// cover any address are omitted. This happens if a delta record starts at the same address as
+/// the INIT record or the previous delta. The first returned row starts the INIT record.
+fn resolve_cfi_rows<'a>(cfi: &BreakpadStackCfiRecord<'a>) -> Vec<CfiRow<'a>> {
+    fn push_row<'a>(rows: &mut Vec<CfiRow<'a>>, range: Range<u64>, rules: &[BreakpadCfiRule<'a>]) {
+        if range.start < range.end {
+            rows.push(CfiRow {
+                address: range.start,
+                size: range.end - range.start,
+                rules: rules.iter().map(CfiRule::compile).collect(),
+            });
+        }
+    }
+
+    let mut rows = Vec::with_capacity(cfi.deltas.len() + 1);
+    let mut rules = cfi.rules.clone();
+    let mut address = cfi.address;
+
+    for delta in &cfi.deltas {
+        let end = cmp::min(cmp::max(delta.address, address), cfi.end());
+        push_row(&mut rows, address..end, &rules);
+
+        for rule in &delta.rules {
+            match rules.iter_mut().find(|r| r.register == rule.register) {
+                Some(existing) => existing.expression = rule.expression,
+                None => rules.push(rule.clone()),
+            }
+        }
+
+        address = end;
+    }
+
+    push_row(&mut rows, address..cfi.end(), &rules);
+    rows
+}
+
+/// Serializes STACK records into a version 2 CFI cache file.
+///
+/// Records can be added in any order. When finishing the file, rows and WIN records are sorted
+/// by address and records overlapping a previous record are dropped, so that every address is
+/// covered by at most one row and one WIN record.
+struct CfiCacheWriterV2 {
+    strings: Vec<u8>,
+    string_map: HashMap<Vec<u8>, CfiSeg>,
+    ops: Vec<CfiOpRecord>,
+    ops_map: HashMap<Vec<CfiOpRecord>, CfiSeg>,
+    rules: Vec<CfiRuleRecord>,
+    rows: Vec<CfiRowRecord>,
+    groups: Vec<Range<usize>>,
+    win: Vec<WinRecord>,
+}
+
+impl CfiCacheWriterV2 {
+    fn new() -> Self {
+        CfiCacheWriterV2 {
+            strings: Vec::new(),
+            string_map: HashMap::new(),
+            ops: Vec::new(),
+            ops_map: HashMap::new(),
+            rules: Vec::new(),
+            rows: Vec::new(),
+            groups: Vec::new(),
+            win: Vec::new(),
+        }
+    }
+
+    fn write_string(&mut self, string: &[u8]) -> CfiSeg {
+        if let Some(seg) = self.string_map.get(string) {
+            return *seg;
+        }
+
+        let seg = CfiSeg {
+            offset: self.strings.len() as u32,
+            len: string.len() as u32,
+        };
+
+        self.strings.extend_from_slice(string);
+        self.string_map.insert(string.to_vec(), seg);
+        seg
+    }
+
+    /// Writes a compiled expression to the operation table, reusing identical expressions.
+    fn write_expression(&mut self, expression: &[CfiOp<'_>]) -> CfiSeg {
+        let records: Vec<_> = expression
+            .iter()
+            .map(|op| {
+                let mut record = CfiOpRecord {
+                    kind: op.kind(),
+                    ..Default::default()
+                };
+
+                match *op {
+                    CfiOp::Const(value) => record.value = value,
+                    CfiOp::Variable(name) => record.name = self.write_string(name),
+                    _ => (),
+                }
+
+                record
+            })
+            .collect();
+
+        if let Some(seg) = self.ops_map.get(&records) {
+            return *seg;
+        }
+
+        let seg = CfiSeg {
+            offset: self.ops.len() as u32,
+            len: records.len() as u32,
+        };
+
+        self.ops.extend_from_slice(&records);
+        self.ops_map.insert(records, seg);
+        seg
+    }
+
+    fn add_cfi(&mut self, cfi: &BreakpadStackCfiRecord<'_>) {
+        let start = self.rows.len();
+
+        for row in resolve_cfi_rows(cfi) {
+            let rules = CfiSeg {
+                offset: self.rules.len() as u32,
+                len: row.rules.len() as u32,
+            };
+
+            for rule in &row.rules {
+                let record = CfiRuleRecord {
+                    register: self.write_string(rule.register),
+                    expression: self.write_expression(&rule.expression),
+                };
+                self.rules.push(record);
+            }
+
+            self.rows.push(CfiRowRecord {
+                addr: row.address,
+                size: row.size,
+                flags: if self.rows.len() == start {
+                    CFI_ROW_INIT
+                } else {
+                    0
+                },
+                rules,
+            });
+        }
+
+        // Records without any rows, such as empty INIT records, are dropped entirely.
+        if self.rows.len() > start {
+            self.groups.push(start..self.rows.len());
+        }
+    }
+
+    fn add_win(&mut self, win: &BreakpadStackWinRecord<'_>) {
+        let mut flags = 0;
+        if win.allocates_base_pointer {
+            flags |= WIN_ALLOCATES_BASE_POINTER;
+        }
+
+        let program_string = match win.program_string {
+            Some(program_string) => {
+                flags |= WIN_HAS_PROGRAM_STRING;
+                self.write_string(program_string)
+            }
+            None => CfiSeg::default(),
+        };
+
+        self.win.push(WinRecord {
+            ty: win.ty as u32,
+            rva: win.rva,
+            code_size: win.code_size,
+            prolog_size: win.prolog_size,
+            epilog_size: win.epilog_size,
+            params_size: win.params_size,
+            saved_regs_size: win.saved_regs_size,
+            locals_size: win.locals_size,
+            max_stack_size: win.max_stack_size,
+            flags,
+            program_string,
+        });
+    }
+
+    /// Sorts rows of INIT records and WIN records by address and removes overlapping records.
+    ///
+    /// Records are kept in the order they were added when they start at the same address. For
+    /// WIN records, frame data is preferred over other types, similar to the Breakpad processor.
+    fn sort_records(&mut self) {
+        let rows = &self.rows;
+        self.groups.sort_by_key(|group| rows[group.start].addr);
+
+        let mut sorted = Vec::with_capacity(self.rows.len());
+        let mut end = 0;
+        for group in &self.groups {
+            let group = &self.rows[group.clone()];
+            if group[0].addr < end {
+                continue;
+            }
+
+            sorted.extend_from_slice(group);
+            let last = &group[group.len() - 1];
+            end = last.addr.saturating_add(last.size);
+        }
+        self.rows = sorted;
+
+        let frame_data = BreakpadStackWinType::FrameData as u32;
+        self.win.sort_by_key(|win| (win.rva, win.ty != frame_data));
+
+        let mut end = 0;
+        self.win.retain(|win| {
+            let start = u64::from(win.rva);
+            if win.code_size == 0 || start < end {
+                return false;
+            }
+
+            end = start + u64::from(win.code_size);
+            true
+        });
+    }
+
+    /// Writes the cache file, laying out all tables after the header.
+    fn finish(mut self) -> Vec<u8> {
+        fn table<T>(offset: &mut usize, items: &[T]) -> CfiSeg {
+            let seg = CfiSeg {
+                offset: *offset as u32,
+                len: items.len() as u32,
+            };
+            *offset += mem::size_of_val(items);
+            seg
+        }
+
+        self.sort_records();
+
+        let mut offset = mem::size_of::<CfiCacheHeaderV2>();
+        let header = CfiCacheHeaderV2 {
+            magic: CFICACHE_MAGIC,
+            version: 2,
+            rows: table(&mut offset, &self.rows),
+            rules: table(&mut offset, &self.rules),
+            ops: table(&mut offset, &self.ops),
+            win: table(&mut offset, &self.win),
+            strings: table(&mut offset, &self.strings),
+        };
+
+        let mut buffer = Vec::with_capacity(offset);
+        buffer.extend_from_slice(record_bytes(&header));
+        for row in &self.rows {
+            buffer.extend_from_slice(record_bytes(row));
+        }
+        for rule in &self.rules {
+            buffer.extend_from_slice(record_bytes(rule));
+        }
+        for op in &self.ops {
+            buffer.extend_from_slice(record_bytes(op));
+        }
+        for win in &self.win {
+            buffer.extend_from_slice(record_bytes(win));
+        }
+        buffer.extend_from_slice(&self.strings);
+        buffer
+    }
+}
+
+/// A binary CFI cache with rows sorted by address.
+///
+/// All records are stored in packed tables that can be used directly from a memory mapped file.
+/// Each row contains the complete set of rules valid for its address range, so that no delta
+/// records need to be applied during lookup. Rule expressions are stored as compiled operations
+/// and do not need to be parsed again.
+struct CfiCacheV2<'a> {
+    byteview: ByteView<'a>,
+    header: CfiCacheHeaderV2,
+}
+
+impl<'a> CfiCacheV2<'a> {
+    /// Converts STACK records in Breakpad ASCII format into a binary cache.
+    pub fn from_ascii(data: &[u8]) -> Result<CfiCacheV2<'static>, CfiError> {
+        let records = BreakpadStackRecords::from_records(BreakpadRecords::from_bytes(data))
+            .context(CfiErrorKind::BadDebugInfo)?;
+
+        let mut writer = CfiCacheWriterV2::new();
+        for cfi in records.cfi_records() {
+            writer.add_cfi(cfi);
+        }
+        for win in records.win_records() {
+            writer.add_win(win);
+        }
+
+        CfiCacheV2::parse(ByteView::from_vec(writer.finish()))
+    }
+
+    /// Validates the header and all tables of a binary cache.
+    pub fn parse(byteview: ByteView<'a>) -> Result<Self, CfiError> {
+        if !byteview.starts_with(&CFICACHE_MAGIC) {
+            return Err(CfiErrorKind::BadFileMagic.into());
+        }
+
+        if byteview.len() < mem::size_of::<CfiCacheHeaderV2>() {
+            return Err(CfiErrorKind::BadCacheFile.into());
+        }
+
+        let header = unsafe { *(byteview.as_ptr() as *const CfiCacheHeaderV2) };
+        if header.version != 2 {
+            return Err(CfiErrorKind::UnsupportedVersion.into());
+        }
+
+        let cache = CfiCacheV2 { byteview, header };
+        cache.table::<CfiRowRecord>(header.rows)?;
+        cache.table::<CfiRuleRecord>(header.rules)?;
+        cache.table::<CfiOpRecord>(header.ops)?;
+        cache.table::<WinRecord>(header.win)?;
+        cache.table::<u8>(header.strings)?;
+        Ok(cache)
+    }
+
+    pub fn raw(&self) -> &[u8] {
+        &self.byteview
+    }
+
+    /// Returns a table of records in the file.
+    fn table<T>(&self, seg: CfiSeg) -> Result<&[T], CfiError> {
+        let offset = seg.offset as usize;
+        let len = seg.len as usize;
+        let end = len
+            .checked_mul(mem::size_of::<T>())
+            .and_then(|size| size.checked_add(offset))
+            .ok_or(CfiErrorKind::BadCacheFile)?;
+
+        if end > self.byteview.len() {
+            return Err(CfiErrorKind::BadCacheFile.into());
+        }
+
+        let data = &self.byteview[offset..];
+        Ok(unsafe { slice::from_raw_parts(data.as_ptr() as *const T, len) })
+    }
+
+    fn rows(&self) -> &[CfiRowRecord] {
+        self.table(self.header.rows).unwrap_or_default()
+    }
+
+    fn win_records(&self) -> &[WinRecord] {
+        self.table(self.header.win).unwrap_or_default()
+    }
+
+    /// Resolves a string from the string table.
+    fn string(&self, seg: CfiSeg) -> Option<&[u8]> {
+        let strings = self.table::<u8>(self.header.strings).ok()?;
+        let start = seg.offset as usize;
+        strings.get(start..start.checked_add(seg.len as usize)?)
+    }
+
+    /// Converts an operation record into a `CfiOp`.
+    fn cfi_op(&self, op: &CfiOpRecord) -> Option<CfiOp<'_>> {
+        Some(match op.kind {
+            CFI_OP_CONST => CfiOp::Const(op.value),
+            CFI_OP_VARIABLE => CfiOp::Variable(self.string(op.name)?),
+            CFI_OP_ADD => CfiOp::Add,
+            CFI_OP_SUB => CfiOp::Sub,
+            CFI_OP_MUL => CfiOp::Mul,
+            CFI_OP_DIV => CfiOp::Div,
+            CFI_OP_REM => CfiOp::Rem,
+            CFI_OP_ALIGN => CfiOp::Align,
+            CFI_OP_DEREF => CfiOp::Deref,
+            CFI_OP_ASSIGN => CfiOp::Assign,
+            _ => return None,
+        })
+    }
+
+    /// Converts a row record into a `CfiRow`.
+    fn cfi_row(&self, row: &CfiRowRecord) -> Option<CfiRow<'_>> {
+        fn segment<T>(table: &[T], seg: CfiSeg) -> Option<&[T]> {
+            let start = seg.offset as usize;
+            table.get(start..start.checked_add(seg.len as usize)?)
+        }
+
+        let all_rules = self.table::<CfiRuleRecord>(self.header.rules).ok()?;
+        let all_ops = self.table::<CfiOpRecord>(self.header.ops).ok()?;
+
+        let mut rules = Vec::with_capacity(row.rules.len as usize);
+        for rule in segment(all_rules, row.rules)? {
+            let expression = segment(all_ops, rule.expression)?
+                .iter()
+                .map(|op| self.cfi_op(op))
+                .collect::<Option<_>>()?;
+
+            rules.push(CfiRule {
+                register: self.string(rule.register)?,
+                expression,
+            });
+        }
+
+        Some(CfiRow {
+            address: row.addr,
+            size: row.size,
+            rules,
+        })
+    }
+
+    /// Converts a WIN record into a `BreakpadStackWinRecord`.
+    fn win_record(&self, win: &WinRecord) -> Option<BreakpadStackWinRecord<'_>> {
+        let ty = match win.ty {
+            0 => BreakpadStackWinType::Fpo,
+            1 => BreakpadStackWinType::Trap,
+            2 => BreakpadStackWinType::Tss,
+            3 => BreakpadStackWinType::Standard,
+            4 => BreakpadStackWinType::FrameData,
+            _ => return None,
+        };
+
+        let program_string = if win.flags & WIN_HAS_PROGRAM_STRING != 0 {
+            Some(self.string(win.program_string)?)
+        } else {
+            None
+        };
+
+        Some(BreakpadStackWinRecord {
+            ty,
+            rva: win.rva,
+            code_size: win.code_size,
+            prolog_size: win.prolog_size,
+            epilog_size: win.epilog_size,
+            params_size: win.params_size,
+            saved_regs_size: win.saved_regs_size,
+            locals_size: win.locals_size,
+            max_stack_size: win.max_stack_size,
+            program_string,
+            allocates_base_pointer: win.flags & WIN_ALLOCATES_BASE_POINTER != 0,
+        })
+    }
+
+    /// Looks up the row covering the given address using binary search.
+    ///
+    /// This relies on the writer to emit non-empty, non-overlapping rows and WIN records sorted by
+    /// address. Only the last record starting at or before the address can cover it.
+    pub fn lookup(&self, address: u64) -> Option<UnwindRule<'_>> {
+        let rows = self.rows();
+        let index = rows.partition_point(|row| row.addr <= address);
+        if let Some(row) = index.checked_sub(1).map(|index| &rows[index]) {
+            if address < row.addr.saturating_add(row.size) {
+                return self.cfi_row(row).map(UnwindRule::Cfi);
+            }
+        }
+
+        let win_records = self.win_records();
+        let index = win_records.partition_point(|win| u64::from(win.rva) <= address);
+        let win = &win_records[index.checked_sub(1)?];
+        if address < u64::from(win.rva) + u64::from(win.code_size) {
+            return self.win_record(win).map(UnwindRule::Win);
+        }
+
+        None
+    }
+
+    /// Writes all records in Breakpad ASCII format.
+    ///
+    /// Rows following a CFI INIT record are written as delta records with changed rules only.
+    pub fn write_ascii<W: Write>(&self, mut writer: W) -> Result<(), io::Error> {
+        let rows = self.rows();
+        let mut index = 0;
+
+        while index < rows.len() {
+            let init_row = self.cfi_row(&rows[index]).ok_or_else(invalid_cache)?;
+            let mut end = index + 1;
+            while end < rows.len() && rows[end].flags & CFI_ROW_INIT == 0 {
+                end += 1;
+            }
+
+            let last = &rows[end - 1];
+            let size = last
+                .addr
+                .saturating_add(last.size)
+                .saturating_sub(init_row.address);
+            write!(writer, "STACK CFI INIT {:x} {:x}", init_row.address, size)?;
+            for rule in &init_row.rules {
+                write!(writer, " {}", rule)?;
+            }
+            writeln!(writer)?;
+
+            let mut previous = init_row.rules;
+            for row in &rows[index + 1..end] {
+                let row = self.cfi_row(row).ok_or_else(invalid_cache)?;
+                write!(writer, "STACK CFI {:x}", row.address)?;
+                for rule in row.rules.iter().filter(|rule| !previous.contains(rule)) {
+                    write!(writer, " {}", rule)?;
+                }
+                writeln!(writer)?;
+                previous = row.rules;
+            }
+
+            index = end;
+        }
+
+        for win in self.win_records() {
+            let win = self.win_record(win).ok_or_else(invalid_cache)?;
+            writeln!(writer, "{}", win)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates an IO error for malformed cache files.
+fn invalid_cache() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed cfi cache file")
 }
 
 enum CfiCacheInner<'a> {
     V1(CfiCacheV1<'a>),
+    V2(CfiCacheV2<'a>),
+}
+
+/// An operation of a compiled postfix expression in a STACK record.
+///
+/// Operators take their operands from the top of the evaluation stack and push their result.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CfiOp<'a> {
+    /// Pushes a constant value. Negative constants are stored in two's complement.
+    Const(u64),
+    /// Pushes the value of a register or variable, such as `$rsp` or `.cfa`.
+    Variable(&'a [u8]),
+    /// Adds the two topmost values (`+`).
+    Add,
+    /// Subtracts the topmost value from the value below it (`-`).
+    Sub,
+    /// Multiplies the two topmost values (`*`).
+    Mul,
+    /// Divides the value below the top by the topmost value (`/`).
+    Div,
+    /// Computes the remainder of dividing the value below the top by the topmost value (`%`).
+    Rem,
+    /// Aligns the value below the top down to a multiple of the topmost value (`@`).
+    Align,
+    /// Replaces the topmost value with the pointer-sized value at that address (`^`).
+    Deref,
+    /// Assigns the topmost value to the variable below it (`=`).
+    Assign,
+}
+
+impl<'a> CfiOp<'a> {
+    /// Parses a single token of a postfix expression.
+    ///
+    /// Tokens that are neither operators nor decimal or hexadecimal numbers are variables.
+    pub fn parse(token: &'a [u8]) -> Self {
+        match token {
+            b"+" => CfiOp::Add,
+            b"-" => CfiOp::Sub,
+            b"*" => CfiOp::Mul,
+            b"/" => CfiOp::Div,
+            b"%" => CfiOp::Rem,
+            b"@" => CfiOp::Align,
+            b"^" => CfiOp::Deref,
+            b"=" => CfiOp::Assign,
+            _ => parse_constant(token).map_or(CfiOp::Variable(token), CfiOp::Const),
+        }
+    }
+
+    /// Compiles a postfix expression or program into a sequence of operations.
+    pub fn compile(expression: &'a [u8]) -> Vec<Self> {
+        expression
+            .split(u8::is_ascii_whitespace)
+            .filter(|token| !token.is_empty())
+            .map(CfiOp::parse)
+            .collect()
+    }
+
+    fn kind(&self) -> u32 {
+        match *self {
+            CfiOp::Const(_) => CFI_OP_CONST,
+            CfiOp::Variable(_) => CFI_OP_VARIABLE,
+            CfiOp::Add => CFI_OP_ADD,
+            CfiOp::Sub => CFI_OP_SUB,
+            CfiOp::Mul => CFI_OP_MUL,
+            CfiOp::Div => CFI_OP_DIV,
+            CfiOp::Rem => CFI_OP_REM,
+            CfiOp::Align => CFI_OP_ALIGN,
+            CfiOp::Deref => CFI_OP_DEREF,
+            CfiOp::Assign => CFI_OP_ASSIGN,
+        }
+    }
+}
+
+impl<'a> fmt::Display for CfiOp<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CfiOp::Const(value) => write!(f, "{}", value as i64),
+            CfiOp::Variable(name) => write!(f, "{}", String::from_utf8_lossy(name)),
+            CfiOp::Add => write!(f, "+"),
+            CfiOp::Sub => write!(f, "-"),
+            CfiOp::Mul => write!(f, "*"),
+            CfiOp::Div => write!(f, "/"),
+            CfiOp::Rem => write!(f, "%"),
+            CfiOp::Align => write!(f, "@"),
+            CfiOp::Deref => write!(f, "^"),
+            CfiOp::Assign => write!(f, "="),
+        }
+    }
+}
+
+/// Parses a decimal or `0x` prefixed hexadecimal constant.
+fn parse_constant(token: &[u8]) -> Option<u64> {
+    let token = str::from_utf8(token).ok()?;
+    match token.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => token.parse::<i64>().ok().map(|value| value as u64),
+    }
+}
+
+/// A rule to recover a register of the caller with a compiled expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CfiRule<'a> {
+    /// The name of the register, such as `$rbp`, `.cfa` or `.ra`.
+    pub register: &'a [u8],
+    /// The operations computing the register's value.
+    pub expression: Vec<CfiOp<'a>>,
+}
+
+impl<'a> CfiRule<'a> {
+    /// Compiles the expression of a Breakpad CFI rule.
+    pub fn compile(rule: &BreakpadCfiRule<'a>) -> Self {
+        CfiRule {
+            register: rule.register,
+            expression: CfiOp::compile(rule.expression),
+        }
+    }
+}
+
+impl<'a> fmt::Display for CfiRule<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", String::from_utf8_lossy(self.register))?;
+        for op in &self.expression {
+            write!(f, " {}", op)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A row of a CFI table with all register rules valid for its address range.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CfiRow<'a> {
+    /// The first address covered by this row.
+    pub address: u64,
+    /// The number of bytes covered by this row.
+    pub size: u64,
+    /// Rules to compute the caller's registers, including `.cfa` and `.ra`.
+    pub rules: Vec<CfiRule<'a>>,
+}
+
+impl<'a> CfiRow<'a> {
+    /// The end address of this row, exclusive.
+    pub fn end(&self) -> u64 {
+        self.address.saturating_add(self.size)
+    }
+
+    /// Returns the compiled expression for the given register, if a rule exists.
+    pub fn rule(&self, register: &str) -> Option<&[CfiOp<'a>]> {
+        self.rules
+            .iter()
+            .find(|rule| rule.register == register.as_bytes())
+            .map(|rule| rule.expression.as_slice())
+    }
+}
+
+/// Unwind information for an instruction address.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnwindRule<'a> {
+    /// Register rules from call frame information (STACK CFI).
+    Cfi(CfiRow<'a>),
+    /// Frame data of a Windows x86 function (STACK WIN).
+    Win(BreakpadStackWinRecord<'a>),
 }
 
 /// A cache file for call frame information (CFI).
@@ -420,9 +1494,8 @@ pub struct CfiCache<'a> {
 impl CfiCache<'static> {
     /// Construct a CFI cache from an `Object`.
     pub fn from_object(object: &Object<'_>) -> Result<Self, CfiError> {
-        let buffer = AsciiCfiWriter::transform(object)?;
-        let byteview = ByteView::from_vec(buffer);
-        let inner = CfiCacheInner::V1(CfiCacheV1 { byteview });
+        let buffer: Vec<u8> = AsciiCfiWriter::transform(object)?;
+        let inner = CfiCacheInner::V2(CfiCacheV2::from_ascii(&buffer)?);
         Ok(CfiCache { inner })
     }
 }
@@ -435,26 +1508,89 @@ impl<'a> CfiCache<'a> {
             return Ok(CfiCache { inner });
         }
 
-        Err(CfiErrorKind::BadFileMagic.into())
+        let inner = CfiCacheInner::V2(CfiCacheV2::parse(byteview)?);
+        Ok(CfiCache { inner })
     }
 
     /// Returns the cache file format version.
     pub fn version(&self) -> u32 {
         match self.inner {
             CfiCacheInner::V1(_) => 1,
+            CfiCacheInner::V2(_) => 2,
         }
     }
 
     /// Returns the raw buffer of the cache file.
-    pub fn as_slice(&self) -> &[u8] {
+    ///
+    /// Depending on the version, this is either a binary cache or STACK records in Breakpad ASCII
+    /// format. Use `to_ascii` to obtain STACK records for all versions.
+    pub fn as_bytes(&self) -> &[u8] {
         match self.inner {
             CfiCacheInner::V1(ref v1) => v1.raw(),
+            CfiCacheInner::V2(ref v2) => v2.raw(),
+        }
+    }
+
+    /// Returns the unwind information for the given address.
+    ///
+    /// The address is relative to the image base of the module. Version 1 caches are parsed on
+    /// every call, which is considerably slower than lookups in version 2 caches.
+    pub fn lookup(&self, address: u64) -> Option<UnwindRule<'_>> {
+        match self.inner {
+            CfiCacheInner::V1(ref v1) => v1.lookup(address),
+            CfiCacheInner::V2(ref v2) => v2.lookup(address),
+        }
+    }
+
+    /// Returns the contents of this cache as STACK records in Breakpad ASCII format.
+    ///
+    /// This is the format expected by the Breakpad stackwalker.
+    pub fn to_ascii(&self) -> Result<Cow<'_, [u8]>, io::Error> {
+        match self.inner {
+            CfiCacheInner::V1(ref v1) => Ok(Cow::Borrowed(v1.raw())),
+            CfiCacheInner::V2(ref v2) => {
+                let mut buffer = Vec::new();
+                v2.write_ascii(&mut buffer)?;
+                Ok(Cow::Owned(buffer))
+            }
         }
     }
 
     /// Writes the cache to the given writer.
     pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), io::Error> {
-        io::copy(&mut self.as_slice(), &mut writer)?;
+        io::copy(&mut self.as_bytes(), &mut writer)?;
         Ok(())
     }
 }
+
+#[test]
+fn test_translate_expression() {
+    use gimli::{EndianSlice, LittleEndian};
+
+    let translate = |bytes: &[u8], push_cfa| {
+        let expression = Expression(EndianSlice::new(bytes, LittleEndian));
+        translate_expression(Arch::X86_64, &expression, push_cfa)
+    };
+
+    // DW_OP_breg7 (rsp) 8; DW_OP_deref
+    assert_eq!(translate(&[0x77, 0x08, 0x06], false).unwrap(), "$rsp 8 + ^");
+    // DW_OP_breg6 (rbp) 0; DW_OP_const1s -16; DW_OP_and
+    assert_eq!(
+        translate(&[0x76, 0x00, 0x09, 0xf0, 0x1a], false).unwrap(),
+        "$rbp 16 @"
+    );
+    // DW_OP_plus_uconst 16, evaluated with the CFA on the stack
+    assert_eq!(translate(&[0x23, 0x10], true).unwrap(), ".cfa 16 +");
+    // DW_OP_lit8; DW_OP_minus, evaluated with the CFA on the stack
+    assert_eq!(translate(&[0x38, 0x1c], true).unwrap(), ".cfa 8 -");
+    // DW_OP_breg7 (rsp) 0; DW_OP_lit3; DW_OP_shl
+    assert_eq!(
+        translate(&[0x77, 0x00, 0x33, 0x24], false),
+        Err(CfiDiagnosticKind::UnsupportedOperation("DW_OP_shl".into()))
+    );
+    // DW_OP_plus with a single operand
+    assert_eq!(
+        translate(&[0x77, 0x00, 0x22], false),
+        Err(CfiDiagnosticKind::InvalidExpression)
+    );
+}
@@ -0,0 +1,361 @@
+//! Structured information on the exception that caused a crash.
+//!
+//! Minidumps store exceptions in an OS specific way: Windows records an `NTSTATUS` code with
+//! parameters, Linux and Android record the signal number and its `si_code`, and macOS and iOS
+//! record the Mach exception type and code. `ExceptionInfo` decodes these into an
+//! `ExceptionCode` and exposes the subcode and faulting address uniformly.
+
+use std::fmt;
+
+use crate::parser::{Minidump, MinidumpError, MinidumpException};
+
+macro_rules! exception_codes {
+    (
+        $(#[$attr:meta])*
+        pub enum $name:ident {
+            $($(#[$vattr:meta])* $variant:ident = $code:expr => $string:expr,)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+        pub enum $name {
+            $($(#[$vattr])* $variant,)*
+            /// An exception code not known to this library.
+            Other(u32),
+        }
+
+        impl $name {
+            /// Decodes the raw exception code.
+            pub fn from_code(code: u32) -> Self {
+                match code {
+                    $($code => $name::$variant,)*
+                    other => $name::Other(other),
+                }
+            }
+
+            /// Returns the raw exception code.
+            pub fn code(self) -> u32 {
+                match self {
+                    $($name::$variant => $code,)*
+                    $name::Other(code) => code,
+                }
+            }
+
+            /// Returns the name of the constant that defines this code, if known.
+            pub fn name(self) -> Option<&'static str> {
+                match self {
+                    $($name::$variant => Some($string),)*
+                    $name::Other(_) => None,
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.name() {
+                    Some(name) => write!(f, "{}", name),
+                    None => write!(f, "{:#x}", self.code()),
+                }
+            }
+        }
+    };
+}
+
+exception_codes! {
+    /// Exception codes on Windows (`NTSTATUS`).
+    pub enum WindowsException {
+        /// A misaligned data access.
+        DatatypeMisalignment = 0x8000_0002 => "EXCEPTION_DATATYPE_MISALIGNMENT",
+        /// A breakpoint was hit.
+        Breakpoint = 0x8000_0003 => "EXCEPTION_BREAKPOINT",
+        /// A single instruction was executed in trace mode.
+        SingleStep = 0x8000_0004 => "EXCEPTION_SINGLE_STEP",
+        /// Invalid read, write or execution of memory.
+        AccessViolation = 0xc000_0005 => "EXCEPTION_ACCESS_VIOLATION",
+        /// A page could not be loaded into memory.
+        InPageError = 0xc000_0006 => "EXCEPTION_IN_PAGE_ERROR",
+        /// An invalid handle was used.
+        InvalidHandle = 0xc000_0008 => "EXCEPTION_INVALID_HANDLE",
+        /// An invalid instruction was executed.
+        IllegalInstruction = 0xc000_001d => "EXCEPTION_ILLEGAL_INSTRUCTION",
+        /// Execution continued after a noncontinuable exception.
+        NoncontinuableException = 0xc000_0025 => "EXCEPTION_NONCONTINUABLE_EXCEPTION",
+        /// An exception handler returned an invalid disposition.
+        InvalidDisposition = 0xc000_0026 => "EXCEPTION_INVALID_DISPOSITION",
+        /// An array was accessed out of bounds, on hardware that checks bounds.
+        ArrayBoundsExceeded = 0xc000_008c => "EXCEPTION_ARRAY_BOUNDS_EXCEEDED",
+        /// An operand of a floating point operation is denormal.
+        FloatDenormalOperand = 0xc000_008d => "EXCEPTION_FLT_DENORMAL_OPERAND",
+        /// A floating point value was divided by zero.
+        FloatDivideByZero = 0xc000_008e => "EXCEPTION_FLT_DIVIDE_BY_ZERO",
+        /// The result of a floating point operation cannot be represented exactly.
+        FloatInexactResult = 0xc000_008f => "EXCEPTION_FLT_INEXACT_RESULT",
+        /// Any other floating point exception.
+        FloatInvalidOperation = 0xc000_0090 => "EXCEPTION_FLT_INVALID_OPERATION",
+        /// The exponent of a floating point operation is too large.
+        FloatOverflow = 0xc000_0091 => "EXCEPTION_FLT_OVERFLOW",
+        /// The floating point stack overflowed or underflowed.
+        FloatStackCheck = 0xc000_0092 => "EXCEPTION_FLT_STACK_CHECK",
+        /// The exponent of a floating point operation is too small.
+        FloatUnderflow = 0xc000_0093 => "EXCEPTION_FLT_UNDERFLOW",
+        /// An integer was divided by zero.
+        IntegerDivideByZero = 0xc000_0094 => "EXCEPTION_INT_DIVIDE_BY_ZERO",
+        /// An integer operation overflowed.
+        IntegerOverflow = 0xc000_0095 => "EXCEPTION_INT_OVERFLOW",
+        /// A privileged instruction was executed in user mode.
+        PrivilegedInstruction = 0xc000_0096 => "EXCEPTION_PRIV_INSTRUCTION",
+        /// The thread used up its stack.
+        StackOverflow = 0xc000_00fd => "EXCEPTION_STACK_OVERFLOW",
+        /// The heap was corrupted.
+        HeapCorruption = 0xc000_0374 => "STATUS_HEAP_CORRUPTION",
+        /// A stack based buffer overrun was detected, or `__fastfail` was called.
+        StackBufferOverrun = 0xc000_0409 => "STATUS_STACK_BUFFER_OVERRUN",
+        /// An invalid parameter was passed to a C runtime function.
+        InvalidCRuntimeParameter = 0xc000_0417 => "STATUS_INVALID_CRUNTIME_PARAMETER",
+        /// A fail fast exception was raised.
+        FailFast = 0xc000_0602 => "STATUS_FAIL_FAST_EXCEPTION",
+        /// An uncaught C++ exception thrown by code compiled with MSVC.
+        CppException = 0xe06d_7363 => "EXCEPTION_CPP",
+    }
+}
+
+exception_codes! {
+    /// POSIX signal numbers as used on Linux and Android.
+    pub enum Signal {
+        /// Hangup.
+        Hangup = 1 => "SIGHUP",
+        /// Interrupt from the keyboard.
+        Interrupt = 2 => "SIGINT",
+        /// Quit from the keyboard.
+        Quit = 3 => "SIGQUIT",
+        /// Illegal instruction.
+        IllegalInstruction = 4 => "SIGILL",
+        /// Trace or breakpoint trap.
+        Trap = 5 => "SIGTRAP",
+        /// Abort, usually from `abort()`.
+        Abort = 6 => "SIGABRT",
+        /// Bus error, such as a misaligned memory access.
+        Bus = 7 => "SIGBUS",
+        /// Floating point exception.
+        FloatingPoint = 8 => "SIGFPE",
+        /// Kill signal.
+        Kill = 9 => "SIGKILL",
+        /// User defined signal 1.
+        User1 = 10 => "SIGUSR1",
+        /// Invalid memory reference.
+        SegmentationFault = 11 => "SIGSEGV",
+        /// User defined signal 2.
+        User2 = 12 => "SIGUSR2",
+        /// Write to a pipe without readers.
+        Pipe = 13 => "SIGPIPE",
+        /// Timer signal from `alarm()`.
+        Alarm = 14 => "SIGALRM",
+        /// Termination signal.
+        Terminate = 15 => "SIGTERM",
+        /// Bad system call.
+        BadSystemCall = 31 => "SIGSYS",
+    }
+}
+
+exception_codes! {
+    /// Mach exception types on macOS and iOS.
+    pub enum MachException {
+        /// Invalid memory access, with a `kern_return_t` code.
+        BadAccess = 1 => "EXC_BAD_ACCESS",
+        /// Illegal or undefined instruction.
+        BadInstruction = 2 => "EXC_BAD_INSTRUCTION",
+        /// Arithmetic exception, such as a division by zero.
+        Arithmetic = 3 => "EXC_ARITHMETIC",
+        /// Emulation instruction.
+        Emulation = 4 => "EXC_EMULATION",
+        /// Software generated exception, such as an uncaught signal.
+        Software = 5 => "EXC_SOFTWARE",
+        /// Trace, breakpoint or trap instruction.
+        Breakpoint = 6 => "EXC_BREAKPOINT",
+        /// System call.
+        Syscall = 7 => "EXC_SYSCALL",
+        /// Mach system call.
+        MachSyscall = 8 => "EXC_MACH_SYSCALL",
+        /// RPC alert.
+        RpcAlert = 9 => "EXC_RPC_ALERT",
+        /// Abnormal process exit.
+        Crash = 10 => "EXC_CRASH",
+        /// A resource consumption limit was hit.
+        Resource = 11 => "EXC_RESOURCE",
+        /// A guarded resource was violated.
+        Guard = 12 => "EXC_GUARD",
+    }
+}
+
+/// `kern_return_t` code of `EXC_BAD_ACCESS` for unmapped addresses.
+const KERN_INVALID_ADDRESS: u32 = 1;
+
+/// `EXC_SOFTWARE` code that Breakpad uses for `abort()`.
+const MAC_SOFTWARE_ABORT: u32 = 0x0001_0002;
+
+/// The OS specific code of an exception.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ExceptionCode {
+    /// An `NTSTATUS` exception code on Windows.
+    Windows(WindowsException),
+    /// A POSIX signal on Linux and Android.
+    Signal(Signal),
+    /// A Mach exception on macOS and iOS.
+    Mach(MachException),
+    /// An exception on an unknown operating system.
+    Unknown(u32),
+}
+
+impl ExceptionCode {
+    /// Returns the raw exception code.
+    pub fn code(self) -> u32 {
+        match self {
+            ExceptionCode::Windows(code) => code.code(),
+            ExceptionCode::Signal(code) => code.code(),
+            ExceptionCode::Mach(code) => code.code(),
+            ExceptionCode::Unknown(code) => code,
+        }
+    }
+}
+
+impl fmt::Display for ExceptionCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ExceptionCode::Windows(code) => code.fmt(f),
+            ExceptionCode::Signal(code) => code.fmt(f),
+            ExceptionCode::Mach(code) => code.fmt(f),
+            ExceptionCode::Unknown(code) => write!(f, "{:#x}", code),
+        }
+    }
+}
+
+/// Structured information on the exception that caused a crash.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExceptionInfo {
+    /// The decoded exception code.
+    pub code: ExceptionCode,
+    /// OS specific exception flags as stored in the minidump.
+    pub flags: u32,
+    /// Further detail on the exception code.
+    ///
+    /// This is the `si_code` of signals on Linux, the `kern_return_t` code of Mach exceptions, and
+    /// the kind of access (0 read, 1 write, 8 execute) of access violations on Windows.
+    pub subcode: Option<u64>,
+    /// The memory address that caused the exception.
+    ///
+    /// For invalid memory accesses, this is the address that was accessed. Otherwise, this is the
+    /// address of the instruction that caused the exception.
+    pub address: u64,
+    /// Additional parameters of the exception.
+    pub parameters: Vec<u64>,
+    /// The identifier of the thread that caused the exception.
+    pub thread_id: u32,
+    /// The raw CPU context of the thread at the time of the exception.
+    pub context: Vec<u8>,
+}
+
+impl ExceptionInfo {
+    /// Reads the exception of a minidump, if it contains one.
+    pub fn from_minidump(minidump: &Minidump<'_>) -> Result<Option<Self>, MinidumpError> {
+        let exception = match minidump.exception()? {
+            Some(exception) => exception,
+            None => return Ok(None),
+        };
+
+        let platform_id = minidump.system_info()?.map(|info| info.platform_id);
+        Ok(Some(Self::from_exception(&exception, platform_id)))
+    }
+
+    /// Decodes an exception according to the platform identifier from the system info.
+    pub fn from_exception(exception: &MinidumpException<'_>, platform_id: Option<u32>) -> Self {
+        let mut subcode = None;
+        let mut address = exception.address;
+
+        let code = match platform_id {
+            Some(0..=3) => {
+                let code = WindowsException::from_code(exception.code);
+                match code {
+                    WindowsException::AccessViolation | WindowsException::InPageError => {
+                        subcode = exception.parameters.first().cloned();
+                        if let Some(&fault_address) = exception.parameters.get(1) {
+                            address = fault_address;
+                        }
+                    }
+                    _ => (),
+                }
+                ExceptionCode::Windows(code)
+            }
+            Some(0x8101) | Some(0x8102) => {
+                subcode = Some(u64::from(exception.flags));
+                ExceptionCode::Mach(MachException::from_code(exception.code))
+            }
+            Some(0x8201) | Some(0x8203) | Some(0x8206) => {
+                subcode = Some(u64::from(exception.flags));
+                ExceptionCode::Signal(Signal::from_code(exception.code))
+            }
+            _ => ExceptionCode::Unknown(exception.code),
+        };
+
+        ExceptionInfo {
+            code,
+            flags: exception.flags,
+            subcode,
+            address,
+            parameters: exception.parameters.clone(),
+            thread_id: exception.thread_id,
+            context: exception.context.to_vec(),
+        }
+    }
+
+    /// Returns the number of the POSIX signal that corresponds to this exception.
+    ///
+    /// Signals are numbered as on Darwin and BSD, which is what
+    /// `symbolic_symcache::InstructionInfo::signal` expects. Only signals raised by faulting
+    /// instructions and aborts are reported.
+    pub fn signal(&self) -> Option<u32> {
+        let signal = match self.code {
+            ExceptionCode::Signal(signal) => signal,
+            ExceptionCode::Mach(exception) => match exception {
+                MachException::BadAccess if self.flags == KERN_INVALID_ADDRESS => {
+                    Signal::SegmentationFault
+                }
+                MachException::BadAccess => Signal::Bus,
+                MachException::BadInstruction => Signal::IllegalInstruction,
+                MachException::Arithmetic => Signal::FloatingPoint,
+                MachException::Breakpoint => Signal::Trap,
+                MachException::Software if self.flags == MAC_SOFTWARE_ABORT => Signal::Abort,
+                _ => return None,
+            },
+            ExceptionCode::Windows(exception) => match exception {
+                WindowsException::AccessViolation | WindowsException::StackOverflow => {
+                    Signal::SegmentationFault
+                }
+                WindowsException::DatatypeMisalignment | WindowsException::InPageError => {
+                    Signal::Bus
+                }
+                WindowsException::IllegalInstruction | WindowsException::PrivilegedInstruction => {
+                    Signal::IllegalInstruction
+                }
+                WindowsException::IntegerDivideByZero
+                | WindowsException::IntegerOverflow
+                | WindowsException::FloatDivideByZero
+                | WindowsException::FloatInvalidOperation
+                | WindowsException::FloatOverflow
+                | WindowsException::FloatUnderflow => Signal::FloatingPoint,
+                WindowsException::Breakpoint | WindowsException::SingleStep => Signal::Trap,
+                _ => return None,
+            },
+            ExceptionCode::Unknown(_) => return None,
+        };
+
+        match signal {
+            Signal::IllegalInstruction => Some(4),
+            Signal::Trap => Some(5),
+            Signal::Abort => Some(6),
+            Signal::FloatingPoint => Some(8),
+            Signal::Bus => Some(10),
+            Signal::SegmentationFault => Some(11),
+            _ => None,
+        }
+    }
+}
@@ -1,5 +1,12 @@
 //! Provides minidump support.
 pub mod cfi;
+pub mod exception;
+pub mod linux;
+pub mod memory;
+pub mod parser;
+#[cfg(feature = "processor")]
 pub mod processor;
 mod registers;
+pub mod stackwalker;
+#[cfg(feature = "processor")]
 mod utils;
@@ -0,0 +1,355 @@
+//! Linux specific information written by Breakpad's Linux client.
+//!
+//! Besides the standard streams, minidumps of Linux processes contain verbatim copies of files
+//! from `/proc` and `/etc` at the time of the crash: memory mappings, process status, the command
+//! line, environment and auxiliary vector of the process, as well as CPU and distribution
+//! information. `LinuxInfo` parses these into typed values.
+//!
+//! **Example:**
+//!
+//! ```
+//! # use symbolic_minidump::linux::LinuxInfo;
+//! # use symbolic_minidump::parser::Minidump;
+//! # use symbolic_testutils::fixture_path;
+//! # fn main() -> Result<(), failure::Error> {
+//! let data = std::fs::read(fixture_path("linux/mini.dmp"))?;
+//! let minidump = Minidump::parse(&data)?;
+//!
+//! if let Some(info) = LinuxInfo::from_minidump(&minidump)? {
+//!     println!("command line: {:?}", info.cmdline);
+//!     for entry in &info.maps {
+//!         println!("{:#x}-{:#x} {}", entry.start, entry.end, entry.permissions);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::parser::{Minidump, MinidumpError, StreamType};
+
+/// Access permissions of a memory mapping.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct LinuxPermissions {
+    /// The mapping can be read.
+    pub read: bool,
+    /// The mapping can be written.
+    pub write: bool,
+    /// The mapping can be executed.
+    pub execute: bool,
+    /// The mapping is shared with other processes, rather than private (copy on write).
+    pub shared: bool,
+}
+
+impl LinuxPermissions {
+    /// Parses permissions in the format of `/proc/self/maps`, such as `r-xp`.
+    pub fn parse(string: &str) -> Option<Self> {
+        let bytes = string.as_bytes();
+        if bytes.len() != 4 {
+            return None;
+        }
+
+        Some(LinuxPermissions {
+            read: bytes[0] == b'r',
+            write: bytes[1] == b'w',
+            execute: bytes[2] == b'x',
+            shared: bytes[3] == b's',
+        })
+    }
+}
+
+impl fmt::Display for LinuxPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            if self.read { 'r' } else { '-' },
+            if self.write { 'w' } else { '-' },
+            if self.execute { 'x' } else { '-' },
+            if self.shared { 's' } else { 'p' },
+        )
+    }
+}
+
+/// A memory mapping of the crashed process (a line of `/proc/self/maps`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LinuxMapsEntry {
+    /// The start address of the mapping.
+    pub start: u64,
+    /// The end address of the mapping, exclusive.
+    pub end: u64,
+    /// Access permissions of the mapping.
+    pub permissions: LinuxPermissions,
+    /// The offset of the mapping in the mapped file.
+    pub offset: u64,
+    /// The major number of the device containing the mapped file.
+    pub device_major: u32,
+    /// The minor number of the device containing the mapped file.
+    pub device_minor: u32,
+    /// The inode of the mapped file, or zero for anonymous mappings.
+    pub inode: u64,
+    /// The path of the mapped file or a pseudo-path like `[stack]`, if any.
+    pub pathname: Option<String>,
+}
+
+impl LinuxMapsEntry {
+    /// Parses a single line of `/proc/self/maps`.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut rest = line;
+        let mut next = || {
+            let trimmed = rest.trim_start();
+            let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            let (token, remainder) = trimmed.split_at(end);
+            rest = remainder;
+            token
+        };
+
+        let mut range = next().splitn(2, '-');
+        let start = u64::from_str_radix(range.next()?, 16).ok()?;
+        let end = u64::from_str_radix(range.next()?, 16).ok()?;
+        let permissions = LinuxPermissions::parse(next())?;
+        let offset = u64::from_str_radix(next(), 16).ok()?;
+
+        let mut device = next().splitn(2, ':');
+        let device_major = u32::from_str_radix(device.next()?, 16).ok()?;
+        let device_minor = u32::from_str_radix(device.next()?, 16).ok()?;
+        let inode = next().parse().ok()?;
+
+        // the path is the remainder of the line and may contain whitespace
+        let pathname = match rest.trim() {
+            "" => None,
+            path => Some(path.to_string()),
+        };
+
+        Some(LinuxMapsEntry {
+            start,
+            end,
+            permissions,
+            offset,
+            device_major,
+            device_minor,
+            inode,
+            pathname,
+        })
+    }
+
+    /// The size of the mapping in bytes.
+    pub fn size(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Determines whether the given address lies within this mapping.
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.start && address < self.end
+    }
+}
+
+/// Information about the Linux distribution, from `/etc/lsb-release` or `/etc/os-release`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LinuxLsbRelease {
+    /// The distributor identifier, such as `Ubuntu`.
+    pub id: Option<String>,
+    /// The release number of the distribution, such as `16.04`.
+    pub release: Option<String>,
+    /// The code name of the release, such as `xenial`.
+    pub codename: Option<String>,
+    /// A human readable description of the distribution and release.
+    pub description: Option<String>,
+}
+
+impl LinuxLsbRelease {
+    /// Parses the contents of `/etc/lsb-release`.
+    ///
+    /// Breakpad falls back to `/etc/os-release` on systems without LSB information, whose keys
+    /// are used for values missing in the LSB format.
+    pub fn parse(string: &str) -> Self {
+        let values: BTreeMap<_, _> = string
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(2, '=');
+                let key = parts.next()?.trim();
+                let value = parts.next()?.trim().trim_matches('"');
+                Some((key, value))
+            })
+            .collect();
+
+        let get = |keys: &[&str]| {
+            keys.iter()
+                .filter_map(|key| values.get(key))
+                .find(|value| !value.is_empty())
+                .map(|value| value.to_string())
+        };
+
+        LinuxLsbRelease {
+            id: get(&["DISTRIB_ID", "NAME", "ID"]),
+            release: get(&["DISTRIB_RELEASE", "VERSION_ID"]),
+            codename: get(&["DISTRIB_CODENAME", "VERSION_CODENAME"]),
+            description: get(&["DISTRIB_DESCRIPTION", "PRETTY_NAME"]),
+        }
+    }
+}
+
+/// Linux specific information about the crashed process and its system.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LinuxInfo {
+    /// All memory mappings of the process (`/proc/self/maps`).
+    pub maps: Vec<LinuxMapsEntry>,
+    /// Status values of the process by name, such as `Pid` or `VmRSS` (`/proc/self/status`).
+    pub status: BTreeMap<String, String>,
+    /// The command line arguments of the process (`/proc/self/cmdline`).
+    pub cmdline: Vec<String>,
+    /// The environment variables of the process (`/proc/self/environ`).
+    pub environ: BTreeMap<String, String>,
+    /// Pairs of type and value of the auxiliary vector (`/proc/self/auxv`).
+    pub auxv: Vec<(u64, u64)>,
+    /// Information about the Linux distribution, if available.
+    pub lsb_release: Option<LinuxLsbRelease>,
+    /// Key-value pairs for every processor of the system (`/proc/cpuinfo`).
+    pub cpu_info: Vec<BTreeMap<String, String>>,
+}
+
+impl LinuxInfo {
+    /// Reads the Linux specific streams of a minidump.
+    ///
+    /// Returns `None` if the minidump contains none of these streams, for instance because it was
+    /// written on another platform.
+    pub fn from_minidump(minidump: &Minidump<'_>) -> Result<Option<Self>, MinidumpError> {
+        let stream = |stream_type| -> Result<Option<String>, MinidumpError> {
+            Ok(minidump
+                .raw_stream(stream_type)?
+                .map(|data| String::from_utf8_lossy(data).into_owned()))
+        };
+
+        let maps = stream(StreamType::LinuxMaps)?;
+        let status = stream(StreamType::LinuxProcStatus)?;
+        let cmdline = stream(StreamType::LinuxCmdLine)?;
+        let environ = stream(StreamType::LinuxEnviron)?;
+        let lsb_release = stream(StreamType::LinuxLsbRelease)?;
+        let cpu_info = stream(StreamType::LinuxCpuInfo)?;
+        let auxv = minidump.raw_stream(StreamType::LinuxAuxv)?;
+
+        if maps.is_none()
+            && status.is_none()
+            && cmdline.is_none()
+            && environ.is_none()
+            && lsb_release.is_none()
+            && cpu_info.is_none()
+            && auxv.is_none()
+        {
+            return Ok(None);
+        }
+
+        // The auxiliary vector consists of native words, whose size is given by the CPU.
+        let word_size = minidump
+            .system_info()?
+            .and_then(|info| info.arch().pointer_size())
+            .unwrap_or(8);
+
+        Ok(Some(LinuxInfo {
+            maps: maps.as_ref().map_or_else(Vec::new, |s| parse_maps(s)),
+            status: status
+                .as_ref()
+                .map_or_else(BTreeMap::new, |s| parse_status(s)),
+            cmdline: cmdline.as_ref().map_or_else(Vec::new, |s| parse_cmdline(s)),
+            environ: environ
+                .as_ref()
+                .map_or_else(BTreeMap::new, |s| parse_environ(s)),
+            auxv: auxv.map_or_else(Vec::new, |data| parse_auxv(data, word_size)),
+            lsb_release: lsb_release.as_ref().map(|s| LinuxLsbRelease::parse(s)),
+            cpu_info: cpu_info
+                .as_ref()
+                .map_or_else(Vec::new, |s| parse_cpu_info(s)),
+        }))
+    }
+
+    /// Returns the memory mapping containing the given address, if any.
+    pub fn find_mapping(&self, address: u64) -> Option<&LinuxMapsEntry> {
+        self.maps.iter().find(|entry| entry.contains(address))
+    }
+}
+
+/// Parses all lines of `/proc/self/maps`, skipping malformed lines.
+fn parse_maps(string: &str) -> Vec<LinuxMapsEntry> {
+    string.lines().filter_map(LinuxMapsEntry::parse).collect()
+}
+
+/// Parses lines of `Key:\tValue` pairs in `/proc/self/status`.
+fn parse_status(string: &str) -> BTreeMap<String, String> {
+    string
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, ':');
+            let key = parts.next()?.trim();
+            let value = parts.next()?.trim();
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Parses the NUL-separated arguments of `/proc/self/cmdline`.
+fn parse_cmdline(string: &str) -> Vec<String> {
+    let string = string.trim_end_matches('\0');
+    if string.is_empty() {
+        return Vec::new();
+    }
+
+    string.split('\0').map(str::to_string).collect()
+}
+
+/// Parses the NUL-separated `KEY=VALUE` pairs of `/proc/self/environ`.
+fn parse_environ(string: &str) -> BTreeMap<String, String> {
+    string
+        .split('\0')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let mut parts = entry.splitn(2, '=');
+            let key = parts.next().unwrap_or_default();
+            let value = parts.next().unwrap_or_default();
+            (key.to_string(), value.to_string())
+        })
+        .collect()
+}
+
+/// Parses the pairs of native words in `/proc/self/auxv` up to the terminating `AT_NULL`.
+fn parse_auxv(data: &[u8], word_size: usize) -> Vec<(u64, u64)> {
+    let read_word = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .rev()
+            .fold(0u64, |value, &byte| value << 8 | u64::from(byte))
+    };
+
+    data.chunks_exact(word_size * 2)
+        .map(|pair| (read_word(&pair[..word_size]), read_word(&pair[word_size..])))
+        .take_while(|&(key, _)| key != 0)
+        .collect()
+}
+
+/// Parses the blocks of `key : value` lines for every processor in `/proc/cpuinfo`.
+fn parse_cpu_info(string: &str) -> Vec<BTreeMap<String, String>> {
+    let mut processors = Vec::new();
+    let mut current = BTreeMap::new();
+
+    for line in string.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                processors.push(current);
+                current = BTreeMap::new();
+            }
+            continue;
+        }
+
+        let mut parts = line.splitn(2, ':');
+        if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+            current.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    if !current.is_empty() {
+        processors.push(current);
+    }
+
+    processors
+}
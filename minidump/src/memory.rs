@@ -0,0 +1,402 @@
+//! Access to the memory of the crashed process captured in a minidump.
+//!
+//! Minidumps store captured memory in two streams: The memory list contains thread stacks and
+//! memory around the crashing instruction, and the 64-bit memory list contains all memory of full
+//! dumps. `MemoryList` combines both and allows to read memory by address. Windows minidumps also
+//! describe the layout of the address space, which is exposed as a list of `MemoryInfo`.
+//!
+//! **Example:**
+//!
+//! ```
+//! # use symbolic_minidump::memory::MemoryList;
+//! # use symbolic_minidump::parser::Minidump;
+//! # use symbolic_testutils::fixture_path;
+//! # fn main() -> Result<(), failure::Error> {
+//! let data = std::fs::read(fixture_path("linux/mini.dmp"))?;
+//! let minidump = Minidump::parse(&data)?;
+//! let memory = MemoryList::from_minidump(&minidump)?;
+//!
+//! if let Some(window) = memory.hex_window(0x7fff_5ae4_cd60, 64) {
+//!     println!("{}", window);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::parser::{Minidump, MinidumpError, MinidumpMemoryInfo};
+
+/// Number of bytes rendered per line of a `HexWindow`.
+const HEX_WINDOW_WIDTH: u64 = 16;
+
+const PAGE_NOACCESS: u32 = 0x01;
+const PAGE_READONLY: u32 = 0x02;
+const PAGE_READWRITE: u32 = 0x04;
+const PAGE_WRITECOPY: u32 = 0x08;
+const PAGE_EXECUTE: u32 = 0x10;
+const PAGE_EXECUTE_READ: u32 = 0x20;
+const PAGE_EXECUTE_READWRITE: u32 = 0x40;
+const PAGE_EXECUTE_WRITECOPY: u32 = 0x80;
+const PAGE_GUARD: u32 = 0x100;
+
+/// A range of memory captured in the minidump.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemoryRegion<'data> {
+    /// The address of the first byte of this region in the crashed process.
+    pub base_address: u64,
+    /// The captured contents of the region.
+    pub data: Cow<'data, [u8]>,
+}
+
+impl<'data> MemoryRegion<'data> {
+    /// The number of captured bytes.
+    pub fn size(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    /// The end address of this region, exclusive.
+    pub fn end(&self) -> u64 {
+        self.base_address.saturating_add(self.size())
+    }
+
+    /// Determines whether the given address lies within this region.
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.base_address && address < self.end()
+    }
+
+    /// Copies the contents of this region, if they are borrowed from the minidump.
+    pub fn into_owned(self) -> MemoryRegion<'static> {
+        MemoryRegion {
+            base_address: self.base_address,
+            data: Cow::Owned(self.data.into_owned()),
+        }
+    }
+}
+
+/// All memory captured in a minidump, sorted by address.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MemoryList<'data> {
+    regions: Vec<MemoryRegion<'data>>,
+}
+
+impl<'data> MemoryList<'data> {
+    /// Reads the memory list and the 64-bit memory list of a minidump.
+    ///
+    /// Memory ranges without contents are skipped.
+    pub fn from_minidump(minidump: &Minidump<'data>) -> Result<Self, MinidumpError> {
+        let mut memory = minidump.memory_list()?;
+        memory.extend(minidump.memory64_list()?);
+
+        let mut regions: Vec<_> = memory
+            .into_iter()
+            .filter(|memory| !memory.data.is_empty())
+            .map(|memory| MemoryRegion {
+                base_address: memory.base_address,
+                data: Cow::Borrowed(memory.data),
+            })
+            .collect();
+
+        regions.sort_by_key(|region| region.base_address);
+        Ok(MemoryList { regions })
+    }
+
+    /// Copies all captured memory, if it is borrowed from the minidump.
+    pub fn into_owned(self) -> MemoryList<'static> {
+        MemoryList {
+            regions: self
+                .regions
+                .into_iter()
+                .map(MemoryRegion::into_owned)
+                .collect(),
+        }
+    }
+
+    /// Returns all captured memory regions, sorted by their base address.
+    pub fn regions(&self) -> &[MemoryRegion<'data>] {
+        &self.regions
+    }
+
+    /// Returns the region containing the given address, if it was captured.
+    pub fn find_region(&self, address: u64) -> Option<&MemoryRegion<'data>> {
+        self.regions.iter().find(|region| region.contains(address))
+    }
+
+    /// Reads `size` bytes of captured memory starting at `address`.
+    ///
+    /// Returns `None` unless the entire range was captured in a single region.
+    pub fn read(&self, address: u64, size: u64) -> Option<&[u8]> {
+        let region = self.find_region(address)?;
+        let start = (address - region.base_address) as usize;
+        let end = start.checked_add(size as usize)?;
+        region.data.get(start..end)
+    }
+
+    /// Reads a little-endian 32-bit value at the given address.
+    pub fn read_u32(&self, address: u64) -> Option<u32> {
+        let bytes = self.read(address, 4)?;
+        Some((0..4).fold(0, |value, i| value | u32::from(bytes[i]) << (i * 8)))
+    }
+
+    /// Reads a little-endian 64-bit value at the given address.
+    pub fn read_u64(&self, address: u64) -> Option<u64> {
+        let bytes = self.read(address, 8)?;
+        Some((0..8).fold(0, |value, i| value | u64::from(bytes[i]) << (i * 8)))
+    }
+
+    /// Returns a window of captured memory around the given address for rendering.
+    ///
+    /// The window covers up to `context` bytes before and after the address, aligned to full
+    /// lines of 16 bytes. It is clipped to the region containing the address. Returns `None` if
+    /// the address was not captured.
+    pub fn hex_window(&self, address: u64, context: u64) -> Option<HexWindow<'_>> {
+        let region = self.find_region(address)?;
+
+        let start = address.saturating_sub(context) / HEX_WINDOW_WIDTH * HEX_WINDOW_WIDTH;
+        let start = start.max(region.base_address);
+        let end = (address.saturating_add(context) / HEX_WINDOW_WIDTH + 1)
+            .saturating_mul(HEX_WINDOW_WIDTH);
+        let end = end.min(region.end());
+
+        let offset = (start - region.base_address) as usize;
+        let len = (end - start) as usize;
+
+        Some(HexWindow {
+            address,
+            base_address: start,
+            data: &region.data[offset..offset + len],
+        })
+    }
+}
+
+/// A window of captured memory around an address of interest.
+///
+/// The `Display` implementation renders a hex dump with 16 bytes per line, followed by their
+/// printable ASCII characters. The line containing the address is marked with `=>`:
+///
+/// ```text
+///    0x00007fff5ae4cd50  70 6d 3d 30 31 3b 33 35 3a 2a 2e 74 69 66 3d 30  pm=01;35:*.tif=0
+/// => 0x00007fff5ae4cd60  31 3b 33 35 3a 2a 2e 74 69 66 66 3d 30 31 3b 33  1;35:*.tiff=01;3
+///    0x00007fff5ae4cd70  35 3a 2a 2e 70 6e 67 3d 30 31 3b 33 35 3a 2a 2e  5:*.png=01;35:*.
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HexWindow<'a> {
+    /// The address of interest within this window.
+    pub address: u64,
+    /// The address of the first byte in this window.
+    pub base_address: u64,
+    /// The captured contents of the window.
+    pub data: &'a [u8],
+}
+
+impl<'a> HexWindow<'a> {
+    /// The end address of this window, exclusive.
+    pub fn end(&self) -> u64 {
+        self.base_address.saturating_add(self.data.len() as u64)
+    }
+}
+
+impl<'a> fmt::Display for HexWindow<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // lines are aligned, so the first line is incomplete if the window starts unaligned
+        let mut line = self.base_address / HEX_WINDOW_WIDTH * HEX_WINDOW_WIDTH;
+
+        while line < self.end() {
+            let marker = if self.address >= line && self.address < line + HEX_WINDOW_WIDTH {
+                "=>"
+            } else {
+                "  "
+            };
+            write!(f, "{} {:#018x} ", marker, line)?;
+
+            let mut ascii = String::with_capacity(HEX_WINDOW_WIDTH as usize);
+            for address in line..line + HEX_WINDOW_WIDTH {
+                if address < self.base_address {
+                    write!(f, "   ")?;
+                    ascii.push(' ');
+                    continue;
+                } else if address >= self.end() {
+                    write!(f, "   ")?;
+                    continue;
+                }
+
+                let byte = self.data[(address - self.base_address) as usize];
+                write!(f, " {:02x}", byte)?;
+                ascii.push(if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                });
+            }
+
+            writeln!(f, "  {}", ascii)?;
+            line += HEX_WINDOW_WIDTH;
+        }
+
+        Ok(())
+    }
+}
+
+/// The state of pages in a region of memory.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum MemoryState {
+    /// Pages with physical storage (`MEM_COMMIT`).
+    Commit,
+    /// Reserved pages without physical storage (`MEM_RESERVE`).
+    Reserve,
+    /// Free pages that are not accessible (`MEM_FREE`).
+    Free,
+    /// A state not known to this library.
+    Other(u32),
+}
+
+impl MemoryState {
+    /// Decodes the raw memory state.
+    pub fn from_raw(state: u32) -> Self {
+        match state {
+            0x1000 => MemoryState::Commit,
+            0x2000 => MemoryState::Reserve,
+            0x1_0000 => MemoryState::Free,
+            other => MemoryState::Other(other),
+        }
+    }
+}
+
+/// The type of pages in a region of memory.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum MemoryType {
+    /// Pages mapped into the view of an executable image (`MEM_IMAGE`).
+    Image,
+    /// Pages mapped into the view of a section, such as a file (`MEM_MAPPED`).
+    Mapped,
+    /// Private pages of the process (`MEM_PRIVATE`).
+    Private,
+    /// A type not known to this library, including free regions without type.
+    Other(u32),
+}
+
+impl MemoryType {
+    /// Decodes the raw memory type.
+    pub fn from_raw(memory_type: u32) -> Self {
+        match memory_type {
+            0x100_0000 => MemoryType::Image,
+            0x4_0000 => MemoryType::Mapped,
+            0x2_0000 => MemoryType::Private,
+            other => MemoryType::Other(other),
+        }
+    }
+}
+
+/// Access protection of pages in a region of memory (`PAGE_*` constants).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct MemoryProtection(pub u32);
+
+impl MemoryProtection {
+    /// Determines whether the pages can be read.
+    pub fn is_readable(self) -> bool {
+        self.0
+            & (PAGE_READONLY
+                | PAGE_READWRITE
+                | PAGE_WRITECOPY
+                | PAGE_EXECUTE_READ
+                | PAGE_EXECUTE_READWRITE
+                | PAGE_EXECUTE_WRITECOPY)
+            != 0
+    }
+
+    /// Determines whether the pages can be written, possibly with copy-on-write.
+    pub fn is_writable(self) -> bool {
+        self.0 & (PAGE_READWRITE | PAGE_WRITECOPY | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY)
+            != 0
+    }
+
+    /// Determines whether the pages can be executed.
+    pub fn is_executable(self) -> bool {
+        self.0
+            & (PAGE_EXECUTE | PAGE_EXECUTE_READ | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY)
+            != 0
+    }
+
+    /// Determines whether the pages are guard pages, which raise an exception on first access.
+    pub fn is_guard(self) -> bool {
+        self.0 & PAGE_GUARD != 0
+    }
+
+    /// Determines whether all access to the pages is disabled.
+    pub fn is_no_access(self) -> bool {
+        self.0 & PAGE_NOACCESS != 0
+    }
+}
+
+impl fmt::Display for MemoryProtection {
+    /// Formats the protection like `r-x`, followed by `g` for guard pages.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            if self.is_readable() { 'r' } else { '-' },
+            if self.is_writable() { 'w' } else { '-' },
+            if self.is_executable() { 'x' } else { '-' },
+        )?;
+
+        if self.is_guard() {
+            write!(f, "g")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Information about a region of virtual memory in the crashed process.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MemoryInfo {
+    /// The address of the first byte of this region.
+    pub base_address: u64,
+    /// The size of this region in bytes.
+    pub region_size: u64,
+    /// The base address of the allocation containing this region.
+    pub allocation_base: u64,
+    /// The protection of the allocation when it was first created.
+    pub allocation_protection: MemoryProtection,
+    /// The state of the pages in this region.
+    pub state: MemoryState,
+    /// The current protection of the pages in this region.
+    pub protection: MemoryProtection,
+    /// The type of the pages in this region.
+    pub memory_type: MemoryType,
+}
+
+impl MemoryInfo {
+    /// Reads the memory info list of a minidump, if it contains one.
+    pub fn from_minidump(minidump: &Minidump<'_>) -> Result<Vec<Self>, MinidumpError> {
+        Ok(minidump
+            .memory_info_list()?
+            .iter()
+            .map(Self::from_memory_info)
+            .collect())
+    }
+
+    /// Decodes the raw memory info of a minidump.
+    pub fn from_memory_info(info: &MinidumpMemoryInfo) -> Self {
+        MemoryInfo {
+            base_address: info.base_address,
+            region_size: info.region_size,
+            allocation_base: info.allocation_base,
+            allocation_protection: MemoryProtection(info.allocation_protection),
+            state: MemoryState::from_raw(info.state),
+            protection: MemoryProtection(info.protection),
+            memory_type: MemoryType::from_raw(info.memory_type),
+        }
+    }
+
+    /// The end address of this region, exclusive.
+    pub fn end(&self) -> u64 {
+        self.base_address.saturating_add(self.region_size)
+    }
+
+    /// Determines whether the given address lies within this region.
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.base_address && address < self.end()
+    }
+}
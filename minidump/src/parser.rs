@@ -0,0 +1,1668 @@
+//! A parser for Minidump files written in pure Rust.
+//!
+//! Unlike the `processor`, this module does not require the Breakpad processor and gives access to
+//! the raw contents of all streams in a minidump. It does not perform any stack walking.
+//!
+//! **Example:**
+//!
+//! ```
+//! # use symbolic_minidump::parser::Minidump;
+//! # use symbolic_testutils::fixture_path;
+//! # fn main() -> Result<(), failure::Error> {
+//! let data = std::fs::read(fixture_path("linux/mini.dmp"))?;
+//! let minidump = Minidump::parse(&data)?;
+//!
+//! for module in minidump.modules()? {
+//!     println!("{:#x} {}", module.base_address, module.code_file());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str;
+
+use failure::{Backtrace, Context, Fail};
+use uuid::Uuid;
+
+use symbolic_common::types::{Arch, CpuFamily, DebugId};
+
+/// Magic bytes at the start of every minidump file.
+const MINIDUMP_SIGNATURE: &[u8; 4] = b"MDMP";
+
+/// The version of the minidump format, stored in the low word of the header version.
+const MINIDUMP_VERSION: u32 = 0xa793;
+
+/// Size of the minidump header in bytes.
+const HEADER_SIZE: usize = 32;
+
+/// Size of a `MINIDUMP_THREAD` record in bytes.
+const THREAD_SIZE: usize = 48;
+
+/// Size of a `MINIDUMP_MODULE` record in bytes.
+const MODULE_SIZE: usize = 108;
+
+/// Size of a `MINIDUMP_MEMORY_DESCRIPTOR` record in bytes.
+const MEMORY_DESCRIPTOR_SIZE: usize = 16;
+
+/// Size of a `MINIDUMP_MEMORY_INFO` entry.
+const MEMORY_INFO_SIZE: usize = 48;
+
+/// Size of a `MINIDUMP_THREAD_INFO` entry.
+const THREAD_INFO_SIZE: usize = 64;
+
+/// Size of a `MINIDUMP_THREAD_NAME` entry, which is packed to four bytes.
+const THREAD_NAME_SIZE: usize = 12;
+
+/// Maximum number of parameters in a `MINIDUMP_EXCEPTION` record.
+const EXCEPTION_MAXIMUM_PARAMETERS: usize = 15;
+
+/// Signature of a `VS_FIXEDFILEINFO` structure.
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xfeef_04bd;
+
+/// CodeView signature of PDB 7.0 records (`RSDS`).
+const CV_SIGNATURE_PDB70: u32 = 0x5344_5352;
+
+/// CodeView signature of PDB 2.0 records (`NB10`).
+const CV_SIGNATURE_PDB20: u32 = 0x3031_424e;
+
+/// CodeView signature of Breakpad ELF build id records (`BpEL`).
+const CV_SIGNATURE_ELF: u32 = 0x4270_454c;
+
+const MISC_INFO_PROCESS_ID: u32 = 0x0001;
+const MISC_INFO_PROCESS_TIMES: u32 = 0x0002;
+const MISC_INFO_PROCESSOR_POWER_INFO: u32 = 0x0004;
+const MISC_INFO_PROCESS_INTEGRITY: u32 = 0x0010;
+const MISC_INFO_PROCESS_EXECUTE_FLAGS: u32 = 0x0020;
+const MISC_INFO_TIMEZONE: u32 = 0x0040;
+const MISC_INFO_PROTECTED_PROCESS: u32 = 0x0080;
+const MISC_INFO_BUILDSTRING: u32 = 0x0100;
+
+/// Size of a `MinidumpModuleCrashpadInfoLink` entry.
+const CRASHPAD_MODULE_LINK_SIZE: usize = 12;
+
+/// Size of a `MinidumpSimpleStringDictionaryEntry`.
+const CRASHPAD_DICTIONARY_ENTRY_SIZE: usize = 8;
+
+/// Size of a `MinidumpAnnotation` entry.
+const CRASHPAD_ANNOTATION_SIZE: usize = 12;
+
+/// Type of Crashpad annotation objects holding a UTF-8 string.
+pub const CRASHPAD_ANNOTATION_STRING: u16 = 1;
+
+/// Prefix of Crashpad annotations carrying the name of a thread, followed by its identifier.
+pub const CRASHPAD_THREAD_NAME_PREFIX: &str = "thread_name:";
+
+/// Possible error kinds of `MinidumpError`.
+#[derive(Debug, Fail, Copy, Clone, Eq, PartialEq)]
+pub enum MinidumpErrorKind {
+    /// The file does not start with the minidump signature.
+    #[fail(display = "bad minidump magic")]
+    BadFileMagic,
+
+    /// The minidump format version is not supported.
+    #[fail(display = "unsupported minidump version")]
+    UnsupportedVersion,
+
+    /// The header or stream directory is truncated or out of bounds.
+    #[fail(display = "malformed minidump header")]
+    BadHeader,
+
+    /// A stream is truncated or references data outside of the file.
+    #[fail(display = "malformed minidump stream")]
+    BadStream,
+}
+
+/// An error returned when parsing a `Minidump`.
+///
+/// This error contains a context with stack traces and an error cause.
+#[derive(Debug)]
+pub struct MinidumpError {
+    inner: Context<MinidumpErrorKind>,
+}
+
+impl Fail for MinidumpError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.inner.cause()
+    }
+
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.inner.backtrace()
+    }
+}
+
+impl fmt::Display for MinidumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl MinidumpError {
+    pub fn kind(&self) -> MinidumpErrorKind {
+        *self.inner.get_context()
+    }
+}
+
+impl From<MinidumpErrorKind> for MinidumpError {
+    fn from(kind: MinidumpErrorKind) -> MinidumpError {
+        MinidumpError {
+            inner: Context::new(kind),
+        }
+    }
+}
+
+impl From<Context<MinidumpErrorKind>> for MinidumpError {
+    fn from(inner: Context<MinidumpErrorKind>) -> MinidumpError {
+        MinidumpError { inner }
+    }
+}
+
+/// Reads little endian values from a stream of a minidump.
+#[derive(Clone, Debug)]
+struct StreamReader<'data> {
+    data: &'data [u8],
+    offset: usize,
+}
+
+impl<'data> StreamReader<'data> {
+    fn new(data: &'data [u8]) -> Self {
+        StreamReader { data, offset: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'data [u8], MinidumpError> {
+        let start = self.offset;
+        let end = start
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or(MinidumpErrorKind::BadStream)?;
+
+        self.offset = end;
+        Ok(&self.data[start..end])
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), MinidumpError> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, MinidumpError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, MinidumpError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from(bytes[0]) | u16::from(bytes[1]) << 8)
+    }
+
+    fn u32(&mut self) -> Result<u32, MinidumpError> {
+        let low = self.u16()?;
+        let high = self.u16()?;
+        Ok(u32::from(low) | u32::from(high) << 16)
+    }
+
+    fn u64(&mut self) -> Result<u64, MinidumpError> {
+        let low = self.u32()?;
+        let high = self.u32()?;
+        Ok(u64::from(low) | u64::from(high) << 32)
+    }
+
+    fn location(&mut self) -> Result<Location, MinidumpError> {
+        Ok(Location {
+            data_size: self.u32()?,
+            rva: self.u32()?,
+        })
+    }
+
+    /// Reads a fixed-size buffer of UTF-16 characters terminated by a NUL character.
+    fn utf16(&mut self, chars: usize) -> Result<String, MinidumpError> {
+        // The length may come from the file, so do not reserve more than the remaining data.
+        let remaining = self.data.len().saturating_sub(self.offset) / 2;
+        let mut buffer = Vec::with_capacity(chars.min(remaining));
+        for _ in 0..chars {
+            buffer.push(self.u16()?);
+        }
+
+        let len = buffer.iter().position(|c| *c == 0).unwrap_or(chars);
+        Ok(String::from_utf16_lossy(&buffer[..len]))
+    }
+}
+
+/// Reads a list of records prefixed with a 32-bit count.
+///
+/// Some minidump writers insert four bytes of padding after the count to align records to eight
+/// bytes. The padding is detected by comparing the stream size with the number of entries.
+fn read_list<'data>(
+    data: &'data [u8],
+    entry_size: usize,
+) -> Result<(StreamReader<'data>, usize), MinidumpError> {
+    let mut reader = StreamReader::new(data);
+    let count = reader.u32()? as usize;
+    let size = count
+        .checked_mul(entry_size)
+        .ok_or(MinidumpErrorKind::BadStream)?;
+
+    if data.len() == 8 + size {
+        reader.skip(4)?;
+    }
+
+    Ok((reader, count))
+}
+
+/// A reference to data in the minidump file (`MINIDUMP_LOCATION_DESCRIPTOR`).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Location {
+    data_size: u32,
+    rva: u32,
+}
+
+/// Types of streams in a minidump file.
+///
+/// Besides the stream types defined by Microsoft, this contains the custom streams written by
+/// Breakpad and Crashpad.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum StreamType {
+    Unused,
+    ThreadList,
+    ModuleList,
+    MemoryList,
+    Exception,
+    SystemInfo,
+    ThreadExList,
+    Memory64List,
+    CommentA,
+    CommentW,
+    HandleData,
+    FunctionTable,
+    UnloadedModuleList,
+    MiscInfo,
+    MemoryInfoList,
+    ThreadInfoList,
+    HandleOperationList,
+    Token,
+    ThreadNames,
+    BreakpadInfo,
+    AssertionInfo,
+    LinuxCpuInfo,
+    LinuxProcStatus,
+    LinuxLsbRelease,
+    LinuxCmdLine,
+    LinuxEnviron,
+    LinuxAuxv,
+    LinuxMaps,
+    LinuxDsoDebug,
+    CrashpadInfo,
+    /// A stream type not known to this parser.
+    Unknown(u32),
+}
+
+impl From<u32> for StreamType {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => StreamType::Unused,
+            3 => StreamType::ThreadList,
+            4 => StreamType::ModuleList,
+            5 => StreamType::MemoryList,
+            6 => StreamType::Exception,
+            7 => StreamType::SystemInfo,
+            8 => StreamType::ThreadExList,
+            9 => StreamType::Memory64List,
+            10 => StreamType::CommentA,
+            11 => StreamType::CommentW,
+            12 => StreamType::HandleData,
+            13 => StreamType::FunctionTable,
+            14 => StreamType::UnloadedModuleList,
+            15 => StreamType::MiscInfo,
+            16 => StreamType::MemoryInfoList,
+            17 => StreamType::ThreadInfoList,
+            18 => StreamType::HandleOperationList,
+            19 => StreamType::Token,
+            24 => StreamType::ThreadNames,
+            0x4767_0001 => StreamType::BreakpadInfo,
+            0x4767_0002 => StreamType::AssertionInfo,
+            0x4767_0003 => StreamType::LinuxCpuInfo,
+            0x4767_0004 => StreamType::LinuxProcStatus,
+            0x4767_0005 => StreamType::LinuxLsbRelease,
+            0x4767_0006 => StreamType::LinuxCmdLine,
+            0x4767_0007 => StreamType::LinuxEnviron,
+            0x4767_0008 => StreamType::LinuxAuxv,
+            0x4767_0009 => StreamType::LinuxMaps,
+            0x4767_000a => StreamType::LinuxDsoDebug,
+            0x4350_0001 => StreamType::CrashpadInfo,
+            other => StreamType::Unknown(other),
+        }
+    }
+}
+
+impl From<StreamType> for u32 {
+    fn from(ty: StreamType) -> Self {
+        match ty {
+            StreamType::Unused => 0,
+            StreamType::ThreadList => 3,
+            StreamType::ModuleList => 4,
+            StreamType::MemoryList => 5,
+            StreamType::Exception => 6,
+            StreamType::SystemInfo => 7,
+            StreamType::ThreadExList => 8,
+            StreamType::Memory64List => 9,
+            StreamType::CommentA => 10,
+            StreamType::CommentW => 11,
+            StreamType::HandleData => 12,
+            StreamType::FunctionTable => 13,
+            StreamType::UnloadedModuleList => 14,
+            StreamType::MiscInfo => 15,
+            StreamType::MemoryInfoList => 16,
+            StreamType::ThreadInfoList => 17,
+            StreamType::HandleOperationList => 18,
+            StreamType::Token => 19,
+            StreamType::ThreadNames => 24,
+            StreamType::BreakpadInfo => 0x4767_0001,
+            StreamType::AssertionInfo => 0x4767_0002,
+            StreamType::LinuxCpuInfo => 0x4767_0003,
+            StreamType::LinuxProcStatus => 0x4767_0004,
+            StreamType::LinuxLsbRelease => 0x4767_0005,
+            StreamType::LinuxCmdLine => 0x4767_0006,
+            StreamType::LinuxEnviron => 0x4767_0007,
+            StreamType::LinuxAuxv => 0x4767_0008,
+            StreamType::LinuxMaps => 0x4767_0009,
+            StreamType::LinuxDsoDebug => 0x4767_000a,
+            StreamType::CrashpadInfo => 0x4350_0001,
+            StreamType::Unknown(other) => other,
+        }
+    }
+}
+
+/// An entry in the stream directory of a minidump.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct DirectoryEntry {
+    stream_type: StreamType,
+    location: Location,
+}
+
+/// The header of a minidump file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MinidumpHeader {
+    /// The format version in the low word, and an implementation specific value in the high word.
+    pub version: u32,
+    /// The number of streams in the stream directory.
+    pub stream_count: u32,
+    /// A checksum of the file, which is usually zero.
+    pub checksum: u32,
+    /// The time the minidump was written, in seconds since the UNIX epoch.
+    pub time_date_stamp: u32,
+    /// Flags indicating the contents of the minidump (`MINIDUMP_TYPE`).
+    pub flags: u64,
+}
+
+/// A range of memory captured in the minidump.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MinidumpMemory<'data> {
+    /// The address of the first byte of this memory range in the crashed process.
+    pub base_address: u64,
+    /// The captured contents of the memory range.
+    pub data: &'data [u8],
+}
+
+impl<'data> MinidumpMemory<'data> {
+    /// The number of captured bytes.
+    pub fn size(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    /// The end address of this memory range, exclusive.
+    pub fn end(&self) -> u64 {
+        self.base_address.saturating_add(self.size())
+    }
+}
+
+/// Information about a region of virtual memory in the crashed process (`MINIDUMP_MEMORY_INFO`).
+///
+/// In contrast to `MinidumpMemory`, this describes the layout of the address space rather than
+/// captured contents. Protection, state and type are stored as raw Windows constants, see
+/// `memory::MemoryInfo` for a decoded version.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MinidumpMemoryInfo {
+    /// The address of the first byte of this region.
+    pub base_address: u64,
+    /// The base address of the allocation containing this region.
+    pub allocation_base: u64,
+    /// The protection of the allocation when it was first created (`PAGE_*`).
+    pub allocation_protection: u32,
+    /// The size of this region in bytes.
+    pub region_size: u64,
+    /// The state of the pages in this region (`MEM_COMMIT`, `MEM_RESERVE` or `MEM_FREE`).
+    pub state: u32,
+    /// The current protection of the pages in this region (`PAGE_*`).
+    pub protection: u32,
+    /// The type of the pages in this region (`MEM_IMAGE`, `MEM_MAPPED` or `MEM_PRIVATE`).
+    pub memory_type: u32,
+}
+
+impl MinidumpMemoryInfo {
+    /// The end address of this region, exclusive.
+    pub fn end(&self) -> u64 {
+        self.base_address.saturating_add(self.region_size)
+    }
+}
+
+/// A thread of the crashed process.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinidumpThread<'data> {
+    /// The identifier of the thread.
+    pub thread_id: u32,
+    /// The suspend count of the thread at the time the minidump was written.
+    pub suspend_count: u32,
+    /// The priority class of the thread.
+    pub priority_class: u32,
+    /// The priority level of the thread.
+    pub priority: u32,
+    /// The address of the thread environment block.
+    pub teb: u64,
+    /// The stack memory of the thread.
+    ///
+    /// The data is empty if the stack memory is missing in the minidump.
+    pub stack: MinidumpMemory<'data>,
+    /// The raw CPU context of the thread.
+    ///
+    /// The layout of the context depends on the CPU architecture. It is empty if the context is
+    /// missing in the minidump.
+    pub context: &'data [u8],
+}
+
+/// Extended information about a thread of the crashed process (`MINIDUMP_THREAD_INFO`).
+///
+/// Times are stored as Windows `FILETIME` values in 100-nanosecond intervals.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MinidumpThreadInfo {
+    /// The identifier of the thread.
+    pub thread_id: u32,
+    /// Flags indicating the state of the thread when it was dumped (`MINIDUMP_THREAD_INFO_*`).
+    pub dump_flags: u32,
+    /// An `HRESULT` indicating why the thread could not be dumped, or zero.
+    pub dump_error: u32,
+    /// The exit status of the thread, which is `STILL_ACTIVE` for running threads.
+    pub exit_status: u32,
+    /// The time the thread was created, since January 1, 1601 (UTC).
+    pub create_time: u64,
+    /// The time the thread exited, since January 1, 1601 (UTC), or zero.
+    pub exit_time: u64,
+    /// The time spent executing in kernel mode.
+    pub kernel_time: u64,
+    /// The time spent executing in user mode.
+    pub user_time: u64,
+    /// The start address of the thread.
+    pub start_address: u64,
+    /// The processor affinity mask of the thread.
+    pub affinity: u64,
+}
+
+/// Version information of a Windows module (`VS_FIXEDFILEINFO`).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FixedFileInfo {
+    pub struct_version: u32,
+    pub file_version_hi: u32,
+    pub file_version_lo: u32,
+    pub product_version_hi: u32,
+    pub product_version_lo: u32,
+    pub file_flags_mask: u32,
+    pub file_flags: u32,
+    pub file_os: u32,
+    pub file_type: u32,
+    pub file_subtype: u32,
+    pub file_date_hi: u32,
+    pub file_date_lo: u32,
+}
+
+impl FixedFileInfo {
+    /// The four components of the file version.
+    pub fn file_version(&self) -> [u16; 4] {
+        split_version(self.file_version_hi, self.file_version_lo)
+    }
+
+    /// The four components of the product version.
+    pub fn product_version(&self) -> [u16; 4] {
+        split_version(self.product_version_hi, self.product_version_lo)
+    }
+
+    fn parse(reader: &mut StreamReader<'_>) -> Result<Option<Self>, MinidumpError> {
+        let signature = reader.u32()?;
+        let info = FixedFileInfo {
+            struct_version: reader.u32()?,
+            file_version_hi: reader.u32()?,
+            file_version_lo: reader.u32()?,
+            product_version_hi: reader.u32()?,
+            product_version_lo: reader.u32()?,
+            file_flags_mask: reader.u32()?,
+            file_flags: reader.u32()?,
+            file_os: reader.u32()?,
+            file_type: reader.u32()?,
+            file_subtype: reader.u32()?,
+            file_date_hi: reader.u32()?,
+            file_date_lo: reader.u32()?,
+        };
+
+        if signature == FIXED_FILE_INFO_SIGNATURE {
+            Ok(Some(info))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+fn split_version(hi: u32, lo: u32) -> [u16; 4] {
+    [(hi >> 16) as u16, hi as u16, (lo >> 16) as u16, lo as u16]
+}
+
+/// Debug information referenced by the CodeView record of a module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum CodeView<'data> {
+    Pdb70 {
+        guid: Uuid,
+        age: u32,
+        file: String,
+    },
+    Pdb20 {
+        signature: u32,
+        age: u32,
+        file: String,
+    },
+    Elf {
+        build_id: &'data [u8],
+    },
+}
+
+impl<'data> CodeView<'data> {
+    fn parse(data: &'data [u8]) -> Option<Self> {
+        let mut reader = StreamReader::new(data);
+        match reader.u32().ok()? {
+            CV_SIGNATURE_PDB70 => {
+                let guid = read_guid(reader.bytes(16).ok()?)?;
+                let age = reader.u32().ok()?;
+                let file = read_c_string(&data[reader.offset..]);
+                Some(CodeView::Pdb70 { guid, age, file })
+            }
+            CV_SIGNATURE_PDB20 => {
+                reader.skip(4).ok()?;
+                let signature = reader.u32().ok()?;
+                let age = reader.u32().ok()?;
+                let file = read_c_string(&data[reader.offset..]);
+                Some(CodeView::Pdb20 {
+                    signature,
+                    age,
+                    file,
+                })
+            }
+            CV_SIGNATURE_ELF => Some(CodeView::Elf {
+                build_id: &data[reader.offset..],
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Reads a GUID in Windows byte order.
+fn read_guid(data: &[u8]) -> Option<Uuid> {
+    let mut reader = StreamReader::new(data);
+    let data1 = reader.u32().ok()?;
+    let data2 = reader.u16().ok()?;
+    let data3 = reader.u16().ok()?;
+    let data4 = reader.bytes(8).ok()?;
+    Uuid::from_fields(data1, data2, data3, data4).ok()
+}
+
+/// Reads a NUL-terminated UTF-8 string.
+fn read_c_string(data: &[u8]) -> String {
+    let len = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..len]).into_owned()
+}
+
+/// A module loaded into the crashed process.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinidumpModule<'data> {
+    /// The address at which the module was loaded.
+    pub base_address: u64,
+    /// The size of the module's image in memory.
+    pub size: u64,
+    /// The checksum from the PE header, or zero.
+    pub checksum: u32,
+    /// The timestamp from the PE header, or zero.
+    pub time_date_stamp: u32,
+    /// The full path of the module.
+    pub name: String,
+    /// Version information of Windows modules.
+    pub version_info: Option<FixedFileInfo>,
+    /// The raw CodeView record identifying the module's debug information.
+    pub cv_record: &'data [u8],
+    /// The raw miscellaneous debug record (`IMAGE_DEBUG_MISC`), rarely present.
+    pub misc_record: &'data [u8],
+}
+
+impl<'data> MinidumpModule<'data> {
+    /// The end address of this module, exclusive.
+    pub fn end(&self) -> u64 {
+        self.base_address.saturating_add(self.size)
+    }
+
+    /// Returns the path or file name that the module was loaded from.
+    pub fn code_file(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns an identifier of the module's code file.
+    ///
+    /// For ELF modules, this is the hex-encoded build id. For PE modules, this is composed of
+    /// the timestamp and the image size, as used by symbol servers.
+    pub fn code_id(&self) -> Option<String> {
+        match CodeView::parse(self.cv_record) {
+            Some(CodeView::Elf { build_id }) => Some(
+                build_id
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect(),
+            ),
+            _ if self.time_date_stamp != 0 => {
+                Some(format!("{:08X}{:x}", self.time_date_stamp, self.size))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the file containing debug information for this module.
+    ///
+    /// If debug information is stored in the module itself, this is the same as `code_file`.
+    pub fn debug_file(&self) -> String {
+        match CodeView::parse(self.cv_record) {
+            Some(CodeView::Pdb70 { file, .. }) | Some(CodeView::Pdb20 { file, .. }) => file,
+            _ => self.name.clone(),
+        }
+    }
+
+    /// Returns the identifier of the module's debug information file.
+    ///
+    /// For ELF modules, this is derived from the first 16 bytes of the build id in the same way
+    /// Breakpad does.
+    pub fn debug_id(&self) -> Option<DebugId> {
+        match CodeView::parse(self.cv_record)? {
+            CodeView::Pdb70 { guid, age, .. } => Some(DebugId::from_parts(guid, age)),
+            CodeView::Pdb20 { signature, age, .. } => {
+                let uuid = Uuid::from_fields(signature, 0, 0, &[0; 8]).ok()?;
+                Some(DebugId::from_parts(uuid, age))
+            }
+            CodeView::Elf { build_id } => {
+                let mut data = [0; 16];
+                let len = build_id.len().min(16);
+                data[..len].copy_from_slice(&build_id[..len]);
+                Some(DebugId::from_parts(read_guid(&data)?, 0))
+            }
+        }
+    }
+}
+
+/// A module that was unloaded from the process before the crash.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinidumpUnloadedModule {
+    /// The address at which the module was loaded.
+    pub base_address: u64,
+    /// The size of the module's image in memory.
+    pub size: u64,
+    /// The checksum from the PE header, or zero.
+    pub checksum: u32,
+    /// The timestamp from the PE header, or zero.
+    pub time_date_stamp: u32,
+    /// The full path of the module.
+    pub name: String,
+}
+
+impl MinidumpUnloadedModule {
+    /// The end address of this module, exclusive.
+    pub fn end(&self) -> u64 {
+        self.base_address.saturating_add(self.size)
+    }
+}
+
+/// Information about the exception that caused the minidump to be written.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinidumpException<'data> {
+    /// The identifier of the thread that caused the exception.
+    pub thread_id: u32,
+    /// The OS specific exception code, for instance an `NTSTATUS` or signal number.
+    pub code: u32,
+    /// OS specific flags, which contain the signal code on Linux.
+    pub flags: u32,
+    /// The address of a nested exception record.
+    pub record_address: u64,
+    /// The address at which the exception occurred.
+    pub address: u64,
+    /// Additional parameters of the exception.
+    pub parameters: Vec<u64>,
+    /// The raw CPU context of the thread at the time of the exception.
+    ///
+    /// It is empty if the context is missing in the minidump.
+    pub context: &'data [u8],
+}
+
+/// Processor and operating system of the crashed process.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinidumpSystemInfo {
+    /// The processor architecture (`PROCESSOR_ARCHITECTURE_*`).
+    pub processor_architecture: u16,
+    /// The processor family or level.
+    pub processor_level: u16,
+    /// The processor model and stepping.
+    pub processor_revision: u16,
+    /// The number of processors in the system.
+    pub cpu_count: u8,
+    /// The product type on Windows (workstation, server or domain controller).
+    pub product_type: u8,
+    /// The major version of the operating system.
+    pub major_version: u32,
+    /// The minor version of the operating system.
+    pub minor_version: u32,
+    /// The build number of the operating system.
+    pub build_number: u32,
+    /// The operating system platform (`VER_PLATFORM_*` or a Breakpad extension).
+    pub platform_id: u32,
+    /// Service pack on Windows, or the `uname` output of the system on Linux.
+    pub csd_version: String,
+    /// Bit flags of product suites available on Windows.
+    pub suite_mask: u16,
+    /// Raw processor information, whose layout depends on the architecture.
+    pub cpu_info: [u8; 24],
+}
+
+impl MinidumpSystemInfo {
+    /// Returns the CPU architecture of the crashed process.
+    pub fn arch(&self) -> Arch {
+        match self.processor_architecture {
+            0 | 10 => Arch::X86,
+            9 => Arch::X86_64,
+            5 => Arch::Arm,
+            12 | 0x8003 => Arch::Arm64,
+            3 => Arch::Ppc,
+            0x8002 => Arch::Ppc64,
+            _ => Arch::Unknown,
+        }
+    }
+
+    /// Returns the name of the operating system, such as "Windows NT" or "Linux".
+    pub fn os_name(&self) -> Option<&'static str> {
+        Some(match self.platform_id {
+            1 => "Windows",
+            2 => "Windows NT",
+            3 => "Windows CE",
+            0x8000 => "Unix",
+            0x8101 => "Mac OS X",
+            0x8102 => "iOS",
+            0x8201 => "Linux",
+            0x8202 => "Solaris",
+            0x8203 => "Android",
+            0x8204 => "PS3",
+            0x8205 => "NaCl",
+            0x8206 => "Fuchsia",
+            _ => return None,
+        })
+    }
+
+    /// Returns the version of the operating system as "major.minor.build".
+    pub fn os_version(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.major_version, self.minor_version, self.build_number
+        )
+    }
+
+    /// Returns the CPU vendor of x86 processors, such as "GenuineIntel".
+    pub fn cpu_vendor(&self) -> Option<String> {
+        match self.arch().cpu_family() {
+            CpuFamily::Intel32 | CpuFamily::Intel64 => {
+                let vendor = read_c_string(&self.cpu_info[..12]);
+                if vendor.is_empty() {
+                    None
+                } else {
+                    Some(vendor)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A typed annotation object of a module, registered with Crashpad's `Annotation` class.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinidumpAnnotation {
+    /// The name of the annotation.
+    pub name: String,
+    /// The type of the value, such as `CRASHPAD_ANNOTATION_STRING`.
+    ///
+    /// Types starting at `0x8000` are defined by the application.
+    pub ty: u16,
+    /// The raw value of the annotation.
+    pub value: Vec<u8>,
+}
+
+impl MinidumpAnnotation {
+    /// Returns the value as string if this is a string annotation.
+    pub fn as_str(&self) -> Option<&str> {
+        match self.ty {
+            CRASHPAD_ANNOTATION_STRING => str::from_utf8(&self.value).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Annotations that Crashpad recorded for a single module.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MinidumpModuleCrashpadInfo {
+    /// The index of the module in the list returned by `Minidump::modules`.
+    pub module_index: usize,
+    /// Annotations stored as list of strings, such as messages of failed assertions.
+    pub list_annotations: Vec<String>,
+    /// Annotations stored as key-value pairs.
+    pub simple_annotations: BTreeMap<String, String>,
+    /// Typed annotation objects.
+    pub annotation_objects: Vec<MinidumpAnnotation>,
+}
+
+/// Information stored by Crashpad in its own stream (`MinidumpCrashpadInfo`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinidumpCrashpadInfo {
+    /// Identifier of the crash report, which is assigned by the Crashpad client.
+    pub report_id: Uuid,
+    /// Identifier of the Crashpad client installation that produced the report.
+    pub client_id: Uuid,
+    /// Process-level annotations stored as key-value pairs.
+    pub simple_annotations: BTreeMap<String, String>,
+    /// Annotations of modules that contain any.
+    pub module_list: Vec<MinidumpModuleCrashpadInfo>,
+}
+
+impl MinidumpCrashpadInfo {
+    /// Returns thread names recorded in annotations, keyed by thread identifier.
+    ///
+    /// Crashpad does not write a thread names stream on Linux, so clients record names as
+    /// annotations with the key `thread_name:<id>` instead. The identifier may be decimal or
+    /// hexadecimal with a `0x` prefix. Process-level annotations, module annotations and string
+    /// annotation objects are considered, in this order. The first name of each thread wins.
+    pub fn thread_names(&self) -> BTreeMap<u32, String> {
+        let mut names = BTreeMap::new();
+        let mut insert = |key: &str, value: &str| {
+            if let Some(thread_id) = parse_thread_name_key(key) {
+                names.entry(thread_id).or_insert_with(|| value.to_string());
+            }
+        };
+
+        for (key, value) in &self.simple_annotations {
+            insert(key, value);
+        }
+
+        for module in &self.module_list {
+            for (key, value) in &module.simple_annotations {
+                insert(key, value);
+            }
+
+            for object in &module.annotation_objects {
+                if let Some(value) = object.as_str() {
+                    insert(&object.name, value);
+                }
+            }
+        }
+
+        names
+    }
+}
+
+/// Parses the thread identifier from a `thread_name:<id>` annotation key.
+fn parse_thread_name_key(key: &str) -> Option<u32> {
+    let id = key.strip_prefix(CRASHPAD_THREAD_NAME_PREFIX)?;
+    match id.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => id.parse().ok(),
+    }
+}
+
+/// Miscellaneous information about the crashed process (`MINIDUMP_MISC_INFO_N`).
+///
+/// All fields are optional, since their presence depends on the version of the structure and the
+/// flags set by the minidump writer.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MinidumpMiscInfo {
+    /// The identifier of the crashed process.
+    pub process_id: Option<u32>,
+    /// The creation time of the process, in seconds since the UNIX epoch.
+    pub process_create_time: Option<u32>,
+    /// The time the process spent in user mode, in seconds.
+    pub process_user_time: Option<u32>,
+    /// The time the process spent in kernel mode, in seconds.
+    pub process_kernel_time: Option<u32>,
+    /// The maximum processor frequency, in MHz.
+    pub processor_max_mhz: Option<u32>,
+    /// The current processor frequency, in MHz.
+    pub processor_current_mhz: Option<u32>,
+    /// The limit of the processor frequency, in MHz.
+    pub processor_mhz_limit: Option<u32>,
+    /// The integrity level of the process.
+    pub process_integrity_level: Option<u32>,
+    /// The data execution prevention flags of the process.
+    pub process_execute_flags: Option<u32>,
+    /// Whether the process is protected.
+    pub protected_process: Option<u32>,
+    /// The time zone identifier (`TIME_ZONE_ID_*`).
+    pub time_zone_id: Option<u32>,
+    /// The offset of the local time zone to UTC, in minutes.
+    pub time_zone_bias: Option<i32>,
+    /// The name of the standard time zone.
+    pub time_zone_name: Option<String>,
+    /// The name of the daylight saving time zone.
+    pub daylight_name: Option<String>,
+    /// The build string of the operating system.
+    pub build_string: Option<String>,
+    /// The version of the debugging library that wrote the minidump.
+    pub dbg_bld_str: Option<String>,
+}
+
+impl MinidumpMiscInfo {
+    fn parse(data: &[u8]) -> Result<Self, MinidumpError> {
+        let mut reader = StreamReader::new(data);
+        let size = (reader.u32()? as usize).min(data.len());
+        let flags = reader.u32()?;
+        let mut info = MinidumpMiscInfo::default();
+
+        let process_id = reader.u32()?;
+        if flags & MISC_INFO_PROCESS_ID != 0 {
+            info.process_id = Some(process_id);
+        }
+
+        let create_time = reader.u32()?;
+        let user_time = reader.u32()?;
+        let kernel_time = reader.u32()?;
+        if flags & MISC_INFO_PROCESS_TIMES != 0 {
+            info.process_create_time = Some(create_time);
+            info.process_user_time = Some(user_time);
+            info.process_kernel_time = Some(kernel_time);
+        }
+
+        // MINIDUMP_MISC_INFO_2
+        if size < 44 {
+            return Ok(info);
+        }
+
+        let max_mhz = reader.u32()?;
+        let current_mhz = reader.u32()?;
+        let mhz_limit = reader.u32()?;
+        reader.skip(8)?; // idle states
+        if flags & MISC_INFO_PROCESSOR_POWER_INFO != 0 {
+            info.processor_max_mhz = Some(max_mhz);
+            info.processor_current_mhz = Some(current_mhz);
+            info.processor_mhz_limit = Some(mhz_limit);
+        }
+
+        // MINIDUMP_MISC_INFO_3
+        if size < 232 {
+            return Ok(info);
+        }
+
+        let integrity_level = reader.u32()?;
+        let execute_flags = reader.u32()?;
+        let protected_process = reader.u32()?;
+        let time_zone_id = reader.u32()?;
+        let bias = reader.u32()? as i32;
+        let standard_name = reader.utf16(32)?;
+        reader.skip(16 + 4)?; // standard date and bias
+        let daylight_name = reader.utf16(32)?;
+        reader.skip(16 + 4)?; // daylight date and bias
+
+        if flags & MISC_INFO_PROCESS_INTEGRITY != 0 {
+            info.process_integrity_level = Some(integrity_level);
+        }
+        if flags & MISC_INFO_PROCESS_EXECUTE_FLAGS != 0 {
+            info.process_execute_flags = Some(execute_flags);
+        }
+        if flags & MISC_INFO_PROTECTED_PROCESS != 0 {
+            info.protected_process = Some(protected_process);
+        }
+        if flags & MISC_INFO_TIMEZONE != 0 {
+            info.time_zone_id = Some(time_zone_id);
+            info.time_zone_bias = Some(bias);
+            info.time_zone_name = Some(standard_name);
+            info.daylight_name = Some(daylight_name);
+        }
+
+        // MINIDUMP_MISC_INFO_4
+        if size < 832 {
+            return Ok(info);
+        }
+
+        let build_string = reader.utf16(260)?;
+        let dbg_bld_str = reader.utf16(40)?;
+        if flags & MISC_INFO_BUILDSTRING != 0 {
+            info.build_string = Some(build_string);
+            info.dbg_bld_str = Some(dbg_bld_str);
+        }
+
+        Ok(info)
+    }
+}
+
+/// A minidump file parsed from a buffer.
+///
+/// The header and stream directory are validated when parsing. Streams are only parsed when
+/// calling the respective accessors, which fail if the stream is malformed. Accessors for
+/// optional streams return `None` if the stream is missing.
+pub struct Minidump<'data> {
+    data: &'data [u8],
+    header: MinidumpHeader,
+    directory: Vec<DirectoryEntry>,
+}
+
+impl<'data> Minidump<'data> {
+    /// Tests whether the buffer could contain a minidump.
+    pub fn test(data: &[u8]) -> bool {
+        data.starts_with(MINIDUMP_SIGNATURE)
+    }
+
+    /// Parses the header and stream directory of a minidump.
+    pub fn parse(data: &'data [u8]) -> Result<Self, MinidumpError> {
+        if !Self::test(data) {
+            return Err(MinidumpErrorKind::BadFileMagic.into());
+        }
+
+        let bad_header = |_| MinidumpError::from(MinidumpErrorKind::BadHeader);
+        let mut reader = StreamReader::new(data.get(..HEADER_SIZE).unwrap_or(data));
+        reader.skip(4).map_err(bad_header)?;
+
+        let version = reader.u32().map_err(bad_header)?;
+        if version & 0xffff != MINIDUMP_VERSION {
+            return Err(MinidumpErrorKind::UnsupportedVersion.into());
+        }
+
+        let stream_count = reader.u32().map_err(bad_header)?;
+        let directory_rva = reader.u32().map_err(bad_header)?;
+        let header = MinidumpHeader {
+            version,
+            stream_count,
+            checksum: reader.u32().map_err(bad_header)?,
+            time_date_stamp: reader.u32().map_err(bad_header)?,
+            flags: reader.u64().map_err(bad_header)?,
+        };
+
+        let mut reader = StreamReader::new(data);
+        reader.offset = directory_rva as usize;
+
+        let mut directory = Vec::with_capacity((stream_count as usize).min(data.len() / 12));
+        for _ in 0..stream_count {
+            let stream_type = StreamType::from(reader.u32().map_err(bad_header)?);
+            let location = reader.location().map_err(bad_header)?;
+            directory.push(DirectoryEntry {
+                stream_type,
+                location,
+            });
+        }
+
+        Ok(Minidump {
+            data,
+            header,
+            directory,
+        })
+    }
+
+    /// Returns the header of the minidump.
+    pub fn header(&self) -> &MinidumpHeader {
+        &self.header
+    }
+
+    /// Returns the time the minidump was written, in seconds since the UNIX epoch.
+    pub fn timestamp(&self) -> u32 {
+        self.header.time_date_stamp
+    }
+
+    /// Returns the types of all streams in the minidump, in the order of the stream directory.
+    pub fn stream_types(&self) -> Vec<StreamType> {
+        self.directory
+            .iter()
+            .map(|entry| entry.stream_type)
+            .filter(|ty| *ty != StreamType::Unused)
+            .collect()
+    }
+
+    /// Returns the raw data of the first stream with the given type.
+    ///
+    /// Returns `Ok(None)` if the stream is missing, and an error if it points outside of the file.
+    pub fn raw_stream(
+        &self,
+        stream_type: StreamType,
+    ) -> Result<Option<&'data [u8]>, MinidumpError> {
+        match self
+            .directory
+            .iter()
+            .find(|entry| entry.stream_type == stream_type)
+        {
+            Some(entry) => self.location(entry.location).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Resolves a location descriptor to the referenced data.
+    fn location(&self, location: Location) -> Result<&'data [u8], MinidumpError> {
+        let start = location.rva as usize;
+        start
+            .checked_add(location.data_size as usize)
+            .and_then(|end| self.data.get(start..end))
+            .ok_or_else(|| MinidumpErrorKind::BadStream.into())
+    }
+
+    /// Resolves a location descriptor, returning empty data if it is out of bounds.
+    ///
+    /// Minidumps are frequently truncated. Stacks and contexts that were not written completely
+    /// should not prevent access to the remaining information.
+    fn location_or_empty(&self, location: Location) -> &'data [u8] {
+        self.location(location).unwrap_or_default()
+    }
+
+    /// Reads a `MINIDUMP_STRING` at the given offset.
+    fn string(&self, rva: u32) -> Result<String, MinidumpError> {
+        let mut reader = StreamReader::new(self.data);
+        reader.offset = rva as usize;
+
+        let len = reader.u32()? as usize;
+        reader.utf16(len / 2)
+    }
+
+    /// Reads a `MinidumpUTF8String` at the given offset.
+    fn utf8_string(&self, rva: u32) -> Result<String, MinidumpError> {
+        let mut reader = StreamReader::new(self.data);
+        reader.offset = rva as usize;
+
+        let len = reader.u32()? as usize;
+        Ok(String::from_utf8_lossy(reader.bytes(len)?).into_owned())
+    }
+
+    /// Reads a `MinidumpSimpleStringDictionary` of Crashpad annotations.
+    fn string_dictionary(
+        &self,
+        location: Location,
+    ) -> Result<BTreeMap<String, String>, MinidumpError> {
+        let mut dictionary = BTreeMap::new();
+        if location.data_size == 0 {
+            return Ok(dictionary);
+        }
+
+        let data = self.location(location)?;
+        let mut reader = StreamReader::new(data);
+        let count = reader.u32()?;
+        for _ in 0..count.min((data.len() / CRASHPAD_DICTIONARY_ENTRY_SIZE) as u32) {
+            let key = self.utf8_string(reader.u32()?)?;
+            let value = self.utf8_string(reader.u32()?)?;
+            dictionary.insert(key, value);
+        }
+
+        Ok(dictionary)
+    }
+
+    /// Reads a `MinidumpRVAList` of strings.
+    fn string_list(&self, location: Location) -> Result<Vec<String>, MinidumpError> {
+        if location.data_size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let data = self.location(location)?;
+        let mut reader = StreamReader::new(data);
+        let count = reader.u32()? as usize;
+
+        let mut strings = Vec::with_capacity(count.min(data.len() / 4));
+        for _ in 0..count {
+            strings.push(self.utf8_string(reader.u32()?)?);
+        }
+
+        Ok(strings)
+    }
+
+    /// Reads a `MinidumpAnnotationList` of typed annotation objects.
+    fn annotation_list(
+        &self,
+        location: Location,
+    ) -> Result<Vec<MinidumpAnnotation>, MinidumpError> {
+        if location.data_size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let data = self.location(location)?;
+        let mut reader = StreamReader::new(data);
+        let count = reader.u32()? as usize;
+
+        let mut annotations = Vec::with_capacity(count.min(data.len() / CRASHPAD_ANNOTATION_SIZE));
+        for _ in 0..count {
+            let name = self.utf8_string(reader.u32()?)?;
+            let ty = reader.u16()?;
+            reader.skip(2)?;
+
+            // the value is a `MinidumpByteArray`, which has the same layout as a UTF-8 string
+            let mut value_reader = StreamReader::new(self.data);
+            value_reader.offset = reader.u32()? as usize;
+            let len = value_reader.u32()? as usize;
+
+            annotations.push(MinidumpAnnotation {
+                name,
+                ty,
+                value: value_reader.bytes(len)?.to_vec(),
+            });
+        }
+
+        Ok(annotations)
+    }
+
+    /// Reads a memory descriptor and resolves its data.
+    fn memory_descriptor(
+        &self,
+        reader: &mut StreamReader<'data>,
+    ) -> Result<MinidumpMemory<'data>, MinidumpError> {
+        Ok(MinidumpMemory {
+            base_address: reader.u64()?,
+            data: self.location_or_empty(reader.location()?),
+        })
+    }
+
+    /// Returns all threads of the crashed process.
+    pub fn threads(&self) -> Result<Vec<MinidumpThread<'data>>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::ThreadList)? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+
+        let (mut reader, count) = read_list(data, THREAD_SIZE)?;
+        let mut threads = Vec::with_capacity(count.min(data.len() / THREAD_SIZE));
+        for _ in 0..count {
+            threads.push(MinidumpThread {
+                thread_id: reader.u32()?,
+                suspend_count: reader.u32()?,
+                priority_class: reader.u32()?,
+                priority: reader.u32()?,
+                teb: reader.u64()?,
+                stack: self.memory_descriptor(&mut reader)?,
+                context: self.location_or_empty(reader.location()?),
+            });
+        }
+
+        Ok(threads)
+    }
+
+    /// Returns extended information about threads, such as their creation time and affinity.
+    ///
+    /// This list is only written on Windows and is empty for other platforms.
+    pub fn thread_info_list(&self) -> Result<Vec<MinidumpThreadInfo>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::ThreadInfoList)? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut reader = StreamReader::new(data);
+        let header_size = reader.u32()? as usize;
+        let entry_size = reader.u32()? as usize;
+        let count = reader.u32()? as usize;
+        reader.offset = header_size;
+
+        if entry_size < THREAD_INFO_SIZE {
+            return Err(MinidumpErrorKind::BadStream.into());
+        }
+
+        let mut infos = Vec::with_capacity(count.min(data.len() / entry_size));
+        for _ in 0..count {
+            let mut entry = StreamReader::new(reader.bytes(entry_size)?);
+            infos.push(MinidumpThreadInfo {
+                thread_id: entry.u32()?,
+                dump_flags: entry.u32()?,
+                dump_error: entry.u32()?,
+                exit_status: entry.u32()?,
+                create_time: entry.u64()?,
+                exit_time: entry.u64()?,
+                kernel_time: entry.u64()?,
+                user_time: entry.u64()?,
+                start_address: entry.u64()?,
+                affinity: entry.u64()?,
+            });
+        }
+
+        Ok(infos)
+    }
+
+    /// Returns the names of threads, keyed by thread identifier.
+    ///
+    /// Thread names are written by Windows 10 and later, as well as Crashpad on some platforms.
+    pub fn thread_names(&self) -> Result<BTreeMap<u32, String>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::ThreadNames)? {
+            Some(data) => data,
+            None => return Ok(BTreeMap::new()),
+        };
+
+        let mut reader = StreamReader::new(data);
+        let count = reader.u32()? as usize;
+
+        let mut names = BTreeMap::new();
+        for _ in 0..count.min(data.len() / THREAD_NAME_SIZE) {
+            let thread_id = reader.u32()?;
+            // the name is referenced by a 64-bit RVA, which must still point into the file
+            let rva = reader.u32()?;
+            if reader.u32()? != 0 {
+                return Err(MinidumpErrorKind::BadStream.into());
+            }
+
+            names.insert(thread_id, self.string(rva)?);
+        }
+
+        Ok(names)
+    }
+
+    /// Returns all modules loaded into the crashed process.
+    pub fn modules(&self) -> Result<Vec<MinidumpModule<'data>>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::ModuleList)? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+
+        let (mut reader, count) = read_list(data, MODULE_SIZE)?;
+        let mut modules = Vec::with_capacity(count.min(data.len() / MODULE_SIZE));
+        for _ in 0..count {
+            let base_address = reader.u64()?;
+            let size = u64::from(reader.u32()?);
+            let checksum = reader.u32()?;
+            let time_date_stamp = reader.u32()?;
+            let name_rva = reader.u32()?;
+            let version_info = FixedFileInfo::parse(&mut reader)?;
+            let cv_record = self.location_or_empty(reader.location()?);
+            let misc_record = self.location_or_empty(reader.location()?);
+            reader.skip(16)?; // reserved
+
+            modules.push(MinidumpModule {
+                base_address,
+                size,
+                checksum,
+                time_date_stamp,
+                name: self.string(name_rva)?,
+                version_info,
+                cv_record,
+                misc_record,
+            });
+        }
+
+        Ok(modules)
+    }
+
+    /// Returns all memory ranges in the memory list.
+    ///
+    /// This list usually contains the stacks of all threads and memory around the crashing
+    /// instruction. Full memory dumps use `memory64_list` instead.
+    pub fn memory_list(&self) -> Result<Vec<MinidumpMemory<'data>>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::MemoryList)? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+
+        let (mut reader, count) = read_list(data, MEMORY_DESCRIPTOR_SIZE)?;
+        let mut memory = Vec::with_capacity(count.min(data.len() / MEMORY_DESCRIPTOR_SIZE));
+        for _ in 0..count {
+            memory.push(self.memory_descriptor(&mut reader)?);
+        }
+
+        Ok(memory)
+    }
+
+    /// Returns all memory ranges in the 64-bit memory list of full memory dumps.
+    ///
+    /// The contents of all ranges are stored consecutively at the end of the file. Full memory
+    /// dumps are frequently truncated, so the list ends at the first range that is not contained
+    /// in the file.
+    pub fn memory64_list(&self) -> Result<Vec<MinidumpMemory<'data>>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::Memory64List)? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut reader = StreamReader::new(data);
+        let count = reader.u64()? as usize;
+        let mut rva = reader.u64()?;
+
+        let mut memory = Vec::with_capacity(count.min(data.len() / MEMORY_DESCRIPTOR_SIZE));
+        for _ in 0..count {
+            let (base_address, size) = match (reader.u64(), reader.u64()) {
+                (Ok(base_address), Ok(size)) => (base_address, size),
+                _ => break,
+            };
+
+            let start = rva as usize;
+            let data = match start
+                .checked_add(size as usize)
+                .and_then(|end| self.data.get(start..end))
+            {
+                Some(data) => data,
+                None => break,
+            };
+
+            memory.push(MinidumpMemory { base_address, data });
+            rva = rva.saturating_add(size);
+        }
+
+        Ok(memory)
+    }
+
+    /// Returns the layout of the crashed process' address space.
+    ///
+    /// This list is only written on Windows and is empty for other platforms.
+    pub fn memory_info_list(&self) -> Result<Vec<MinidumpMemoryInfo>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::MemoryInfoList)? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut reader = StreamReader::new(data);
+        let header_size = reader.u32()? as usize;
+        let entry_size = reader.u32()? as usize;
+        let count = reader.u64()? as usize;
+        reader.offset = header_size;
+
+        if entry_size < MEMORY_INFO_SIZE {
+            return Err(MinidumpErrorKind::BadStream.into());
+        }
+
+        let mut infos = Vec::with_capacity(count.min(data.len() / entry_size));
+        for _ in 0..count {
+            let mut entry = StreamReader::new(reader.bytes(entry_size)?);
+            let base_address = entry.u64()?;
+            let allocation_base = entry.u64()?;
+            let allocation_protection = entry.u32()?;
+            entry.skip(4)?;
+            let region_size = entry.u64()?;
+
+            infos.push(MinidumpMemoryInfo {
+                base_address,
+                allocation_base,
+                allocation_protection,
+                region_size,
+                state: entry.u32()?,
+                protection: entry.u32()?,
+                memory_type: entry.u32()?,
+            });
+        }
+
+        Ok(infos)
+    }
+
+    /// Returns information about the exception that caused the crash, if any.
+    pub fn exception(&self) -> Result<Option<MinidumpException<'data>>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::Exception)? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let mut reader = StreamReader::new(data);
+        let thread_id = reader.u32()?;
+        reader.skip(4)?; // alignment
+
+        let code = reader.u32()?;
+        let flags = reader.u32()?;
+        let record_address = reader.u64()?;
+        let address = reader.u64()?;
+        let parameter_count = reader.u32()? as usize;
+        reader.skip(4)?; // alignment
+
+        let mut parameters = Vec::with_capacity(EXCEPTION_MAXIMUM_PARAMETERS);
+        for _ in 0..EXCEPTION_MAXIMUM_PARAMETERS {
+            parameters.push(reader.u64()?);
+        }
+        parameters.truncate(parameter_count);
+
+        Ok(Some(MinidumpException {
+            thread_id,
+            code,
+            flags,
+            record_address,
+            address,
+            parameters,
+            context: self.location_or_empty(reader.location()?),
+        }))
+    }
+
+    /// Returns information about the processor and operating system, if available.
+    pub fn system_info(&self) -> Result<Option<MinidumpSystemInfo>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::SystemInfo)? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let mut reader = StreamReader::new(data);
+        let processor_architecture = reader.u16()?;
+        let processor_level = reader.u16()?;
+        let processor_revision = reader.u16()?;
+        let cpu_count = reader.u8()?;
+        let product_type = reader.u8()?;
+        let major_version = reader.u32()?;
+        let minor_version = reader.u32()?;
+        let build_number = reader.u32()?;
+        let platform_id = reader.u32()?;
+        let csd_version_rva = reader.u32()?;
+        let suite_mask = reader.u16()?;
+        reader.skip(2)?; // reserved
+
+        let mut cpu_info = [0; 24];
+        cpu_info.copy_from_slice(reader.bytes(24)?);
+
+        let csd_version = if csd_version_rva != 0 {
+            self.string(csd_version_rva)?
+        } else {
+            String::new()
+        };
+
+        Ok(Some(MinidumpSystemInfo {
+            processor_architecture,
+            processor_level,
+            processor_revision,
+            cpu_count,
+            product_type,
+            major_version,
+            minor_version,
+            build_number,
+            platform_id,
+            csd_version,
+            suite_mask,
+            cpu_info,
+        }))
+    }
+
+    /// Returns miscellaneous information about the crashed process, if available.
+    pub fn misc_info(&self) -> Result<Option<MinidumpMiscInfo>, MinidumpError> {
+        match self.raw_stream(StreamType::MiscInfo)? {
+            Some(data) => MinidumpMiscInfo::parse(data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns information stored by Crashpad, if the minidump was written by Crashpad.
+    ///
+    /// This contains identifiers of the report and client, as well as process and module level
+    /// annotations. Module annotations refer to modules by their index in `modules`.
+    pub fn crashpad_info(&self) -> Result<Option<MinidumpCrashpadInfo>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::CrashpadInfo)? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let mut reader = StreamReader::new(data);
+        reader.skip(4)?; // version
+        let report_id = read_guid(reader.bytes(16)?).unwrap_or_default();
+        let client_id = read_guid(reader.bytes(16)?).unwrap_or_default();
+        let simple_annotations = self.string_dictionary(reader.location()?)?;
+        let module_list_location = reader.location()?;
+
+        let mut module_list = Vec::new();
+        if module_list_location.data_size > 0 {
+            let (mut reader, count) = read_list(
+                self.location(module_list_location)?,
+                CRASHPAD_MODULE_LINK_SIZE,
+            )?;
+
+            for _ in 0..count {
+                let module_index = reader.u32()? as usize;
+                let mut module = StreamReader::new(self.location(reader.location()?)?);
+                module.skip(4)?; // version
+
+                module_list.push(MinidumpModuleCrashpadInfo {
+                    module_index,
+                    list_annotations: self.string_list(module.location()?)?,
+                    simple_annotations: self.string_dictionary(module.location()?)?,
+                    // annotation objects were added later and might be missing
+                    annotation_objects: match module.location() {
+                        Ok(location) => self.annotation_list(location)?,
+                        Err(_) => Vec::new(),
+                    },
+                });
+            }
+        }
+
+        Ok(Some(MinidumpCrashpadInfo {
+            report_id,
+            client_id,
+            simple_annotations,
+            module_list,
+        }))
+    }
+
+    /// Returns all modules that were unloaded from the process before the crash.
+    pub fn unloaded_modules(&self) -> Result<Vec<MinidumpUnloadedModule>, MinidumpError> {
+        let data = match self.raw_stream(StreamType::UnloadedModuleList)? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut reader = StreamReader::new(data);
+        let header_size = reader.u32()? as usize;
+        let entry_size = reader.u32()? as usize;
+        let count = reader.u32()? as usize;
+        reader.offset = header_size;
+
+        let mut modules = Vec::with_capacity(count.min(data.len() / entry_size.max(1)));
+        for _ in 0..count {
+            let mut entry = StreamReader::new(reader.bytes(entry_size)?);
+            let base_address = entry.u64()?;
+            let size = u64::from(entry.u32()?);
+            let checksum = entry.u32()?;
+            let time_date_stamp = entry.u32()?;
+            let name_rva = entry.u32()?;
+
+            modules.push(MinidumpUnloadedModule {
+                base_address,
+                size,
+                checksum,
+                time_date_stamp,
+                name: self.string(name_rva)?,
+            });
+        }
+
+        Ok(modules)
+    }
+}
+
+impl<'data> fmt::Debug for Minidump<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Minidump")
+            .field("header", &self.header)
+            .field("streams", &self.stream_types())
+            .finish()
+    }
+}
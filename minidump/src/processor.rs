@@ -10,12 +10,20 @@ use std::{fmt, ptr, slice, str};
 use failure::Fail;
 use lazy_static::lazy_static;
 use regex::Regex;
-use uuid::Uuid;
 
 use symbolic_common::byteview::ByteView;
-use symbolic_common::types::{Arch, CpuFamily, DebugId, ParseDebugIdError};
-
-use crate::cfi::CfiCache;
+use symbolic_common::types::{Arch, CpuFamily};
+
+use crate::exception::ExceptionInfo;
+use crate::linux::LinuxInfo;
+use crate::memory::{HexWindow, MemoryInfo, MemoryList};
+use crate::parser::{
+    FixedFileInfo, Minidump, MinidumpCrashpadInfo, MinidumpModuleCrashpadInfo, MinidumpThreadInfo,
+    MinidumpUnloadedModule,
+};
+pub use crate::stackwalker::{
+    CodeModuleId, FrameInfoMap, FrameTrust, ParseCodeModuleIdError, RegVal,
+};
 use crate::utils;
 
 lazy_static! {
@@ -24,28 +32,31 @@ lazy_static! {
 }
 
 extern "C" {
-    fn code_module_base_address(module: *const CodeModule) -> u64;
-    fn code_module_size(module: *const CodeModule) -> u64;
-    fn code_module_code_file(module: *const CodeModule) -> *mut c_char;
-    fn code_module_code_identifier(module: *const CodeModule) -> *mut c_char;
-    fn code_module_debug_file(module: *const CodeModule) -> *mut c_char;
-    fn code_module_debug_identifier(module: *const CodeModule) -> *mut c_char;
-    fn code_modules_delete(state: *mut *const CodeModule);
-
-    fn stack_frame_return_address(frame: *const StackFrame) -> u64;
-    fn stack_frame_instruction(frame: *const StackFrame) -> u64;
-    fn stack_frame_module(frame: *const StackFrame) -> *const CodeModule;
-    fn stack_frame_trust(frame: *const StackFrame) -> FrameTrust;
+    fn code_module_base_address(module: *const ICodeModule) -> u64;
+    fn code_module_size(module: *const ICodeModule) -> u64;
+    fn code_module_code_file(module: *const ICodeModule) -> *mut c_char;
+    fn code_module_code_identifier(module: *const ICodeModule) -> *mut c_char;
+    fn code_module_debug_file(module: *const ICodeModule) -> *mut c_char;
+    fn code_module_debug_identifier(module: *const ICodeModule) -> *mut c_char;
+    fn code_module_version(module: *const ICodeModule) -> *mut c_char;
+    fn code_modules_delete(state: *mut *const ICodeModule);
+
+    fn stack_frame_return_address(frame: *const IStackFrame) -> u64;
+    fn stack_frame_instruction(frame: *const IStackFrame) -> u64;
+    fn stack_frame_module(frame: *const IStackFrame) -> *const ICodeModule;
+    fn stack_frame_trust(frame: *const IStackFrame) -> FrameTrust;
     fn stack_frame_registers(
-        frame: *const StackFrame,
+        frame: *const IStackFrame,
         family: u32,
         size_out: *mut usize,
     ) -> *mut IRegVal;
     fn regval_delete(state: *mut IRegVal);
 
-    fn call_stack_thread_id(stack: *const CallStack) -> u32;
-    fn call_stack_frames(stack: *const CallStack, size_out: *mut usize)
-        -> *const *const StackFrame;
+    fn call_stack_thread_id(stack: *const ICallStack) -> u32;
+    fn call_stack_frames(
+        stack: *const ICallStack,
+        size_out: *mut usize,
+    ) -> *const *const IStackFrame;
 
     fn system_info_os_name(info: *const SystemInfo) -> *mut c_char;
     fn system_info_os_version(info: *const SystemInfo) -> *mut c_char;
@@ -64,7 +75,7 @@ extern "C" {
     fn process_state_threads(
         state: *const IProcessState,
         size_out: *mut usize,
-    ) -> *const *const CallStack;
+    ) -> *const *const ICallStack;
     fn process_state_requesting_thread(state: *const IProcessState) -> i32;
     fn process_state_timestamp(state: *const IProcessState) -> u64;
     fn process_state_crashed(state: *const IProcessState) -> bool;
@@ -75,101 +86,39 @@ extern "C" {
     fn process_state_modules(
         state: *const IProcessState,
         size_out: *mut usize,
-    ) -> *mut *const CodeModule;
+    ) -> *mut *const ICodeModule;
 }
 
-/// An error returned when parsing invalid `CodeModuleId`s.
-pub type ParseCodeModuleIdError = ParseDebugIdError;
-
-/// Breakpad code module IDs.
-///
-/// **Example:**
-///
-/// ```
-/// use std::str::FromStr;
-/// use symbolic_minidump::processor::CodeModuleId;
-/// # use symbolic_minidump::processor::ParseCodeModuleIdError;
-///
-/// # fn foo() -> Result<(), ParseCodeModuleIdError> {
-/// let id = CodeModuleId::from_str("DFB8E43AF2423D73A453AEB6A777EF75a")?;
-/// assert_eq!("DFB8E43AF2423D73A453AEB6A777EF75a".to_string(), id.to_string());
-/// # Ok(())
-/// # }
-///
-/// # fn main() { foo().unwrap() }
-/// ```
-#[derive(Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
-pub struct CodeModuleId {
-    inner: DebugId,
-}
-
-impl CodeModuleId {
-    /// Constructs a `CodeModuleId` from its `uuid` and `age` parts.
-    pub fn from_parts(uuid: Uuid, age: u32) -> CodeModuleId {
-        CodeModuleId {
-            inner: DebugId::from_parts(uuid, age),
-        }
-    }
+type ICodeModule = c_void;
 
-    /// Returns the UUID part of the code module id.
-    pub fn uuid(&self) -> Uuid {
-        self.inner.uuid()
-    }
-
-    /// Returns the appendix part of the code module id.
-    ///
-    /// On Windows, this is an incrementing counter to identify the build.
-    /// On all other platforms, this value will always be zero.
-    pub fn age(&self) -> u32 {
-        self.inner.appendix()
-    }
-
-    /// Converts this code module id into a debug identifier.
-    pub fn as_object_id(&self) -> DebugId {
-        self.inner
-    }
+/// Fields of a module in the minidump's module list that Breakpad does not retain.
+#[derive(Clone, Copy, Debug)]
+struct ModuleMetadata {
+    checksum: u32,
+    time_date_stamp: u32,
+    version_info: Option<FixedFileInfo>,
 }
 
-impl From<DebugId> for CodeModuleId {
-    fn from(inner: DebugId) -> Self {
-        CodeModuleId { inner }
-    }
-}
+/// Loaded modules and their metadata, keyed by base address.
+type ModuleMetadataMap = BTreeMap<u64, ModuleMetadata>;
 
-impl Into<DebugId> for CodeModuleId {
-    fn into(self) -> DebugId {
-        self.inner
-    }
-}
-
-impl fmt::Display for CodeModuleId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.inner.breakpad().fmt(f)
-    }
+/// Carries information about a code module loaded into the process during the
+/// crash. The `debug_identifier` uniquely identifies this module.
+#[derive(Clone, Copy)]
+pub struct CodeModule<'s> {
+    internal: &'s ICodeModule,
+    metadata: Option<&'s ModuleMetadata>,
 }
 
-impl str::FromStr for CodeModuleId {
-    type Err = ParseCodeModuleIdError;
-
-    fn from_str(string: &str) -> Result<CodeModuleId, ParseCodeModuleIdError> {
-        Ok(CodeModuleId {
-            inner: DebugId::from_breakpad(string)?,
-        })
+impl<'s> CodeModule<'s> {
+    fn new(internal: &'s ICodeModule, modules: &'s ModuleMetadataMap) -> Self {
+        let base_address = unsafe { code_module_base_address(internal) };
+        CodeModule {
+            internal,
+            metadata: modules.get(&base_address),
+        }
     }
-}
 
-#[cfg(feature = "with_serde")]
-serde_plain::derive_deserialize_from_str!(CodeModuleId, "CodeModuleId");
-
-#[cfg(feature = "with_serde")]
-serde_plain::derive_serialize_from_display!(CodeModuleId);
-
-/// Carries information about a code module loaded into the process during the
-/// crash. The `debug_identifier` uniquely identifies this module.
-#[repr(C)]
-pub struct CodeModule(c_void);
-
-impl CodeModule {
     /// Returns the unique identifier of this `CodeModule`.
     pub fn id(&self) -> Option<CodeModuleId> {
         CodeModuleId::from_str(&self.debug_identifier()).ok()
@@ -178,18 +127,18 @@ impl CodeModule {
     /// Returns the base address of this code module as it was loaded by the
     /// process. (uint64_t)-1 on error.
     pub fn base_address(&self) -> u64 {
-        unsafe { code_module_base_address(self) }
+        unsafe { code_module_base_address(self.internal) }
     }
 
     /// The size of the code module. 0 on error.
     pub fn size(&self) -> u64 {
-        unsafe { code_module_size(self) }
+        unsafe { code_module_size(self.internal) }
     }
 
     /// Returns the path or file name that the code module was loaded from.
     pub fn code_file(&self) -> String {
         unsafe {
-            let ptr = code_module_code_file(self);
+            let ptr = code_module_code_file(self.internal);
             utils::ptr_to_string(ptr)
         }
     }
@@ -201,7 +150,7 @@ impl CodeModule {
     /// information, in an implementation-defined format.
     pub fn code_identifier(&self) -> String {
         unsafe {
-            let ptr = code_module_code_identifier(self);
+            let ptr = code_module_code_identifier(self.internal);
             utils::ptr_to_string(ptr)
         }
     }
@@ -214,7 +163,7 @@ impl CodeModule {
     /// this will be the same as code_file.
     pub fn debug_file(&self) -> String {
         unsafe {
-            let ptr = code_module_debug_file(self);
+            let ptr = code_module_debug_file(self.internal);
             utils::ptr_to_string(ptr)
         }
     }
@@ -228,39 +177,75 @@ impl CodeModule {
     /// generation counter, on all other platforms it is mostly zero.
     pub fn debug_identifier(&self) -> String {
         unsafe {
-            let ptr = code_module_debug_identifier(self);
+            let ptr = code_module_debug_identifier(self.internal);
+            utils::ptr_to_string(ptr)
+        }
+    }
+
+    /// A human-readable representation of the module's file version, such as "6.2.14393.1715".
+    ///
+    /// This is only available for Windows modules that carry version information
+    /// (`VS_FIXEDFILEINFO`) and empty otherwise.
+    pub fn version(&self) -> String {
+        unsafe {
+            let ptr = code_module_version(self.internal);
             utils::ptr_to_string(ptr)
         }
     }
+
+    /// The checksum from the PE header, or zero.
+    pub fn checksum(&self) -> u32 {
+        self.metadata.map_or(0, |metadata| metadata.checksum)
+    }
+
+    /// The timestamp from the PE header, or zero.
+    pub fn time_date_stamp(&self) -> u32 {
+        self.metadata.map_or(0, |metadata| metadata.time_date_stamp)
+    }
+
+    /// Version information of Windows modules (`VS_FIXEDFILEINFO`), if available.
+    pub fn version_info(&self) -> Option<FixedFileInfo> {
+        self.metadata?.version_info
+    }
+
+    /// The four components of the module's file version, if available.
+    pub fn file_version(&self) -> Option<[u16; 4]> {
+        self.version_info().map(|info| info.file_version())
+    }
+
+    /// The four components of the module's product version, if available.
+    pub fn product_version(&self) -> Option<[u16; 4]> {
+        self.version_info().map(|info| info.product_version())
+    }
 }
 
-impl Eq for CodeModule {}
+impl Eq for CodeModule<'_> {}
 
-impl PartialEq for CodeModule {
+impl PartialEq for CodeModule<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.id() == other.id()
     }
 }
 
-impl Hash for CodeModule {
+impl Hash for CodeModule<'_> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.id().hash(state)
     }
 }
 
-impl Ord for CodeModule {
+impl Ord for CodeModule<'_> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.id().cmp(&other.id())
     }
 }
 
-impl PartialOrd for CodeModule {
+impl PartialOrd for CodeModule<'_> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl fmt::Debug for CodeModule {
+impl fmt::Debug for CodeModule<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("CodeModule")
             .field("id", &self.id())
@@ -274,52 +259,6 @@ impl fmt::Debug for CodeModule {
     }
 }
 
-/// Indicates how well the instruction pointer derived during
-/// stack walking is trusted. Since the stack walker can resort to
-/// stack scanning, it can wind up with dubious frames.
-///
-/// In rough order of "trust metric".
-#[repr(u32)]
-#[derive(Debug)]
-pub enum FrameTrust {
-    /// Unknown trust.
-    None,
-
-    /// Scanned the stack, found this (lowest precision).
-    Scan,
-
-    /// Found while scanning stack using call frame info.
-    CFIScan,
-
-    /// Derived from frame pointer.
-    FP,
-
-    /// Derived from call frame info.
-    CFI,
-
-    /// Explicitly provided by some external stack walker.
-    Prewalked,
-
-    /// Given as instruction pointer in a context (highest precision).
-    Context,
-}
-
-impl fmt::Display for FrameTrust {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let string = match *self {
-            FrameTrust::None => "none",
-            FrameTrust::Scan => "stack scanning",
-            FrameTrust::CFIScan => "call frame info with scanning",
-            FrameTrust::FP => "previous frame's frame pointer",
-            FrameTrust::CFI => "call frame info",
-            FrameTrust::Prewalked => "recovered by external stack walker",
-            FrameTrust::Context => "given as instruction pointer in context",
-        };
-
-        write!(f, "{}", string)
-    }
-}
-
 /// Helper for register values.
 #[repr(C)]
 struct IRegVal {
@@ -328,31 +267,18 @@ struct IRegVal {
     size: u8,
 }
 
-/// Value of a stack frame register.
-#[derive(Clone, Copy, Debug)]
-pub enum RegVal {
-    /// 32-bit register value.
-    U32(u32),
-    /// 64-bit register value.
-    U64(u64),
-}
-
-impl fmt::Display for RegVal {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            RegVal::U32(u) => write!(f, "{:#010x}", u),
-            RegVal::U64(u) => write!(f, "{:#018x}", u),
-        }
-    }
-}
+type IStackFrame = c_void;
 
 /// Contains information from the memorydump, especially the frame's instruction
 /// pointer. Also references an optional `CodeModule` that contains the
 /// instruction of this stack frame.
-#[repr(C)]
-pub struct StackFrame(c_void);
+#[derive(Clone, Copy)]
+pub struct StackFrame<'s> {
+    internal: &'s IStackFrame,
+    modules: &'s ModuleMetadataMap,
+}
 
-impl StackFrame {
+impl<'s> StackFrame<'s> {
     /// Returns the program counter location as an absolute virtual address.
     ///
     /// - For the innermost called frame in a stack, this will be an exact
@@ -377,14 +303,14 @@ impl StackFrame {
     ///
     /// Use `trust` to obtain how trustworthy this instruction is.
     pub fn instruction(&self) -> u64 {
-        unsafe { stack_frame_instruction(self) }
+        unsafe { stack_frame_instruction(self.internal) }
     }
 
     // Return the actual return address, as saved on the stack or in a
     // register. See the comments for `StackFrame::instruction' for
     // details.
     pub fn return_address(&self, arch: Arch) -> u64 {
-        let address = unsafe { stack_frame_return_address(self) };
+        let address = unsafe { stack_frame_return_address(self.internal) };
 
         // The return address reported for ARM* frames is actually the
         // instruction with heuristics from Breakpad applied already.
@@ -399,20 +325,35 @@ impl StackFrame {
     }
 
     /// Returns the `CodeModule` that contains this frame's instruction.
-    pub fn module(&self) -> Option<&CodeModule> {
-        unsafe { stack_frame_module(self).as_ref() }
+    pub fn module(&self) -> Option<CodeModule<'s>> {
+        let internal = unsafe { stack_frame_module(self.internal).as_ref()? };
+        Some(CodeModule::new(internal, self.modules))
     }
 
     /// Returns how well the instruction pointer is trusted.
     pub fn trust(&self) -> FrameTrust {
-        unsafe { stack_frame_trust(self) }
+        unsafe { stack_frame_trust(self.internal) }
+    }
+
+    /// Returns the value of the stack pointer in this frame, if known.
+    pub fn stack_pointer(&self, arch: Arch) -> Option<u64> {
+        let register = match arch.cpu_family() {
+            CpuFamily::Intel32 => "esp",
+            CpuFamily::Intel64 => "rsp",
+            CpuFamily::Arm32 | CpuFamily::Arm64 => "sp",
+            _ => return None,
+        };
+
+        self.registers(arch)
+            .get(register)
+            .map(|value| value.value())
     }
 
     /// Returns a mapping of registers to their known values, if any.
     pub fn registers(&self, arch: Arch) -> BTreeMap<&'static str, RegVal> {
         unsafe {
             let mut size = 0 as usize;
-            let values = stack_frame_registers(self, arch.cpu_family() as u32, &mut size);
+            let values = stack_frame_registers(self.internal, arch.cpu_family() as u32, &mut size);
             let map = slice::from_raw_parts(values, size)
                 .into_iter()
                 .filter_map(|v| {
@@ -433,7 +374,7 @@ impl StackFrame {
     }
 }
 
-impl fmt::Debug for StackFrame {
+impl fmt::Debug for StackFrame<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("StackFrame")
             .field("return_address", &self.return_address(Arch::Unknown))
@@ -444,27 +385,61 @@ impl fmt::Debug for StackFrame {
     }
 }
 
+type ICallStack = c_void;
+
 /// Represents a thread of the `ProcessState` which holds a list of `StackFrame`s.
-#[repr(C)]
-pub struct CallStack(c_void);
+///
+/// Breakpad only records the thread identifier of a call stack. Thread names and other
+/// information about the thread are read from the minidump separately and attached by the
+/// `ProcessState` that returns this call stack.
+#[derive(Clone, Copy)]
+pub struct CallStack<'s> {
+    internal: &'s ICallStack,
+    info: Option<&'s ThreadInfo>,
+    modules: &'s ModuleMetadataMap,
+}
 
-impl CallStack {
+impl<'s> CallStack<'s> {
     /// Returns the thread identifier of this callstack.
     pub fn thread_id(&self) -> u32 {
-        unsafe { call_stack_thread_id(self) }
+        unsafe { call_stack_thread_id(self.internal) }
     }
 
     /// Returns the list of `StackFrame`s in the call stack.
-    pub fn frames(&self) -> &[&StackFrame] {
-        unsafe {
+    pub fn frames(&self) -> Vec<StackFrame<'s>> {
+        let frames = unsafe {
             let mut size = 0 as usize;
-            let data = call_stack_frames(self, &mut size);
-            slice::from_raw_parts(data as *const &StackFrame, size)
-        }
+            let data = call_stack_frames(self.internal, &mut size);
+            slice::from_raw_parts(data as *const &IStackFrame, size)
+        };
+
+        frames
+            .iter()
+            .map(|&internal| StackFrame {
+                internal,
+                modules: self.modules,
+            })
+            .collect()
+    }
+
+    /// Returns the name of the thread, if available.
+    ///
+    /// Names are read from the thread names stream written on Windows and, for Linux dumps, from
+    /// Crashpad annotations (see `MinidumpCrashpadInfo::thread_names`).
+    pub fn thread_name(&self) -> Option<&'s str> {
+        self.thread_info()?.name.as_deref()
+    }
+
+    /// Returns additional information about the thread, if available.
+    ///
+    /// Creation times and affinity are only available if the minidump contains extended thread
+    /// information, which is mostly the case on Windows.
+    pub fn thread_info(&self) -> Option<&'s ThreadInfo> {
+        self.info
     }
 }
 
-impl fmt::Debug for CallStack {
+impl fmt::Debug for CallStack<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("CallStack")
             .field("thread_id", &self.thread_id())
@@ -473,6 +448,46 @@ impl fmt::Debug for CallStack {
     }
 }
 
+/// Seconds between the Windows `FILETIME` epoch (1601) and the UNIX epoch (1970).
+const FILETIME_UNIX_OFFSET: u64 = 11_644_473_600;
+
+/// Converts a Windows `FILETIME` to seconds since the UNIX epoch, or `None` if it is not set.
+fn filetime_to_unix(filetime: u64) -> Option<u64> {
+    match filetime {
+        0 => None,
+        _ => (filetime / 10_000_000).checked_sub(FILETIME_UNIX_OFFSET),
+    }
+}
+
+/// Additional information about a thread that Breakpad does not retain, see
+/// `CallStack::thread_info`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ThreadInfo {
+    /// The name of the thread, if it was recorded.
+    pub name: Option<String>,
+    /// The priority class of the thread.
+    pub priority_class: u32,
+    /// The priority level of the thread.
+    pub priority: u32,
+    /// The time the thread was created, in seconds since the UNIX epoch.
+    pub create_time: Option<u64>,
+    /// The exit status of the thread, which is `STILL_ACTIVE` (259) for running threads.
+    pub exit_status: Option<u32>,
+    /// The start address of the thread.
+    pub start_address: Option<u64>,
+    /// The processor affinity mask of the thread.
+    pub affinity: Option<u64>,
+}
+
+impl ThreadInfo {
+    fn set_extended(&mut self, info: &MinidumpThreadInfo) {
+        self.create_time = filetime_to_unix(info.create_time);
+        self.exit_status = Some(info.exit_status);
+        self.start_address = Some(info.start_address);
+        self.affinity = Some(info.affinity);
+    }
+}
+
 /// Information about the CPU and OS on which a minidump was generated.
 #[repr(C)]
 pub struct SystemInfo(c_void);
@@ -653,19 +668,21 @@ struct SymbolEntry {
     symbol_data: *const u8,
 }
 
-/// Container for call frame information (CFI) of `CodeModules`.
-///
-/// This information is required by the stackwalker in case framepointers are
-/// missing in the raw stacktraces. Frame information is given as plain ASCII
-/// text as specified in the Breakpad symbol file specification.
-pub type FrameInfoMap<'a> = BTreeMap<CodeModuleId, CfiCache<'a>>;
-
 type IProcessState = c_void;
 
 /// Snapshot of the state of a processes during its crash. The object can be
 /// obtained by processing Minidump or Microdump files.
 pub struct ProcessState<'a> {
     internal: *mut IProcessState,
+    exception: Option<ExceptionInfo>,
+    memory: MemoryList<'static>,
+    memory_info: Vec<MemoryInfo>,
+    linux_info: Option<LinuxInfo>,
+    crashpad_info: Option<MinidumpCrashpadInfo>,
+    crashpad_modules: BTreeMap<u64, usize>,
+    module_metadata: ModuleMetadataMap,
+    unloaded_modules: Vec<MinidumpUnloadedModule>,
+    thread_infos: BTreeMap<u32, ThreadInfo>,
     _ty: PhantomData<ByteView<'a>>,
 }
 
@@ -683,14 +700,15 @@ impl<'a> ProcessState<'a> {
         let cfi_count = frame_infos.map_or(0, |s| s.len());
         let mut result: ProcessResult = ProcessResult::Ok;
 
-        // Keep a reference to all CStrings to extend their lifetime.
+        // Keep a reference to all CStrings and ASCII buffers to extend their lifetime. Binary
+        // caches are converted to ASCII, as this is the only format the stackwalker understands.
+        // Malformed caches are passed as empty buffers.
         let cfi_vec: Vec<_> = frame_infos.map_or(Vec::new(), |s| {
             s.iter()
                 .map(|(k, v)| {
                     (
                         CString::new(k.to_string()),
-                        v.as_slice().len(),
-                        v.as_slice().as_ptr(),
+                        v.to_ascii().unwrap_or_default(),
                     )
                 })
                 .collect()
@@ -699,10 +717,10 @@ impl<'a> ProcessState<'a> {
         // Keep a reference to all symbol entries to extend their lifetime.
         let cfi_entries: Vec<_> = cfi_vec
             .iter()
-            .map(|&(ref id, size, data)| SymbolEntry {
+            .map(|(id, data)| SymbolEntry {
                 debug_identifier: id.as_ref().map(|i| i.as_ptr()).unwrap_or(ptr::null()),
-                symbol_size: size,
-                symbol_data: data,
+                symbol_size: data.len(),
+                symbol_data: data.as_ptr(),
             })
             .collect();
 
@@ -717,8 +735,94 @@ impl<'a> ProcessState<'a> {
         };
 
         if result == ProcessResult::Ok && !internal.is_null() {
+            // Breakpad only exposes a formatted crash reason, no memory and no Linux streams, so
+            // these are decoded from the minidump directly. The memory is copied, since the
+            // buffer is not retained.
+            let mut exception = None;
+            let mut memory = MemoryList::default();
+            let mut memory_info = Vec::new();
+            let mut linux_info = None;
+            let mut crashpad_info = None;
+            let mut crashpad_modules = BTreeMap::new();
+            let mut module_metadata = BTreeMap::new();
+            let mut unloaded_modules = Vec::new();
+            let mut thread_infos = BTreeMap::new();
+
+            if let Ok(minidump) = Minidump::parse(buffer) {
+                exception = ExceptionInfo::from_minidump(&minidump).unwrap_or(None);
+                memory = MemoryList::from_minidump(&minidump)
+                    .map(MemoryList::into_owned)
+                    .unwrap_or_default();
+                memory_info = MemoryInfo::from_minidump(&minidump).unwrap_or_default();
+                linux_info = LinuxInfo::from_minidump(&minidump).unwrap_or(None);
+                crashpad_info = minidump.crashpad_info().unwrap_or(None);
+
+                // Crashpad refers to modules by index, which is resolved to the base address to
+                // match `CodeModule`s.
+                let modules = minidump.modules().unwrap_or_default();
+                if let Some(ref info) = crashpad_info {
+                    for (index, module_info) in info.module_list.iter().enumerate() {
+                        if let Some(module) = modules.get(module_info.module_index) {
+                            crashpad_modules.insert(module.base_address, index);
+                        }
+                    }
+                }
+
+                for module in &modules {
+                    let metadata = ModuleMetadata {
+                        checksum: module.checksum,
+                        time_date_stamp: module.time_date_stamp,
+                        version_info: module.version_info,
+                    };
+                    module_metadata.insert(module.base_address, metadata);
+                }
+
+                unloaded_modules = minidump.unloaded_modules().unwrap_or_default();
+
+                for thread in minidump.threads().unwrap_or_default() {
+                    let info = ThreadInfo {
+                        priority_class: thread.priority_class,
+                        priority: thread.priority,
+                        ..ThreadInfo::default()
+                    };
+                    thread_infos.insert(thread.thread_id, info);
+                }
+
+                for info in minidump.thread_info_list().unwrap_or_default() {
+                    thread_infos
+                        .entry(info.thread_id)
+                        .or_default()
+                        .set_extended(&info);
+                }
+
+                for (thread_id, name) in minidump.thread_names().unwrap_or_default() {
+                    thread_infos.entry(thread_id).or_default().name = Some(name);
+                }
+
+                // Linux dumps carry thread names in Crashpad annotations, if at all. Names from
+                // the thread names stream take precedence.
+                if let Some(ref info) = crashpad_info {
+                    for (thread_id, name) in info.thread_names() {
+                        thread_infos
+                            .entry(thread_id)
+                            .or_default()
+                            .name
+                            .get_or_insert(name);
+                    }
+                }
+            }
+
             Ok(ProcessState {
                 internal,
+                exception,
+                memory,
+                memory_info,
+                linux_info,
+                crashpad_info,
+                crashpad_modules,
+                module_metadata,
+                unloaded_modules,
+                thread_infos,
                 _ty: PhantomData,
             })
         } else {
@@ -768,6 +872,62 @@ impl<'a> ProcessState<'a> {
         }
     }
 
+    /// If the process crashed, structured information on the exception that caused the crash.
+    ///
+    /// In contrast to `crash_reason` and `crash_address`, this exposes the raw OS specific code,
+    /// its subcode, the exception parameters and the context of the crashing thread.
+    pub fn exception_info(&self) -> Option<&ExceptionInfo> {
+        self.exception.as_ref()
+    }
+
+    /// Returns the signal that caused the crash if the call stack belongs to the crashing thread.
+    ///
+    /// For all other threads, this returns `None`. The signal can be passed as
+    /// `InstructionInfo::signal` in `symbolic-symcache` for the top frame of the call stack.
+    pub fn crash_signal(&self, stack: &CallStack<'_>) -> Option<u32> {
+        self.exception_info()
+            .filter(|exception| exception.thread_id == stack.thread_id())
+            .and_then(ExceptionInfo::signal)
+    }
+
+    /// Returns all memory captured in the minidump.
+    ///
+    /// This usually contains the stacks of all threads and memory around the crashing
+    /// instruction. Full memory dumps contain the entire address space of the process.
+    pub fn memory(&self) -> &MemoryList<'static> {
+        &self.memory
+    }
+
+    /// Reads `size` bytes of captured memory starting at `address`.
+    ///
+    /// Returns `None` unless the entire range was captured in the minidump.
+    pub fn read_memory(&self, address: u64, size: u64) -> Option<&[u8]> {
+        self.memory.read(address, size)
+    }
+
+    /// Returns the layout of the crashed process' address space.
+    ///
+    /// This is only available for Windows minidumps.
+    pub fn memory_info(&self) -> &[MemoryInfo] {
+        &self.memory_info
+    }
+
+    /// Returns a window of captured memory around the given address for rendering.
+    ///
+    /// See `MemoryList::hex_window` for more information.
+    pub fn hex_window(&self, address: u64, context: u64) -> Option<HexWindow<'_>> {
+        self.memory.hex_window(address, context)
+    }
+
+    /// Returns a window of captured memory around the crash address, if it was captured.
+    pub fn crash_window(&self, context: u64) -> Option<HexWindow<'_>> {
+        if !self.crashed() {
+            return None;
+        }
+
+        self.hex_window(self.crash_address(), context)
+    }
+
     /// If there was an assertion that was hit, a textual representation
     /// of that assertion, possibly including the file and line at which
     /// it occurred.
@@ -783,31 +943,101 @@ impl<'a> ProcessState<'a> {
         unsafe { process_state_system_info(self.internal).as_ref().unwrap() }
     }
 
+    /// Returns Linux specific information about the process and its system.
+    ///
+    /// This includes memory mappings, process status, command line, environment, auxiliary vector
+    /// and distribution information. It is only available for minidumps written on Linux.
+    pub fn linux_info(&self) -> Option<&LinuxInfo> {
+        self.linux_info.as_ref()
+    }
+
+    /// Returns information stored by Crashpad, if the minidump was written by Crashpad.
+    ///
+    /// This includes the report and client identifiers as well as process-level annotations.
+    pub fn crashpad_info(&self) -> Option<&MinidumpCrashpadInfo> {
+        self.crashpad_info.as_ref()
+    }
+
+    /// Returns annotations that Crashpad recorded for the given module.
+    pub fn module_crashpad_info(
+        &self,
+        module: &CodeModule<'_>,
+    ) -> Option<&MinidumpModuleCrashpadInfo> {
+        let index = *self.crashpad_modules.get(&module.base_address())?;
+        self.crashpad_info.as_ref()?.module_list.get(index)
+    }
+
     /// Returns a list of `CallStack`s in the minidump.
-    pub fn threads(&self) -> &[&CallStack] {
-        unsafe {
+    pub fn threads(&self) -> Vec<CallStack<'_>> {
+        let stacks = unsafe {
             let mut size = 0 as usize;
             let data = process_state_threads(self.internal, &mut size);
-            slice::from_raw_parts(data as *const &CallStack, size)
-        }
+            slice::from_raw_parts(data as *const &ICallStack, size)
+        };
+
+        stacks
+            .iter()
+            .map(|&internal| {
+                let thread_id = unsafe { call_stack_thread_id(internal) };
+                CallStack {
+                    internal,
+                    info: self.thread_infos.get(&thread_id),
+                    modules: &self.module_metadata,
+                }
+            })
+            .collect()
     }
 
     /// Returns the full list of loaded `CodeModule`s.
-    pub fn modules(&self) -> Vec<&CodeModule> {
+    pub fn modules(&self) -> Vec<CodeModule<'_>> {
         unsafe {
             let mut size = 0 as usize;
             let data = process_state_modules(self.internal, &mut size);
-            let vec = slice::from_raw_parts(data as *mut &CodeModule, size).to_vec();
+            let vec = slice::from_raw_parts(data as *mut &ICodeModule, size)
+                .iter()
+                .map(|&internal| CodeModule::new(internal, &self.module_metadata))
+                .collect();
             code_modules_delete(data);
             vec
         }
     }
 
+    /// Returns the loaded `CodeModule` that contains the given address.
+    pub fn find_module(&self, address: u64) -> Option<CodeModule<'_>> {
+        self.modules().into_iter().find(|module| {
+            address >= module.base_address() && address - module.base_address() < module.size()
+        })
+    }
+
+    /// Returns modules that were unloaded from the process before the crash.
+    ///
+    /// This is only available on Windows. Modules that were loaded and unloaded repeatedly
+    /// may be listed multiple times.
+    pub fn unloaded_modules(&self) -> &[MinidumpUnloadedModule] {
+        &self.unloaded_modules
+    }
+
+    /// Returns the unloaded module that contained the given address.
+    ///
+    /// This is used to identify crashes in code that was freed, for instance after returning into
+    /// an unloaded DLL. Returns `None` if a loaded module contains the address. If multiple
+    /// unloaded modules contained the address, the one listed last is returned.
+    pub fn find_unloaded_module(&self, address: u64) -> Option<&MinidumpUnloadedModule> {
+        if self.find_module(address).is_some() {
+            return None;
+        }
+
+        self.unloaded_modules
+            .iter()
+            .rev()
+            .find(|module| address >= module.base_address && address < module.end())
+    }
+
     /// Returns a list of all `CodeModule`s referenced in one of the `CallStack`s.
-    pub fn referenced_modules(&self) -> BTreeSet<&CodeModule> {
+    pub fn referenced_modules(&self) -> BTreeSet<CodeModule<'_>> {
         self.threads()
-            .iter()
-            .flat_map(|stack| stack.frames().iter())
+            .into_iter()
+            .flat_map(|stack| stack.frames())
             .filter_map(|frame| frame.module())
             .collect()
     }
@@ -826,6 +1056,7 @@ impl<'a> fmt::Debug for ProcessState<'a> {
             .field("timestamp", &self.timestamp())
             .field("crash_address", &self.crash_address())
             .field("crash_reason", &self.crash_reason())
+            .field("exception_info", &self.exception_info())
             .field("assertion", &self.assertion())
             .field("system_info", &self.system_info())
             .field("threads", &self.threads())
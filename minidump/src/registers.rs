@@ -1,19 +1,35 @@
-use symbolic_common::types::{Arch, CpuFamily, UnknownArchError};
+use gimli::Register;
+use symbolic_common::types::{Arch, CpuFamily};
+
+/// An error returned by `get_register_name`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RegisterNameError {
+    /// Register names for the CPU family are not known.
+    UnknownArch,
+    /// The register number does not exist for the CPU family.
+    InvalidRegister,
+}
 
 /// Returns the name of a register in a given architecture.
 ///
 /// This can fail if register names for the CPU family are not known or the register number is
 /// invalid.
-pub fn get_register_name(arch: Arch, register: u8) -> Result<&'static str, UnknownArchError> {
-    let index = register as usize;
+pub fn get_register_name(
+    arch: Arch,
+    register: Register,
+) -> Result<&'static str, RegisterNameError> {
+    let registers = match arch.cpu_family() {
+        CpuFamily::Intel32 => I386,
+        CpuFamily::Intel64 => X86_64,
+        CpuFamily::Arm64 => ARM64,
+        CpuFamily::Arm32 => ARM,
+        _ => return Err(RegisterNameError::UnknownArch),
+    };
 
-    Ok(match arch.cpu_family() {
-        CpuFamily::Intel32 => I386[index],
-        CpuFamily::Intel64 => X86_64[index],
-        CpuFamily::Arm64 => ARM64[index],
-        CpuFamily::Arm32 => ARM[index],
-        _ => return Err(UnknownArchError),
-    })
+    registers
+        .get(register.0 as usize)
+        .cloned()
+        .ok_or(RegisterNameError::InvalidRegister)
 }
 
 /// Names for x86 CPU registers by register number.
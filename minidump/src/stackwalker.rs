@@ -0,0 +1,1030 @@
+//! A stackwalker for minidumps written in pure Rust.
+//!
+//! The stackwalker recovers the call stacks of all threads in a minidump from their CPU contexts
+//! and stack memory. It supports x86, x86_64, ARM and ARM64. For every frame, the following
+//! strategies are tried in order:
+//!
+//!  1. Call frame information (CFI) from a `CfiCache` of the module, including `STACK WIN`
+//!     records for Windows x86 modules.
+//!  2. Frame pointers, if the frame pointer register points into the stack.
+//!  3. Stack scanning for values that look like return addresses into a loaded module.
+//!
+//! Return addresses recovered from frame pointers or by scanning can additionally be checked
+//! against the functions of a module, for instance from a `SymCache`, with
+//! `Stackwalker::set_function_lookup`. This rejects values that point into a module, but not
+//! into any of its functions.
+//!
+//! The `FrameTrust` of every frame indicates which of these strategies produced the frame.
+//!
+//! **Example:**
+//!
+//! ```
+//! # use symbolic_minidump::parser::Minidump;
+//! # use symbolic_minidump::stackwalker::Stackwalker;
+//! # use symbolic_testutils::fixture_path;
+//! # fn main() -> Result<(), failure::Error> {
+//! let data = std::fs::read(fixture_path("linux/mini.dmp"))?;
+//! let minidump = Minidump::parse(&data)?;
+//! let walker = Stackwalker::new(&minidump, None)?;
+//!
+//! for thread in walker.walk() {
+//!     for frame in &thread.frames {
+//!         println!("{:#x} ({})", frame.instruction, frame.trust);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str;
+
+use uuid::Uuid;
+
+use symbolic_common::types::{Arch, CpuFamily, DebugId, ParseDebugIdError};
+
+use crate::cfi::{CfiCache, CfiOp, CfiRow, UnwindRule};
+use crate::parser::{Minidump, MinidumpError, MinidumpMemory, MinidumpModule, MinidumpThread};
+use symbolic_debuginfo::BreakpadStackWinRecord;
+
+/// The maximum number of frames recovered for a single thread.
+const MAX_FRAMES: usize = 1024;
+
+/// The number of words searched for a return address when scanning the stack.
+const SCAN_WORDS: usize = 40;
+
+/// The number of words searched for a return address of the context frame.
+///
+/// The context frame is often in a leaf function that has not set up a frame, so the search
+/// extends further than for other frames.
+const SCAN_WORDS_CONTEXT: usize = 4 * SCAN_WORDS;
+
+/// An error returned when parsing invalid `CodeModuleId`s.
+pub type ParseCodeModuleIdError = ParseDebugIdError;
+
+/// Breakpad code module IDs.
+///
+/// **Example:**
+///
+/// ```
+/// use std::str::FromStr;
+/// use symbolic_minidump::stackwalker::CodeModuleId;
+/// # use symbolic_minidump::stackwalker::ParseCodeModuleIdError;
+///
+/// # fn foo() -> Result<(), ParseCodeModuleIdError> {
+/// let id = CodeModuleId::from_str("DFB8E43AF2423D73A453AEB6A777EF75a")?;
+/// assert_eq!("DFB8E43AF2423D73A453AEB6A777EF75a".to_string(), id.to_string());
+/// # Ok(())
+/// # }
+///
+/// # fn main() { foo().unwrap() }
+/// ```
+#[derive(Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
+pub struct CodeModuleId {
+    inner: DebugId,
+}
+
+impl CodeModuleId {
+    /// Constructs a `CodeModuleId` from its `uuid` and `age` parts.
+    pub fn from_parts(uuid: Uuid, age: u32) -> CodeModuleId {
+        CodeModuleId {
+            inner: DebugId::from_parts(uuid, age),
+        }
+    }
+
+    /// Returns the UUID part of the code module id.
+    pub fn uuid(&self) -> Uuid {
+        self.inner.uuid()
+    }
+
+    /// Returns the appendix part of the code module id.
+    ///
+    /// On Windows, this is an incrementing counter to identify the build.
+    /// On all other platforms, this value will always be zero.
+    pub fn age(&self) -> u32 {
+        self.inner.appendix()
+    }
+
+    /// Converts this code module id into a debug identifier.
+    pub fn as_object_id(&self) -> DebugId {
+        self.inner
+    }
+}
+
+impl From<DebugId> for CodeModuleId {
+    fn from(inner: DebugId) -> Self {
+        CodeModuleId { inner }
+    }
+}
+
+impl From<CodeModuleId> for DebugId {
+    fn from(id: CodeModuleId) -> Self {
+        id.inner
+    }
+}
+
+impl fmt::Display for CodeModuleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.breakpad().fmt(f)
+    }
+}
+
+impl str::FromStr for CodeModuleId {
+    type Err = ParseCodeModuleIdError;
+
+    fn from_str(string: &str) -> Result<CodeModuleId, ParseCodeModuleIdError> {
+        Ok(CodeModuleId {
+            inner: DebugId::from_breakpad(string)?,
+        })
+    }
+}
+
+#[cfg(feature = "with_serde")]
+serde_plain::derive_deserialize_from_str!(CodeModuleId, "CodeModuleId");
+
+#[cfg(feature = "with_serde")]
+serde_plain::derive_serialize_from_display!(CodeModuleId);
+
+/// Container for call frame information (CFI) of `CodeModules`.
+///
+/// This information is required by the stackwalker in case framepointers are
+/// missing in the raw stacktraces. Frame information is given as `CfiCache`s,
+/// which are converted to STACK records as specified in the Breakpad symbol
+/// file specification.
+pub type FrameInfoMap<'a> = BTreeMap<CodeModuleId, CfiCache<'a>>;
+
+/// Indicates how well the instruction pointer derived during
+/// stack walking is trusted. Since the stack walker can resort to
+/// stack scanning, it can wind up with dubious frames.
+///
+/// In rough order of "trust metric".
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FrameTrust {
+    /// Unknown trust.
+    None,
+
+    /// Scanned the stack, found this (lowest precision).
+    Scan,
+
+    /// Found while scanning stack using call frame info.
+    CFIScan,
+
+    /// Derived from frame pointer.
+    FP,
+
+    /// Derived from call frame info.
+    CFI,
+
+    /// Explicitly provided by some external stack walker.
+    Prewalked,
+
+    /// Given as instruction pointer in a context (highest precision).
+    Context,
+}
+
+impl fmt::Display for FrameTrust {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string = match *self {
+            FrameTrust::None => "none",
+            FrameTrust::Scan => "stack scanning",
+            FrameTrust::CFIScan => "call frame info with scanning",
+            FrameTrust::FP => "previous frame's frame pointer",
+            FrameTrust::CFI => "call frame info",
+            FrameTrust::Prewalked => "recovered by external stack walker",
+            FrameTrust::Context => "given as instruction pointer in context",
+        };
+
+        write!(f, "{}", string)
+    }
+}
+
+/// Value of a stack frame register.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RegVal {
+    /// 32-bit register value.
+    U32(u32),
+    /// 64-bit register value.
+    U64(u64),
+}
+
+impl RegVal {
+    /// Returns the value of the register, zero-extended to 64 bits.
+    pub fn value(self) -> u64 {
+        match self {
+            RegVal::U32(u) => u64::from(u),
+            RegVal::U64(u) => u,
+        }
+    }
+}
+
+impl fmt::Display for RegVal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RegVal::U32(u) => write!(f, "{:#010x}", u),
+            RegVal::U64(u) => write!(f, "{:#018x}", u),
+        }
+    }
+}
+
+/// Register layout of a CPU family supported by the stackwalker.
+struct CpuInfo {
+    /// Size of a pointer in bytes.
+    word_size: u64,
+    /// All registers in the order they are reported, as named by Breakpad.
+    registers: &'static [&'static str],
+    /// Registers preserved across calls, which are inherited by the caller if not recovered.
+    callee_saved: &'static [&'static str],
+    /// The instruction pointer register.
+    ip: &'static str,
+    /// The stack pointer register.
+    sp: &'static str,
+    /// The frame pointer register.
+    fp: &'static str,
+    /// The offset of the return address relative to the frame pointer.
+    ra_offset: u64,
+    /// The adjustment from a return address to an address within the call instruction.
+    call_adjust: u64,
+}
+
+static X86_INFO: CpuInfo = CpuInfo {
+    word_size: 4,
+    registers: &[
+        "eip", "esp", "ebp", "ebx", "esi", "edi", "eax", "ecx", "edx", "eflags",
+    ],
+    callee_saved: &["ebp", "ebx", "esi", "edi"],
+    ip: "eip",
+    sp: "esp",
+    fp: "ebp",
+    ra_offset: 4,
+    call_adjust: 1,
+};
+
+static AMD64_INFO: CpuInfo = CpuInfo {
+    word_size: 8,
+    registers: &[
+        "rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11", "r12",
+        "r13", "r14", "r15", "rip",
+    ],
+    callee_saved: &["rbx", "rbp", "r12", "r13", "r14", "r15"],
+    ip: "rip",
+    sp: "rsp",
+    fp: "rbp",
+    ra_offset: 8,
+    call_adjust: 1,
+};
+
+static ARM_INFO: CpuInfo = CpuInfo {
+    word_size: 4,
+    registers: &[
+        "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10", "fp", "r12", "sp", "lr",
+        "pc",
+    ],
+    callee_saved: &["r4", "r5", "r6", "r7", "r8", "r9", "r10", "fp"],
+    ip: "pc",
+    sp: "sp",
+    fp: "fp",
+    ra_offset: 4,
+    call_adjust: 2,
+};
+
+static ARM64_INFO: CpuInfo = CpuInfo {
+    word_size: 8,
+    registers: &[
+        "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+        "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26",
+        "x27", "x28", "x29", "x30", "sp", "pc",
+    ],
+    callee_saved: &[
+        "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26", "x27", "x28", "x29",
+    ],
+    ip: "pc",
+    sp: "sp",
+    fp: "x29",
+    ra_offset: 8,
+    call_adjust: 4,
+};
+
+impl CpuInfo {
+    fn from_arch(arch: Arch) -> Option<&'static CpuInfo> {
+        match arch.cpu_family() {
+            CpuFamily::Intel32 => Some(&X86_INFO),
+            CpuFamily::Intel64 => Some(&AMD64_INFO),
+            CpuFamily::Arm32 => Some(&ARM_INFO),
+            CpuFamily::Arm64 => Some(&ARM64_INFO),
+            _ => None,
+        }
+    }
+
+    /// Resolves a register name used in CFI or STACK WIN programs to its canonical name.
+    fn register(&self, name: &str) -> Option<&'static str> {
+        let name = name.trim_start_matches('$');
+        let name = match name {
+            "r11" if self.fp == "fp" => "fp",
+            "r13" if self.sp == "sp" => "sp",
+            "r14" => "lr",
+            "r15" => "pc",
+            "efl" | "eflags" => "eflags",
+            other => other,
+        };
+
+        self.registers.iter().find(|reg| **reg == name).cloned()
+    }
+
+    /// Truncates a value to the word size of this CPU.
+    fn mask(&self, value: u64) -> u64 {
+        if self.word_size == 4 {
+            value & 0xffff_ffff
+        } else {
+            value
+        }
+    }
+
+    fn reg_val(&self, value: u64) -> RegVal {
+        if self.word_size == 4 {
+            RegVal::U32(value as u32)
+        } else {
+            RegVal::U64(value)
+        }
+    }
+
+    /// Parses the raw CPU context of a thread into register values.
+    fn parse_context(&self, data: &[u8]) -> Option<Registers> {
+        let read32 = |offset: usize| {
+            data.get(offset..offset + 4)
+                .map(|b| u64::from(u32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+        };
+        let read64 = |offset: usize| {
+            data.get(offset..offset + 8).map(|b| {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(b);
+                u64::from_le_bytes(bytes)
+            })
+        };
+
+        let mut registers = Registers::new();
+        if self.ip == "eip" {
+            // MDRawContextX86
+            let offsets = [
+                ("edi", 156),
+                ("esi", 160),
+                ("ebx", 164),
+                ("edx", 168),
+                ("ecx", 172),
+                ("eax", 176),
+                ("ebp", 180),
+                ("eip", 184),
+                ("eflags", 192),
+                ("esp", 196),
+            ];
+            for &(name, offset) in &offsets {
+                registers.insert(name, read32(offset)?);
+            }
+        } else if self.ip == "rip" {
+            // MDRawContextAMD64
+            let names = [
+                "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11",
+                "r12", "r13", "r14", "r15", "rip",
+            ];
+            for (index, name) in names.iter().enumerate() {
+                registers.insert(name, read64(120 + 8 * index)?);
+            }
+        } else if self.word_size == 4 {
+            // MDRawContextARM
+            for (index, name) in self.registers.iter().enumerate() {
+                registers.insert(name, read32(4 + 4 * index)?);
+            }
+        } else {
+            // MDRawContextARM64. General purpose registers, sp and pc have the same offsets in
+            // the legacy Breakpad layout and the layout used by Windows and Crashpad.
+            for (index, name) in self.registers.iter().enumerate() {
+                registers.insert(name, read64(8 + 8 * index)?);
+            }
+        }
+
+        Some(registers)
+    }
+}
+
+/// Register values of a frame.
+type Registers = BTreeMap<&'static str, u64>;
+
+/// A frame recovered by the `Stackwalker`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Frame<'data> {
+    /// The address of the instruction in this frame.
+    ///
+    /// For the context frame, this is the instruction pointer. For all other frames, this points
+    /// into the call instruction, so that it resolves to the location of the call when
+    /// symbolicating.
+    pub instruction: u64,
+    /// The return address as saved by the machine, or the instruction pointer of the context frame.
+    pub return_address: u64,
+    /// How well the instruction of this frame is trusted.
+    pub trust: FrameTrust,
+    /// All registers known in this frame.
+    pub registers: BTreeMap<&'static str, RegVal>,
+    /// The module containing the instruction of this frame.
+    pub module: Option<MinidumpModule<'data>>,
+}
+
+/// The call stack of a thread recovered by the `Stackwalker`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ThreadStack<'data> {
+    /// The identifier of the thread.
+    pub thread_id: u32,
+    /// The frames of the call stack, starting with the innermost frame.
+    pub frames: Vec<Frame<'data>>,
+}
+
+/// Evaluates Breakpad postfix expressions and programs.
+///
+/// Supported are the binary operators `+ - * / % @`, the dereference operator `^` and assignments
+/// with `=`. Identifiers are resolved from the variables, or with the given resolver.
+struct PostfixEvaluator<'a> {
+    cpu: &'static CpuInfo,
+    memory: &'a dyn Fn(u64) -> Option<u64>,
+    variables: BTreeMap<String, u64>,
+}
+
+impl<'a> PostfixEvaluator<'a> {
+    fn new(cpu: &'static CpuInfo, memory: &'a dyn Fn(u64) -> Option<u64>) -> Self {
+        PostfixEvaluator {
+            cpu,
+            memory,
+            variables: BTreeMap::new(),
+        }
+    }
+
+    fn set(&mut self, name: &str, value: u64) {
+        self.variables.insert(name.to_string(), value);
+    }
+
+    fn get(&self, name: &str) -> Option<u64> {
+        self.variables.get(name).cloned()
+    }
+
+    /// Evaluates an expression or program in Breakpad's postfix notation.
+    fn evaluate(&mut self, expression: &str) -> Result<Option<u64>, ()> {
+        self.evaluate_ops(&CfiOp::compile(expression.as_bytes()))
+    }
+
+    /// Evaluates a compiled expression or program.
+    ///
+    /// Returns the value remaining on the stack for expressions, or `None` after a program that
+    /// only consists of assignments. Returns `Err` if the expression is invalid.
+    fn evaluate_ops(&mut self, ops: &[CfiOp<'_>]) -> Result<Option<u64>, ()> {
+        // Each entry holds the name of a variable operand, and its value if it is known.
+        let mut stack: Vec<(Option<&[u8]>, Option<u64>)> = Vec::new();
+
+        for op in ops {
+            match *op {
+                CfiOp::Const(value) => stack.push((None, Some(value))),
+                CfiOp::Variable(name) => {
+                    let value = str::from_utf8(name).ok().and_then(|name| self.get(name));
+                    stack.push((Some(name), value));
+                }
+                CfiOp::Deref => {
+                    let address = stack.pop().ok_or(())?.1.ok_or(())?;
+                    stack.push((None, (self.memory)(address)));
+                }
+                CfiOp::Assign => {
+                    let (_, value) = stack.pop().ok_or(())?;
+                    let name = stack.pop().ok_or(())?.0.ok_or(())?;
+                    let name = str::from_utf8(name).map_err(|_| ())?;
+
+                    // Assigning an unreadable value removes the variable, so that registers
+                    // that cannot be recovered are not reported.
+                    match value {
+                        Some(value) => self.set(name, value),
+                        None => {
+                            self.variables.remove(name);
+                        }
+                    }
+                }
+                _ => {
+                    let b = stack.pop().ok_or(())?.1.ok_or(())?;
+                    let a = stack.pop().ok_or(())?.1.ok_or(())?;
+
+                    let result = match *op {
+                        CfiOp::Add => a.wrapping_add(b),
+                        CfiOp::Sub => a.wrapping_sub(b),
+                        CfiOp::Mul => a.wrapping_mul(b),
+                        CfiOp::Div => a.checked_div(b).ok_or(())?,
+                        CfiOp::Rem => a.checked_rem(b).ok_or(())?,
+                        _ => a & b.wrapping_neg(),
+                    };
+
+                    stack.push((None, Some(self.cpu.mask(result))));
+                }
+            }
+        }
+
+        match stack.len() {
+            0 => Ok(None),
+            1 => stack[0].1.map(Some).ok_or(()),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A stackwalker for minidumps.
+///
+/// The stackwalker holds the modules and memory of a minidump. Call frame information can be
+/// supplied for modules in a `FrameInfoMap` keyed by their debug identifiers.
+pub struct Stackwalker<'a, 'data> {
+    arch: Arch,
+    modules: Vec<MinidumpModule<'data>>,
+    memory: Vec<MinidumpMemory<'data>>,
+    threads: Vec<MinidumpThread<'data>>,
+    exception: Option<(u32, &'data [u8])>,
+    frame_infos: Option<&'a FrameInfoMap<'a>>,
+    function_lookup: Option<Box<FunctionLookup<'a>>>,
+}
+
+/// A callback checking whether an address lies within a function of a module.
+type FunctionLookup<'a> = dyn Fn(CodeModuleId, u64) -> Option<bool> + 'a;
+
+impl<'a, 'data> Stackwalker<'a, 'data> {
+    /// Creates a stackwalker for the threads of a minidump.
+    ///
+    /// The parameter `frame_infos` expects a map of `CfiCache`s to allow stackwalking with
+    /// omitted frame pointers.
+    pub fn new(
+        minidump: &Minidump<'data>,
+        frame_infos: Option<&'a FrameInfoMap<'a>>,
+    ) -> Result<Self, MinidumpError> {
+        let arch = match minidump.system_info()? {
+            Some(system_info) => system_info.arch(),
+            None => Arch::Unknown,
+        };
+
+        let mut modules = minidump.modules()?;
+        modules.sort_by_key(|module| module.base_address);
+
+        let mut memory = minidump.memory_list()?;
+        memory.extend(minidump.memory64_list()?);
+
+        let exception = minidump
+            .exception()?
+            .filter(|exception| !exception.context.is_empty())
+            .map(|exception| (exception.thread_id, exception.context));
+
+        Ok(Stackwalker {
+            arch,
+            modules,
+            memory,
+            threads: minidump.threads()?,
+            exception,
+            frame_infos,
+            function_lookup: None,
+        })
+    }
+
+    /// Sets a callback to validate return addresses recovered by frame pointers or scanning.
+    ///
+    /// The callback receives the debug identifier of a module and an address relative to its
+    /// image base. It returns whether the address lies within a function of the module, or `None`
+    /// if no symbols are available for the module. Typically, this is answered with a lookup in
+    /// the module's `SymCache`. Addresses rejected by the callback are not used as caller frames.
+    pub fn set_function_lookup<F>(&mut self, callback: F)
+    where
+        F: Fn(CodeModuleId, u64) -> Option<bool> + 'a,
+    {
+        self.function_lookup = Some(Box::new(callback));
+    }
+
+    /// The CPU architecture of the crashed process.
+    pub fn arch(&self) -> Arch {
+        self.arch
+    }
+
+    /// All modules loaded into the process, ordered by address.
+    pub fn modules(&self) -> &[MinidumpModule<'data>] {
+        &self.modules
+    }
+
+    /// Recovers the call stacks of all threads.
+    ///
+    /// For the thread that caused an exception, the context stored with the exception is used,
+    /// since the thread context usually points into the exception handler.
+    pub fn walk(&self) -> Vec<ThreadStack<'data>> {
+        self.threads
+            .iter()
+            .map(|thread| {
+                let frames = match self.exception {
+                    Some((thread_id, context)) if thread_id == thread.thread_id => {
+                        self.walk_context(context, &thread.stack)
+                    }
+                    _ => self.walk_thread(thread),
+                };
+
+                ThreadStack {
+                    thread_id: thread.thread_id,
+                    frames,
+                }
+            })
+            .collect()
+    }
+
+    /// Recovers the call stack of a thread from its context.
+    pub fn walk_thread(&self, thread: &MinidumpThread<'data>) -> Vec<Frame<'data>> {
+        self.walk_context(thread.context, &thread.stack)
+    }
+
+    /// Recovers a call stack from a raw CPU context and stack memory.
+    ///
+    /// Returns an empty list if the architecture is not supported or the context is invalid.
+    pub fn walk_context(&self, context: &[u8], stack: &MinidumpMemory<'data>) -> Vec<Frame<'data>> {
+        let cpu = match CpuInfo::from_arch(self.arch) {
+            Some(cpu) => cpu,
+            None => return Vec::new(),
+        };
+
+        let registers = match cpu.parse_context(context) {
+            Some(registers) => registers,
+            None => return Vec::new(),
+        };
+
+        let walk = Walk {
+            walker: self,
+            cpu,
+            stack,
+        };
+
+        let mut frames = Vec::new();
+        let ip = registers[cpu.ip];
+        let mut frame = WalkFrame {
+            registers,
+            instruction: ip,
+            return_address: ip,
+            trust: FrameTrust::Context,
+        };
+
+        loop {
+            let caller = walk.caller(&frame, frames.is_empty());
+            frames.push(walk.to_frame(frame));
+
+            match caller {
+                Some(caller) if frames.len() < MAX_FRAMES => frame = caller,
+                _ => break,
+            }
+        }
+
+        frames
+    }
+
+    /// Returns the module containing the given address.
+    fn module(&self, address: u64) -> Option<&MinidumpModule<'data>> {
+        let index = match self
+            .modules
+            .binary_search_by_key(&address, |module| module.base_address)
+        {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(index) => index - 1,
+        };
+
+        let module = &self.modules[index];
+        if address < module.end() {
+            Some(module)
+        } else {
+            None
+        }
+    }
+
+    /// Checks whether an instruction address lies within a function of its module.
+    ///
+    /// Without a function lookup or symbols for the module, all addresses in the module are
+    /// accepted.
+    fn is_function_address(&self, module: &MinidumpModule<'data>, address: u64) -> bool {
+        let lookup = match self.function_lookup {
+            Some(ref lookup) => lookup,
+            None => return true,
+        };
+
+        match module.debug_id() {
+            Some(id) => {
+                lookup(CodeModuleId::from(id), address - module.base_address).unwrap_or(true)
+            }
+            None => true,
+        }
+    }
+
+    /// Looks up unwind information for an instruction address.
+    fn unwind_rule(&self, address: u64) -> Option<UnwindRule<'a>> {
+        let module = self.module(address)?;
+        let id = CodeModuleId::from(module.debug_id()?);
+        let cache = self.frame_infos?.get(&id)?;
+        cache.lookup(address - module.base_address)
+    }
+}
+
+/// Intermediate state of a frame during stack walking.
+struct WalkFrame {
+    registers: Registers,
+    instruction: u64,
+    return_address: u64,
+    trust: FrameTrust,
+}
+
+/// State of a single stack walk.
+struct Walk<'w, 'a, 'data> {
+    walker: &'w Stackwalker<'a, 'data>,
+    cpu: &'static CpuInfo,
+    stack: &'w MinidumpMemory<'data>,
+}
+
+impl<'w, 'a, 'data> Walk<'w, 'a, 'data> {
+    /// Reads a pointer-sized value from the stack or other captured memory.
+    fn read_word(&self, address: u64) -> Option<u64> {
+        let size = self.cpu.word_size;
+        let end = address.checked_add(size)?;
+
+        let region = Some(self.stack)
+            .into_iter()
+            .chain(self.walker.memory.iter())
+            .find(|region| region.base_address <= address && end <= region.end())?;
+
+        let offset = (address - region.base_address) as usize;
+        let bytes = &region.data[offset..offset + size as usize];
+        Some(
+            bytes
+                .iter()
+                .rev()
+                .fold(0, |value, byte| value << 8 | u64::from(*byte)),
+        )
+    }
+
+    fn to_frame(&self, frame: WalkFrame) -> Frame<'data> {
+        Frame {
+            instruction: frame.instruction,
+            return_address: frame.return_address,
+            trust: frame.trust,
+            registers: self
+                .cpu
+                .registers
+                .iter()
+                .filter_map(|name| {
+                    let value = *frame.registers.get(name)?;
+                    Some((*name, self.cpu.reg_val(value)))
+                })
+                .collect(),
+            module: self.walker.module(frame.instruction).cloned(),
+        }
+    }
+
+    /// Recovers the caller of the given frame.
+    fn caller(&self, callee: &WalkFrame, is_context: bool) -> Option<WalkFrame> {
+        self.caller_by_unwind_info(callee)
+            .filter(|caller| self.is_valid_caller(callee, caller))
+            .or_else(|| {
+                self.caller_by_frame_pointer(callee)
+                    .filter(|caller| self.is_valid_caller(callee, caller))
+            })
+            .or_else(|| {
+                self.caller_by_scan(callee, is_context)
+                    .filter(|caller| self.is_valid_caller(callee, caller))
+            })
+    }
+
+    /// Checks whether a recovered caller frame continues the stack.
+    ///
+    /// The walk ends at the end of the stack, or if the stack pointer does not move towards the
+    /// bottom of the stack. This prevents infinite loops on corrupted stacks.
+    fn is_valid_caller(&self, callee: &WalkFrame, caller: &WalkFrame) -> bool {
+        let callee_sp = callee.registers.get(self.cpu.sp);
+        let caller_sp = caller.registers.get(self.cpu.sp);
+        caller.return_address != 0 && callee_sp.is_some() && caller_sp > callee_sp
+    }
+
+    /// Checks whether a return address points into the code of a loaded module.
+    fn is_return_address(&self, address: u64) -> bool {
+        let instruction = match address.checked_sub(self.cpu.call_adjust) {
+            Some(instruction) => instruction,
+            None => return false,
+        };
+
+        match self.walker.module(instruction) {
+            Some(module) => self.walker.is_function_address(module, instruction),
+            None => false,
+        }
+    }
+
+    /// Finalizes a caller frame from recovered registers.
+    fn make_caller(&self, mut registers: Registers, trust: FrameTrust) -> Option<WalkFrame> {
+        let return_address = self.cpu.mask(*registers.get(self.cpu.ip)?);
+        registers.insert(self.cpu.ip, return_address);
+
+        Some(WalkFrame {
+            registers,
+            instruction: return_address.saturating_sub(self.cpu.call_adjust),
+            return_address,
+            trust,
+        })
+    }
+
+    /// Inherits registers preserved across calls from the callee if they were not recovered.
+    fn inherit_callee_saved(&self, callee: &WalkFrame, registers: &mut Registers) {
+        for name in self.cpu.callee_saved {
+            if let Some(value) = callee.registers.get(name) {
+                registers.entry(name).or_insert(*value);
+            }
+        }
+    }
+
+    fn caller_by_unwind_info(&self, callee: &WalkFrame) -> Option<WalkFrame> {
+        match self.walker.unwind_rule(callee.instruction)? {
+            UnwindRule::Cfi(row) => self.caller_by_cfi(callee, &row),
+            UnwindRule::Win(record) => self.caller_by_stack_win(callee, &record),
+        }
+    }
+
+    /// Evaluates CFI rules to recover the caller's registers.
+    fn caller_by_cfi(&self, callee: &WalkFrame, row: &CfiRow<'_>) -> Option<WalkFrame> {
+        let read = |address| self.read_word(address);
+        let mut evaluator = PostfixEvaluator::new(self.cpu, &read);
+        for (name, value) in &callee.registers {
+            evaluator.set(name, *value);
+            evaluator.set(&format!("${}", name), *value);
+        }
+
+        // ARM CFI refers to registers by their numeric names.
+        if let Some(fp) = callee.registers.get("fp") {
+            evaluator.set("r11", *fp);
+        }
+
+        let cfa = evaluator.evaluate_ops(row.rule(".cfa")?).ok()??;
+        let mut registers = Registers::new();
+        registers.insert(self.cpu.sp, cfa);
+
+        evaluator.set(".cfa", cfa);
+        let mut evaluate = |expression: &[CfiOp<'_>]| evaluator.evaluate_ops(expression).ok()?;
+
+        for rule in &row.rules {
+            let register = str::from_utf8(rule.register).ok()?;
+            if register == ".cfa" || register == ".ra" {
+                continue;
+            }
+
+            if let Some(name) = self.cpu.register(register) {
+                if let Some(value) = evaluate(&rule.expression) {
+                    registers.insert(name, value);
+                }
+            }
+        }
+
+        let ra = match row.rule(".ra") {
+            Some(expression) => evaluate(expression)?,
+            // ARM records do not always specify the return address. It is held by the link
+            // register instead, which either has a rule or is unchanged from the callee.
+            None => match self.cpu.ip {
+                "pc" => {
+                    let lr = if self.cpu.word_size == 4 { "lr" } else { "x30" };
+                    registers
+                        .get(lr)
+                        .or_else(|| callee.registers.get(lr))
+                        .cloned()?
+                }
+                _ => return None,
+            },
+        };
+
+        registers.insert(self.cpu.ip, ra);
+        self.inherit_callee_saved(callee, &mut registers);
+        self.make_caller(registers, FrameTrust::CFI)
+    }
+
+    /// Evaluates a STACK WIN record to recover the caller's registers on Windows x86.
+    fn caller_by_stack_win(
+        &self,
+        callee: &WalkFrame,
+        record: &BreakpadStackWinRecord<'_>,
+    ) -> Option<WalkFrame> {
+        if self.cpu.ip != "eip" {
+            return None;
+        }
+
+        let esp = *callee.registers.get("esp")?;
+        let read = |address| self.read_word(address);
+        let mut evaluator = PostfixEvaluator::new(self.cpu, &read);
+        for (name, value) in &callee.registers {
+            evaluator.set(&format!("${}", name), *value);
+        }
+
+        let locals_size = u64::from(record.locals_size);
+        let saved_regs_size = u64::from(record.saved_regs_size);
+        let ra_search_start = esp.checked_add(locals_size)?.checked_add(saved_regs_size)?;
+
+        evaluator.set(".cbCalleeParams", 0);
+        evaluator.set(".cbSavedRegs", saved_regs_size);
+        evaluator.set(".cbLocals", locals_size);
+        evaluator.set(".cbParams", u64::from(record.params_size));
+        evaluator.set(".raSearchStart", ra_search_start);
+        evaluator.set(".raSearch", ra_search_start);
+
+        let program = match record.program_string {
+            Some(program) => str::from_utf8(program).ok()?,
+            None if record.allocates_base_pointer => {
+                "$T0 $ebp = $eip $T0 4 + ^ = $ebp $T0 ^ = $esp $T0 8 + ="
+            }
+            None => "$eip .raSearchStart ^ = $esp .raSearchStart 4 + =",
+        };
+
+        evaluator.evaluate(program).ok()?;
+
+        let mut registers = Registers::new();
+        for name in X86_INFO.registers {
+            if let Some(value) = evaluator.get(&format!("${}", name)) {
+                registers.insert(name, value);
+            }
+        }
+
+        registers.get("esp")?;
+        self.inherit_callee_saved(callee, &mut registers);
+        self.make_caller(registers, FrameTrust::CFI)
+    }
+
+    /// Recovers the caller using the frame pointer chain.
+    fn caller_by_frame_pointer(&self, callee: &WalkFrame) -> Option<WalkFrame> {
+        let fp = *callee.registers.get(self.cpu.fp)?;
+        let sp = *callee.registers.get(self.cpu.sp)?;
+        let word_size = self.cpu.word_size;
+
+        // The frame pointer must point into the stack above the stack pointer and be aligned.
+        // Otherwise, it is likely used as a general purpose register.
+        if fp < sp || fp % word_size != 0 {
+            return None;
+        }
+
+        let ra_address = fp.checked_add(self.cpu.ra_offset)?;
+        let caller_sp = ra_address.checked_add(word_size)?;
+        let caller_fp = self.read_word(fp)?;
+        let caller_ip = self.read_word(ra_address)?;
+        if !self.is_return_address(caller_ip) {
+            return None;
+        }
+
+        let mut registers = Registers::new();
+        registers.insert(self.cpu.fp, caller_fp);
+        registers.insert(self.cpu.ip, caller_ip);
+        registers.insert(self.cpu.sp, caller_sp);
+        self.inherit_callee_saved(callee, &mut registers);
+        registers.insert(self.cpu.fp, caller_fp);
+
+        self.make_caller(registers, FrameTrust::FP)
+    }
+
+    /// Searches the stack for a value that looks like a return address into a loaded module.
+    fn caller_by_scan(&self, callee: &WalkFrame, is_context: bool) -> Option<WalkFrame> {
+        let sp = *callee.registers.get(self.cpu.sp)?;
+        let word_size = self.cpu.word_size;
+        let words = if is_context {
+            SCAN_WORDS_CONTEXT
+        } else {
+            SCAN_WORDS
+        };
+
+        // The return address of the context frame may not have been pushed yet, so start
+        // scanning at the stack pointer. For all other frames, the stack pointer is already past
+        // the return address of the callee.
+        for index in 0..words as u64 {
+            let address = sp.checked_add(index * word_size)?;
+            let value = self.read_word(address)?;
+
+            if self.is_return_address(value) {
+                let mut registers = Registers::new();
+                registers.insert(self.cpu.ip, value);
+                registers.insert(self.cpu.sp, address.checked_add(word_size)?);
+                self.inherit_callee_saved(callee, &mut registers);
+                return self.make_caller(registers, FrameTrust::Scan);
+            }
+        }
+
+        None
+    }
+}
+
+#[test]
+fn test_postfix_evaluator() {
+    let memory = |address| match address {
+        0x1000 => Some(0x2000),
+        _ => None,
+    };
+
+    let mut evaluator = PostfixEvaluator::new(&X86_INFO, &memory);
+    evaluator.set("$esp", 0xff8);
+    assert_eq!(evaluator.evaluate("$esp 8 + ^"), Ok(Some(0x2000)));
+    assert_eq!(evaluator.evaluate("$esp 16 @"), Ok(Some(0xff0)));
+    assert_eq!(evaluator.evaluate("$esp -8 +"), Ok(Some(0xff0)));
+    assert_eq!(evaluator.evaluate("2 0 /"), Err(()));
+    assert_eq!(evaluator.evaluate("1 +"), Err(()));
+
+    assert_eq!(
+        evaluator.evaluate("$T0 $esp 8 + = $eip $T0 ^ = $esp $T0 4 + ="),
+        Ok(None)
+    );
+    assert_eq!(evaluator.get("$eip"), Some(0x2000));
+    assert_eq!(evaluator.get("$esp"), Some(0x1004));
+}
@@ -1,9 +1,13 @@
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::str;
 
 use symbolic_common::byteview::ByteView;
 use symbolic_debuginfo::FatObject;
-use symbolic_minidump::cfi::{AsciiCfiWriter, CfiCache};
-use symbolic_testutils::{assert_snapshot_plain, fixture_path};
+use symbolic_minidump::cfi::{
+    AsciiCfiWriter, CfiCache, CfiDiagnostic, CfiDiagnosticKind, CfiErrorKind, CfiOp, UnwindRule,
+};
+use symbolic_testutils::{assert_snapshot_plain, fixture_path, read_fixture};
 
 #[test]
 fn load_empty_cfi_cache() {
@@ -35,6 +39,75 @@ fn cfi_from_elf() {
     assert_snapshot_plain("cfi_elf.txt", cfi);
 }
 
+#[test]
+fn cfi_diagnostics_from_elf() {
+    let buffer = ByteView::from_path(fixture_path("linux/crash"))
+        .expect("Could not open the executable file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let diagnostics = Rc::new(RefCell::new(Vec::new()));
+    let mut cfi = Vec::new();
+    {
+        let diagnostics = diagnostics.clone();
+        let mut writer = AsciiCfiWriter::new(&mut cfi);
+        writer.set_diagnostics(move |d| diagnostics.borrow_mut().push(d.clone()));
+        writer.process(&object).expect("Could not write CFI");
+    }
+
+    // The PLT stub computes its CFA with a comparison, which Breakpad cannot express.
+    assert_eq!(
+        *diagnostics.borrow(),
+        vec![CfiDiagnostic {
+            address: 0x1590,
+            register: ".cfa",
+            kind: CfiDiagnosticKind::UnsupportedOperation("DW_OP_ge".into()),
+        }]
+    );
+}
+
+#[test]
+fn cfi_diagnostics_invalid_register() {
+    // Replace `DW_OP_breg7 8; DW_OP_breg16 0` at the start of the PLT stub's CFA expression with
+    // `DW_OP_bregx 200 8`, which has the same length but refers to a nonexistent register.
+    let mut data = read_fixture("linux/crash");
+    let expression = [
+        0x77, 0x08, 0x80, 0x00, 0x3f, 0x1a, 0x3b, 0x2a, 0x33, 0x24, 0x22,
+    ];
+    let offset = data
+        .windows(expression.len())
+        .position(|window| window == expression)
+        .expect("Missing PLT expression");
+    data[offset..offset + 4].copy_from_slice(&[0x92, 0xc8, 0x01, 0x08]);
+
+    let fat = FatObject::parse(ByteView::from_slice(&data)).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let diagnostics = Rc::new(RefCell::new(Vec::new()));
+    let mut cfi = Vec::new();
+    {
+        let diagnostics = diagnostics.clone();
+        let mut writer = AsciiCfiWriter::new(&mut cfi);
+        writer.set_diagnostics(move |d| diagnostics.borrow_mut().push(d.clone()));
+        writer.process(&object).expect("Could not write CFI");
+    }
+
+    assert_eq!(
+        *diagnostics.borrow(),
+        vec![CfiDiagnostic {
+            address: 0x1590,
+            register: ".cfa",
+            kind: CfiDiagnosticKind::InvalidExpression,
+        }]
+    );
+}
+
 #[test]
 fn cfi_from_macho() {
     let buffer =
@@ -117,3 +190,196 @@ fn cfi_from_sym_windows() {
     let cfi = str::from_utf8(&cfi).expect("Invalid CFI encoding");
     assert_snapshot_plain("cfi_sym_windows.txt", cfi);
 }
+
+#[test]
+fn cfi_from_sym_malformed() {
+    let sym = b"MODULE Linux x86_64 3249D99D0C4049318610F4E4FB0B69370 crash\n\
+                FUNC garbage\n\
+                1000 garbage\n\
+                STACK CFI INIT 1000 10 .cfa: $rsp 8 + .ra: .cfa -8 + ^\n\
+                STACK CFI 1004 .cfa: $rsp 16 +\n";
+    let fat = FatObject::parse(ByteView::from_slice(sym)).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let cfi: Vec<u8> = AsciiCfiWriter::transform(&object).expect("Could not write CFI");
+    assert_eq!(
+        str::from_utf8(&cfi).expect("Invalid CFI encoding"),
+        "STACK CFI INIT 1000 10 .cfa: $rsp 8 + .ra: .cfa -8 + ^\n\
+         STACK CFI 1004 .cfa: $rsp 16 +\n"
+    );
+
+    let sym = b"MODULE Linux x86_64 3249D99D0C4049318610F4E4FB0B69370 crash\n\
+                STACK CFI INIT garbage\n";
+    let fat = FatObject::parse(ByteView::from_slice(sym)).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    assert!(AsciiCfiWriter::<Vec<u8>>::transform(&object).is_err());
+}
+
+#[test]
+fn cfi_cache_lookup_cfi() {
+    let buffer = ByteView::from_path(fixture_path("linux/crash"))
+        .expect("Could not open the executable file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let cache = CfiCache::from_object(&object).expect("Could not create cache");
+    assert_eq!(cache.version(), 2);
+
+    let row = match cache.lookup(0x1586) {
+        Some(UnwindRule::Cfi(row)) => row,
+        other => panic!("Expected CFI row, got {:?}", other),
+    };
+
+    assert!(row.address <= 0x1586 && 0x1586 < row.end());
+    assert_eq!(
+        row.rule(".cfa"),
+        Some(&[CfiOp::Variable(b"$rsp"), CfiOp::Const(24), CfiOp::Add][..])
+    );
+    assert_eq!(row.rule(".ra"), Some(&CfiOp::compile(b".cfa -8 + ^")[..]));
+    assert!(cache.lookup(0).is_none());
+}
+
+#[test]
+fn cfi_cache_overlapping_records() {
+    let sym = b"MODULE Linux x86_64 3249D99D0C4049318610F4E4FB0B69370 crash\n\
+                STACK CFI INIT 2000 10 .cfa: $rsp 8 + .ra: .cfa -8 + ^\n\
+                STACK CFI INIT 1000 10 .cfa: $rsp 8 + .ra: .cfa -8 + ^\n\
+                STACK CFI 1000 .cfa: $rsp 16 +\n\
+                STACK CFI 1004 .cfa: $rsp 24 +\n\
+                STACK CFI INIT 1000 20 .cfa: $rsp 32 + .ra: .cfa -8 + ^\n\
+                STACK CFI INIT 3000 0 .cfa: $rsp 8 + .ra: .cfa -8 + ^\n";
+    let fat = FatObject::parse(ByteView::from_slice(sym)).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let cache = CfiCache::from_object(&object).expect("Could not create cache");
+    let cfa = |address| match cache.lookup(address) {
+        Some(UnwindRule::Cfi(row)) => row.rule(".cfa").map(|ops| ops[1]),
+        _ => None,
+    };
+
+    // The delta at the INIT address supersedes the INIT rules, and the second INIT record at the
+    // same address is dropped.
+    assert_eq!(cfa(0x1000), Some(CfiOp::Const(16)));
+    assert_eq!(cfa(0x1004), Some(CfiOp::Const(24)));
+    assert_eq!(cfa(0x100f), Some(CfiOp::Const(24)));
+    assert_eq!(cfa(0x1010), None);
+    assert_eq!(cfa(0x2000), Some(CfiOp::Const(8)));
+    assert_eq!(cfa(0x3000), None);
+
+    let ascii = cache.to_ascii().expect("Could not convert cache");
+    assert_eq!(
+        str::from_utf8(&ascii).expect("Invalid CFI encoding"),
+        "STACK CFI INIT 1000 10 .cfa: $rsp 16 + .ra: .cfa -8 + ^\n\
+         STACK CFI 1004 .cfa: $rsp 24 +\n\
+         STACK CFI INIT 2000 10 .cfa: $rsp 8 + .ra: .cfa -8 + ^\n"
+    );
+}
+
+#[test]
+fn cfi_cache_large_records() {
+    let sym = b"MODULE Linux x86_64 3249D99D0C4049318610F4E4FB0B69370 crash\n\
+                STACK CFI INIT 1000 100001000 .cfa: $rsp 8 + .ra: .cfa -8 + ^\n\
+                STACK CFI INIT ffffffffffffff00 200 .cfa: $rsp 16 + .ra: .cfa -8 + ^\n";
+    let fat = FatObject::parse(ByteView::from_slice(sym)).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let cache = CfiCache::from_object(&object).expect("Could not create cache");
+    let cfa = |address| match cache.lookup(address) {
+        Some(UnwindRule::Cfi(row)) => row.rule(".cfa").map(|ops| ops[1]),
+        _ => None,
+    };
+
+    // Row sizes beyond 32 bits must not be clipped, and records reaching past the end of the
+    // address space must not overflow.
+    assert_eq!(cfa(0x1_0000_1000), Some(CfiOp::Const(8)));
+    assert_eq!(cfa(0x1_0000_2000), None);
+    assert_eq!(cfa(0xffff_ffff_ffff_ff00), Some(CfiOp::Const(16)));
+    assert_eq!(cfa(0xffff_ffff_ffff_fffe), Some(CfiOp::Const(16)));
+}
+
+#[test]
+fn cfi_cache_invalid() {
+    let error = CfiCache::from_bytes(ByteView::from_slice(b"CFIX\x02\0\0\0"))
+        .err()
+        .expect("Loaded invalid cache");
+    assert_eq!(error.kind(), CfiErrorKind::BadFileMagic);
+
+    let error = CfiCache::from_bytes(ByteView::from_slice(b"CFIC\x02\0\0\0"))
+        .err()
+        .expect("Loaded truncated cache");
+    assert_eq!(error.kind(), CfiErrorKind::BadCacheFile);
+}
+
+#[test]
+fn cfi_cache_roundtrip() {
+    let buffer = ByteView::from_path(fixture_path("linux/crash"))
+        .expect("Could not open the executable file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let cache = CfiCache::from_object(&object).expect("Could not create cache");
+    let mut buffer = Vec::new();
+    cache.write_to(&mut buffer).expect("Could not write cache");
+
+    let loaded = CfiCache::from_bytes(ByteView::from_vec(buffer)).expect("Could not load cache");
+    assert_eq!(loaded.version(), 2);
+    assert_eq!(loaded.lookup(0x1586), cache.lookup(0x1586));
+
+    // The ASCII representation must contain the same records as the CFI writer output.
+    let ascii: Vec<u8> = AsciiCfiWriter::transform(&object).expect("Could not write CFI");
+    let converted = loaded.to_ascii().expect("Could not convert cache");
+    let init_count = |data: &[u8]| {
+        str::from_utf8(data)
+            .expect("Invalid CFI encoding")
+            .lines()
+            .filter(|line| line.starts_with("STACK CFI INIT"))
+            .count()
+    };
+    assert_eq!(init_count(&converted), init_count(&ascii));
+
+    let legacy = CfiCache::from_bytes(ByteView::from_vec(ascii)).expect("Could not load cache");
+    assert_eq!(legacy.version(), 1);
+    assert_eq!(legacy.lookup(0x1586), cache.lookup(0x1586));
+}
+
+#[test]
+fn cfi_cache_lookup_win() {
+    let buffer = ByteView::from_path(fixture_path("windows/crash.sym"))
+        .expect("Could not open the symbol file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let cache = CfiCache::from_object(&object).expect("Could not create cache");
+    let win = match cache.lookup(0x1000) {
+        Some(UnwindRule::Win(win)) => win,
+        other => panic!("Expected STACK WIN record, got {:?}", other),
+    };
+
+    assert_eq!(win.rva, 0x1000);
+    assert_eq!(
+        win.program_string,
+        Some(&b"$T0 .raSearch = $eip $T0 ^ = $esp $T0 4 + ="[..])
+    );
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use symbolic_minidump::parser::{Minidump, MinidumpAnnotation, CRASHPAD_ANNOTATION_STRING};
+use symbolic_testutils::minidump::{push_location, MinidumpBuilder};
+use symbolic_testutils::read_fixture;
+
+fn push_dictionary(builder: &mut MinidumpBuilder, entries: &[(&str, &str)]) -> (u32, u32) {
+    let mut dictionary = (entries.len() as u32).to_le_bytes().to_vec();
+    for (key, value) in entries {
+        dictionary.extend_from_slice(&builder.push_bytes(key.as_bytes()).to_le_bytes());
+        dictionary.extend_from_slice(&builder.push_bytes(value.as_bytes()).to_le_bytes());
+    }
+    builder.push(&dictionary)
+}
+
+fn build_crashpad_info() -> Vec<u8> {
+    build_crashpad_info_with(
+        &[("prod", "app"), ("ver", "1.0")],
+        &[("name", 1, b"value"), ("custom", 0x8001, &[1, 2])],
+    )
+}
+
+/// Builds a Crashpad info stream with process annotations and a single module carrying list
+/// annotations and the given annotation objects.
+fn build_crashpad_info_with(
+    annotations: &[(&str, &str)],
+    objects: &[(&str, u16, &[u8])],
+) -> Vec<u8> {
+    let mut builder = MinidumpBuilder::new();
+    let annotations = push_dictionary(&mut builder, annotations);
+
+    // A module with list annotations and annotation objects, but no simple annotations
+    let message = builder.push_bytes(b"assertion failed");
+    let mut list = 1u32.to_le_bytes().to_vec();
+    list.extend_from_slice(&message.to_le_bytes());
+    let list = builder.push(&list);
+
+    let mut object_list = (objects.len() as u32).to_le_bytes().to_vec();
+    for &(name, ty, value) in objects {
+        object_list.extend_from_slice(&builder.push_bytes(name.as_bytes()).to_le_bytes());
+        object_list.extend_from_slice(&ty.to_le_bytes());
+        object_list.extend_from_slice(&0u16.to_le_bytes());
+        object_list.extend_from_slice(&builder.push_bytes(value).to_le_bytes());
+    }
+    let objects = builder.push(&object_list);
+
+    let mut module = 1u32.to_le_bytes().to_vec(); // version
+    push_location(&mut module, list);
+    push_location(&mut module, (0, 0)); // simple annotations
+    push_location(&mut module, objects);
+    let module = builder.push(&module);
+
+    let mut links = 1u32.to_le_bytes().to_vec();
+    links.extend_from_slice(&3u32.to_le_bytes()); // module index
+    push_location(&mut links, module);
+    let links = builder.push(&links);
+
+    let mut stream = 1u32.to_le_bytes().to_vec(); // version
+    stream.extend_from_slice(&[
+        0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee,
+        0xff,
+    ]);
+    stream.extend_from_slice(&[0xff; 16]);
+    push_location(&mut stream, annotations);
+    push_location(&mut stream, links);
+
+    builder.add_stream(0x4350_0001, &stream);
+    builder.finish()
+}
+
+#[test]
+fn crashpad_info() {
+    let data = build_crashpad_info();
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let info = minidump
+        .crashpad_info()
+        .expect("Could not read crashpad info")
+        .expect("Missing crashpad info");
+
+    assert_eq!(
+        info.report_id.to_string(),
+        "00112233-4455-6677-8899-aabbccddeeff"
+    );
+    assert_eq!(
+        info.client_id.to_string(),
+        "ffffffff-ffff-ffff-ffff-ffffffffffff"
+    );
+
+    let mut annotations = BTreeMap::new();
+    annotations.insert("prod".to_string(), "app".to_string());
+    annotations.insert("ver".to_string(), "1.0".to_string());
+    assert_eq!(info.simple_annotations, annotations);
+
+    assert_eq!(info.module_list.len(), 1);
+    let module = &info.module_list[0];
+    assert_eq!(module.module_index, 3);
+    assert_eq!(
+        module.list_annotations,
+        vec!["assertion failed".to_string()]
+    );
+    assert!(module.simple_annotations.is_empty());
+    assert_eq!(
+        module.annotation_objects,
+        vec![
+            MinidumpAnnotation {
+                name: "name".into(),
+                ty: CRASHPAD_ANNOTATION_STRING,
+                value: b"value".to_vec(),
+            },
+            MinidumpAnnotation {
+                name: "custom".into(),
+                ty: 0x8001,
+                value: vec![1, 2],
+            },
+        ]
+    );
+    assert_eq!(module.annotation_objects[0].as_str(), Some("value"));
+    assert_eq!(module.annotation_objects[1].as_str(), None);
+}
+
+#[test]
+fn crashpad_thread_names() {
+    let data = build_crashpad_info_with(
+        &[
+            ("thread_name:1234", "RenderThread"),
+            ("thread_name:0x10", "IO"),
+            ("thread_name:main", "invalid"),
+            ("prod", "app"),
+        ],
+        &[
+            ("thread_name:42", 1, b"Worker"),
+            ("thread_name:1234", 1, b"Shadowed"),
+            ("thread_name:43", 0x8001, &[1]),
+        ],
+    );
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let info = minidump
+        .crashpad_info()
+        .expect("Could not read crashpad info")
+        .expect("Missing crashpad info");
+
+    let mut names = BTreeMap::new();
+    names.insert(0x10, "IO".to_string());
+    names.insert(42, "Worker".to_string());
+    names.insert(1234, "RenderThread".to_string());
+    assert_eq!(info.thread_names(), names);
+
+    let data = build_crashpad_info();
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let info = minidump
+        .crashpad_info()
+        .expect("Could not read crashpad info")
+        .expect("Missing crashpad info");
+    assert!(info.thread_names().is_empty());
+}
+
+#[test]
+fn crashpad_info_missing() {
+    let data = read_fixture("linux/mini.dmp");
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    assert_eq!(
+        minidump
+            .crashpad_info()
+            .expect("Could not read crashpad info"),
+        None
+    );
+}
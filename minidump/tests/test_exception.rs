@@ -0,0 +1,66 @@
+use std::fs;
+
+use symbolic_minidump::exception::{
+    ExceptionCode, ExceptionInfo, MachException, Signal, WindowsException,
+};
+use symbolic_minidump::parser::Minidump;
+use symbolic_testutils::fixture_path;
+
+fn exception_info(path: &str) -> Result<ExceptionInfo, failure::Error> {
+    let data = fs::read(fixture_path(path))?;
+    let minidump = Minidump::parse(&data)?;
+    Ok(ExceptionInfo::from_minidump(&minidump)?.expect("missing exception"))
+}
+
+#[test]
+fn exception_linux() -> Result<(), failure::Error> {
+    let info = exception_info("linux/mini.dmp")?;
+    assert_eq!(info.code, ExceptionCode::Signal(Signal::SegmentationFault));
+    assert_eq!(info.code.to_string(), "SIGSEGV");
+    assert_eq!(info.subcode, Some(0));
+    assert_eq!(info.address, 0x45);
+    assert_eq!(info.thread_id, 1304);
+    assert!(!info.context.is_empty());
+    assert_eq!(info.signal(), Some(11));
+    Ok(())
+}
+
+#[test]
+fn exception_macos() -> Result<(), failure::Error> {
+    let info = exception_info("macos/mini.dmp")?;
+    assert_eq!(info.code, ExceptionCode::Mach(MachException::BadAccess));
+    assert_eq!(info.code.to_string(), "EXC_BAD_ACCESS");
+    assert_eq!(info.subcode, Some(1));
+    assert_eq!(info.address, 0x45);
+    assert_eq!(info.thread_id, 775);
+    assert_eq!(info.signal(), Some(11));
+    Ok(())
+}
+
+#[test]
+fn exception_windows() -> Result<(), failure::Error> {
+    let info = exception_info("windows/mini.dmp")?;
+    assert_eq!(
+        info.code,
+        ExceptionCode::Windows(WindowsException::AccessViolation)
+    );
+    assert_eq!(info.code.to_string(), "EXCEPTION_ACCESS_VIOLATION");
+    assert_eq!(info.code.code(), 0xc000_0005);
+    // Write access to address 0x45
+    assert_eq!(info.subcode, Some(1));
+    assert_eq!(info.address, 0x45);
+    assert_eq!(info.parameters, vec![1, 0x45]);
+    assert_eq!(info.thread_id, 1636);
+    assert_eq!(info.signal(), Some(11));
+    Ok(())
+}
+
+#[test]
+fn exception_unknown_codes() {
+    assert_eq!(Signal::from_code(64), Signal::Other(64));
+    assert_eq!(Signal::Other(64).to_string(), "0x40");
+    assert_eq!(
+        WindowsException::from_code(0xc000_0094).name(),
+        Some("EXCEPTION_INT_DIVIDE_BY_ZERO")
+    );
+}
@@ -0,0 +1,80 @@
+use symbolic_minidump::linux::{LinuxInfo, LinuxMapsEntry, LinuxPermissions};
+use symbolic_minidump::parser::Minidump;
+use symbolic_testutils::read_fixture;
+
+#[test]
+fn linux_info_linux() {
+    let data = read_fixture("linux/mini.dmp");
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let info = LinuxInfo::from_minidump(&minidump)
+        .expect("Could not read Linux streams")
+        .expect("Missing Linux streams");
+
+    let crash = &info.maps[0];
+    assert_eq!(crash.start, 0x40_0000);
+    assert_eq!(crash.end, 0x41_a000);
+    assert_eq!(crash.permissions.to_string(), "r-xp");
+    assert_eq!((crash.device_major, crash.device_minor), (0, 0x47));
+    assert_eq!(crash.inode, 8_612_374_361);
+    assert_eq!(crash.pathname, Some("/work/linux/build/crash".into()));
+
+    let libm = info
+        .find_mapping(0x7f51_3ff5_c000)
+        .expect("Missing mapping of libm");
+    assert_eq!(libm.offset, 0x10_8000);
+    assert!(!libm.permissions.read);
+    assert_eq!(info.find_mapping(0x1000), None);
+
+    assert_eq!(info.status.get("Name").map(String::as_str), Some("crash"));
+    assert_eq!(info.status.get("Pid").map(String::as_str), Some("1304"));
+    assert_eq!(info.cmdline, vec!["./crash".to_string()]);
+    assert_eq!(info.environ.get("HOME").map(String::as_str), Some("/root"));
+    assert_eq!(info.auxv[0], (33, 0x7fff_5aef_1000)); // AT_SYSINFO_EHDR
+    assert!(info.auxv.iter().all(|&(key, _)| key != 0));
+
+    let lsb_release = info.lsb_release.expect("Missing LSB release");
+    assert_eq!(lsb_release.id, Some("Ubuntu".into()));
+    assert_eq!(lsb_release.release, Some("16.04".into()));
+    assert_eq!(lsb_release.codename, Some("xenial".into()));
+    assert_eq!(lsb_release.description, Some("Ubuntu 16.04.3 LTS".into()));
+
+    assert_eq!(info.cpu_info.len(), 4);
+    assert_eq!(
+        info.cpu_info[1].get("vendor_id").map(String::as_str),
+        Some("GenuineIntel")
+    );
+}
+
+#[test]
+fn linux_info_windows() {
+    let data = read_fixture("windows/mini.dmp");
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let info = LinuxInfo::from_minidump(&minidump).expect("Could not read Linux streams");
+    assert_eq!(info, None);
+}
+
+#[test]
+fn parse_maps_entry() {
+    let entry = LinuxMapsEntry::parse(
+        "7f5140523000-7f5140527000 rw-s 00001000 08:01 42   /tmp/file with spaces",
+    )
+    .expect("Could not parse maps entry");
+
+    assert_eq!(entry.size(), 0x4000);
+    assert_eq!(
+        entry.permissions,
+        LinuxPermissions {
+            read: true,
+            write: true,
+            execute: false,
+            shared: true,
+        }
+    );
+    assert_eq!(entry.pathname, Some("/tmp/file with spaces".into()));
+
+    let anonymous = LinuxMapsEntry::parse("7f5140523000-7f5140527000 rw-p 00000000 00:00 0 ")
+        .expect("Could not parse maps entry");
+    assert_eq!(anonymous.pathname, None);
+
+    assert_eq!(LinuxMapsEntry::parse("7f5140523000 rw-p"), None);
+}
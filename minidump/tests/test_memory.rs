@@ -0,0 +1,120 @@
+use symbolic_minidump::memory::{
+    MemoryInfo, MemoryList, MemoryProtection, MemoryState, MemoryType,
+};
+use symbolic_minidump::parser::Minidump;
+use symbolic_testutils::minidump::MinidumpBuilder;
+use symbolic_testutils::{assert_snapshot_plain, read_fixture};
+
+#[test]
+fn read_memory_linux() {
+    let data = read_fixture("linux/mini.dmp");
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let memory = MemoryList::from_minidump(&minidump).expect("Could not read memory");
+
+    let regions = memory.regions();
+    assert_eq!(regions.len(), 2);
+    assert_eq!(regions[0].base_address, 0x40_1cf2);
+    assert_eq!(regions[1].base_address, 0x7fff_5ae4_a000);
+
+    let stack = memory
+        .find_region(0x7fff_5ae4_c000)
+        .expect("Missing stack memory");
+    assert_eq!(stack.size(), 12288);
+
+    // Reads must be fully contained in a region
+    assert_eq!(memory.read(0x7fff_5ae4_cff8, 8).map(<[u8]>::len), Some(8));
+    assert_eq!(memory.read(0x7fff_5ae4_cff8, 9), None);
+    assert_eq!(memory.read(0x1000, 1), None);
+    assert_eq!(
+        memory.read_u64(0x7fff_5ae4_a000),
+        memory
+            .read_u32(0x7fff_5ae4_a000)
+            .map(|low| u64::from(low) | u64::from(memory.read_u32(0x7fff_5ae4_a004).unwrap()) << 32)
+    );
+}
+
+#[test]
+fn hex_window_linux() {
+    let data = read_fixture("linux/mini.dmp");
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let memory = MemoryList::from_minidump(&minidump).expect("Could not read memory");
+
+    // The window is clipped to the start of the region
+    let window = memory
+        .hex_window(0x40_1cf8, 32)
+        .expect("Missing memory around the crash");
+    assert_eq!(window.base_address, 0x40_1cf2);
+    assert_eq!(window.end(), 0x40_1d20);
+    assert_snapshot_plain("hex_window_linux.txt", &window.to_string());
+
+    assert!(memory.hex_window(0x1000, 32).is_none());
+}
+
+#[test]
+fn read_memory64_truncated() {
+    // Two ranges, but the file ends within the contents of the second one
+    let mut builder = MinidumpBuilder::new();
+    let (_, base_rva) = builder.push(&[0xab; 8]);
+
+    let mut stream = Vec::new();
+    stream.extend_from_slice(&2u64.to_le_bytes()); // range count
+    stream.extend_from_slice(&u64::from(base_rva).to_le_bytes());
+    for &(base, size) in &[(0x1000u64, 8u64), (0x2000u64, 0x100u64)] {
+        stream.extend_from_slice(&base.to_le_bytes());
+        stream.extend_from_slice(&size.to_le_bytes());
+    }
+
+    builder.add_stream(9, &stream);
+    let data = builder.finish();
+
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let memory = minidump
+        .memory64_list()
+        .expect("Could not read memory list");
+    assert_eq!(memory.len(), 1);
+    assert_eq!(memory[0].base_address, 0x1000);
+    assert_eq!(memory[0].data, &[0xab; 8][..]);
+}
+
+#[test]
+fn read_memory_info() {
+    let mut stream = Vec::new();
+    stream.extend_from_slice(&16u32.to_le_bytes()); // header size
+    stream.extend_from_slice(&48u32.to_le_bytes()); // entry size
+    stream.extend_from_slice(&2u64.to_le_bytes()); // entry count
+
+    // An executable image, followed by a reserved region
+    for &(base, protect, size, state, ty) in &[
+        (0x40_0000u64, 0x20u32, 0x1000u64, 0x1000u32, 0x100_0000u32),
+        (0x40_1000u64, 0x104u32, 0x2000u64, 0x2000u32, 0x2_0000u32),
+    ] {
+        stream.extend_from_slice(&base.to_le_bytes());
+        stream.extend_from_slice(&base.to_le_bytes()); // allocation base
+        stream.extend_from_slice(&protect.to_le_bytes()); // allocation protect
+        stream.extend_from_slice(&[0; 4]);
+        stream.extend_from_slice(&size.to_le_bytes());
+        stream.extend_from_slice(&state.to_le_bytes());
+        stream.extend_from_slice(&protect.to_le_bytes());
+        stream.extend_from_slice(&ty.to_le_bytes());
+        stream.extend_from_slice(&[0; 4]);
+    }
+
+    let mut builder = MinidumpBuilder::new();
+    builder.add_stream(16, &stream);
+    let data = builder.finish();
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let infos = MemoryInfo::from_minidump(&minidump).expect("Could not read memory info");
+    assert_eq!(infos.len(), 2);
+
+    assert_eq!(infos[0].base_address, 0x40_0000);
+    assert_eq!(infos[0].end(), 0x40_1000);
+    assert_eq!(infos[0].state, MemoryState::Commit);
+    assert_eq!(infos[0].memory_type, MemoryType::Image);
+    assert_eq!(infos[0].protection.to_string(), "r-x");
+
+    assert!(infos[1].contains(0x40_2fff));
+    assert_eq!(infos[1].state, MemoryState::Reserve);
+    assert_eq!(infos[1].memory_type, MemoryType::Private);
+    assert_eq!(infos[1].protection, MemoryProtection(0x104));
+    assert_eq!(infos[1].protection.to_string(), "rw-g");
+}
@@ -0,0 +1,177 @@
+use symbolic_common::types::Arch;
+use symbolic_minidump::parser::{Minidump, MinidumpErrorKind, StreamType};
+use symbolic_testutils::read_fixture;
+
+#[test]
+fn parse_minidump_linux() {
+    let data = read_fixture("linux/mini.dmp");
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    assert_eq!(minidump.timestamp(), 1_522_061_032);
+    assert!(minidump.stream_types().contains(&StreamType::LinuxMaps));
+
+    let threads = minidump.threads().expect("Could not read threads");
+    assert_eq!(threads.len(), 1);
+    assert_eq!(threads[0].thread_id, 1304);
+    assert_eq!(threads[0].stack.base_address, 0x7fff_5ae4_a000);
+    assert_eq!(threads[0].stack.size(), 12288);
+
+    let modules = minidump.modules().expect("Could not read modules");
+    assert_eq!(modules.len(), 8);
+    assert_eq!(modules[0].base_address, 0x40_0000);
+    assert_eq!(modules[0].size, 106_496);
+    assert_eq!(modules[0].code_file(), "/work/linux/build/crash");
+    assert_eq!(modules[0].debug_file(), "/work/linux/build/crash");
+    assert_eq!(
+        modules[0].code_id(),
+        Some("f1c3bcc0279865fe3058404b2831d9e64135386c".into())
+    );
+    assert_eq!(
+        modules[0].debug_id().map(|id| id.breakpad().to_string()),
+        Some("C0BCC3F19827FE653058404B2831D9E60".into())
+    );
+
+    let exception = minidump
+        .exception()
+        .expect("Could not read exception")
+        .expect("Missing exception");
+    assert_eq!(exception.thread_id, 1304);
+    assert_eq!(exception.code, 11); // SIGSEGV
+    assert_eq!(exception.address, 69);
+    assert!(!exception.context.is_empty());
+
+    let system_info = minidump
+        .system_info()
+        .expect("Could not read system info")
+        .expect("Missing system info");
+    assert_eq!(system_info.arch(), Arch::X86_64);
+    assert_eq!(system_info.os_name(), Some("Linux"));
+    assert_eq!(system_info.cpu_count, 4);
+    assert_eq!(system_info.cpu_vendor(), Some("GenuineIntel".into()));
+    assert!(system_info
+        .csd_version
+        .starts_with("Linux 4.9.60-linuxkit-aufs"));
+
+    assert_eq!(
+        minidump.memory_list().expect("Could not read memory").len(),
+        2
+    );
+    assert!(minidump
+        .memory64_list()
+        .expect("Could not read memory")
+        .is_empty());
+    assert!(minidump
+        .misc_info()
+        .expect("Could not read misc info")
+        .is_none());
+}
+
+#[test]
+fn parse_minidump_macos() {
+    let data = read_fixture("macos/mini.dmp");
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+
+    let modules = minidump.modules().expect("Could not read modules");
+    assert_eq!(modules.len(), 43);
+    assert_eq!(modules[0].debug_file(), "crash");
+    assert_eq!(
+        modules[0].debug_id().map(|id| id.breakpad().to_string()),
+        Some("67E9247C814E392BA027DBDE6748FCBF0".into())
+    );
+
+    let system_info = minidump
+        .system_info()
+        .expect("Could not read system info")
+        .expect("Missing system info");
+    assert_eq!(system_info.os_name(), Some("Mac OS X"));
+    assert_eq!(system_info.os_version(), "10.12.6");
+    assert_eq!(system_info.csd_version, "16G29");
+
+    let misc_info = minidump
+        .misc_info()
+        .expect("Could not read misc info")
+        .expect("Missing misc info");
+    assert_eq!(misc_info.process_id, Some(2984));
+    assert_eq!(misc_info.process_create_time, Some(1_521_713_398));
+    assert_eq!(misc_info.build_string, None);
+}
+
+#[test]
+fn parse_minidump_windows() {
+    let data = read_fixture("windows/mini.dmp");
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+
+    let threads = minidump.threads().expect("Could not read threads");
+    assert_eq!(threads.len(), 4);
+    assert_eq!(threads[0].thread_id, 1636);
+
+    let modules = minidump.modules().expect("Could not read modules");
+    assert_eq!(modules.len(), 17);
+    assert_eq!(
+        modules[0].code_file(),
+        "C:\\projects\\breakpad-tools\\windows\\Release\\crash.exe"
+    );
+    assert_eq!(
+        modules[0].debug_file(),
+        "C:\\projects\\breakpad-tools\\windows\\Release\\crash.pdb"
+    );
+    assert_eq!(modules[0].code_id(), Some("5AB380779000".into()));
+    assert_eq!(
+        modules[0].debug_id().map(|id| id.breakpad().to_string()),
+        Some("3249D99D0C4049318610F4E4FB0B69361".into())
+    );
+
+    let ntdll = &modules[1];
+    assert_eq!(ntdll.code_file(), "C:\\Windows\\System32\\ntdll.dll");
+    assert_eq!(
+        ntdll.version_info.map(|info| info.file_version()),
+        Some([6, 2, 14393, 1715])
+    );
+
+    let exception = minidump
+        .exception()
+        .expect("Could not read exception")
+        .expect("Missing exception");
+    assert_eq!(exception.code, 0xc000_0005); // EXCEPTION_ACCESS_VIOLATION
+    assert_eq!(exception.parameters, vec![1, 69]);
+
+    let system_info = minidump
+        .system_info()
+        .expect("Could not read system info")
+        .expect("Missing system info");
+    assert_eq!(system_info.arch(), Arch::X86);
+    assert_eq!(system_info.os_name(), Some("Windows NT"));
+    assert_eq!(system_info.os_version(), "10.0.14393");
+
+    let misc_info = minidump
+        .misc_info()
+        .expect("Could not read misc info")
+        .expect("Missing misc info");
+    assert_eq!(misc_info.process_id, Some(1928));
+    assert_eq!(
+        misc_info.time_zone_name,
+        Some("Pacific Standard Time".into())
+    );
+    assert_eq!(
+        misc_info.build_string,
+        Some("10.0.14393.1715 (rs1_release_inmarket.170906-1810)".into())
+    );
+
+    assert_eq!(
+        minidump.memory_list().expect("Could not read memory").len(),
+        8
+    );
+    assert!(minidump
+        .unloaded_modules()
+        .expect("Could not read unloaded modules")
+        .is_empty());
+}
+
+#[test]
+fn parse_invalid_minidump() {
+    let error = Minidump::parse(b"MDMX").expect_err("Parsed invalid minidump");
+    assert_eq!(error.kind(), MinidumpErrorKind::BadFileMagic);
+
+    let data = read_fixture("linux/mini.dmp");
+    let error = Minidump::parse(&data[..40]).expect_err("Parsed truncated minidump");
+    assert_eq!(error.kind(), MinidumpErrorKind::BadHeader);
+}
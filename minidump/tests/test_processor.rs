@@ -1,6 +1,44 @@
 use symbolic_common::byteview::ByteView;
 use symbolic_minidump::processor::ProcessState;
-use symbolic_testutils::{assert_snapshot, fixture_path};
+use symbolic_testutils::minidump::{push_location, MinidumpBuilder};
+use symbolic_testutils::{assert_snapshot, fixture_path, read_fixture};
+
+/// Appends an `UnloadedModuleListStream` with the given base addresses, sizes and names.
+fn add_unloaded_modules(data: &[u8], modules: &[(u64, u32, &str)]) -> Vec<u8> {
+    let mut builder = MinidumpBuilder::from_bytes(data);
+    let mut stream = Vec::new();
+    stream.extend_from_slice(&12u32.to_le_bytes()); // header size
+    stream.extend_from_slice(&24u32.to_le_bytes()); // entry size
+    stream.extend_from_slice(&(modules.len() as u32).to_le_bytes());
+    for &(base, size, name) in modules {
+        stream.extend_from_slice(&base.to_le_bytes());
+        stream.extend_from_slice(&size.to_le_bytes());
+        stream.extend_from_slice(&[0; 8]); // checksum and timestamp
+        stream.extend_from_slice(&builder.push_string(name).to_le_bytes());
+    }
+
+    builder.add_stream(14, &stream);
+    builder.finish()
+}
+
+/// Appends a Crashpad info stream with the given process annotations and no modules.
+fn add_crashpad_annotations(data: &[u8], annotations: &[(&str, &str)]) -> Vec<u8> {
+    let mut builder = MinidumpBuilder::from_bytes(data);
+    let mut dictionary = (annotations.len() as u32).to_le_bytes().to_vec();
+    for &(key, value) in annotations {
+        dictionary.extend_from_slice(&builder.push_bytes(key.as_bytes()).to_le_bytes());
+        dictionary.extend_from_slice(&builder.push_bytes(value.as_bytes()).to_le_bytes());
+    }
+    let dictionary = builder.push(&dictionary);
+
+    let mut stream = 1u32.to_le_bytes().to_vec(); // version
+    stream.extend_from_slice(&[0; 32]); // report and client id
+    push_location(&mut stream, dictionary);
+    push_location(&mut stream, (0, 0)); // module list
+
+    builder.add_stream(0x4350_0001, &stream);
+    builder.finish()
+}
 
 #[test]
 fn process_minidump_linux() {
@@ -26,6 +64,21 @@ fn process_minidump_windows() {
     assert_snapshot("process_state_windows.txt", &state);
 }
 
+#[test]
+fn get_crash_signal_linux() {
+    let buffer = ByteView::from_path(fixture_path("linux/mini.dmp"))
+        .expect("Could not open the minidump file");
+    let state = ProcessState::from_minidump(&buffer, None).expect("Could not process minidump");
+    let crashed = state.threads()[state.requesting_thread() as usize];
+    assert_eq!(state.crash_signal(&crashed), Some(11));
+
+    for thread in state.threads() {
+        if thread.thread_id() != crashed.thread_id() {
+            assert_eq!(state.crash_signal(&thread), None);
+        }
+    }
+}
+
 #[test]
 fn get_referenced_modules_linux() {
     let buffer = ByteView::from_path(fixture_path("linux/mini.dmp"))
@@ -52,3 +105,105 @@ fn get_referenced_modules_windows() {
         &state.referenced_modules(),
     );
 }
+
+#[test]
+fn get_module_info_windows() {
+    let buffer = ByteView::from_path(fixture_path("windows/mini.dmp"))
+        .expect("Could not open the minidump file");
+    let state = ProcessState::from_minidump(&buffer, None).expect("Could not process minidump");
+
+    let ntdll = state
+        .modules()
+        .into_iter()
+        .find(|module| module.code_file().ends_with("ntdll.dll"))
+        .expect("Missing ntdll");
+    assert_eq!(ntdll.version(), "6.2.14393.1715");
+    assert_eq!(ntdll.checksum(), 0x18_d92c);
+    assert_eq!(ntdll.time_date_stamp(), 0x59b0_d8f3);
+    assert_eq!(ntdll.file_version(), Some([6, 2, 14393, 1715]));
+    assert_eq!(ntdll.product_version(), Some([10, 0, 14393, 1715]));
+
+    let address = ntdll.base_address() + 0x100;
+    assert_eq!(state.find_module(address), Some(ntdll));
+    assert!(state.unloaded_modules().is_empty());
+    assert_eq!(state.find_unloaded_module(address), None);
+}
+
+#[test]
+fn find_unloaded_module_windows() {
+    let data = read_fixture("windows/mini.dmp");
+    let state = ProcessState::from_minidump(&ByteView::from_slice(&data), None)
+        .expect("Could not process minidump");
+
+    // Place the unloaded module right behind the last loaded module, and another one overlapping
+    // ntdll, which must never be reported.
+    let ntdll = state
+        .modules()
+        .into_iter()
+        .find(|module| module.code_file().ends_with("ntdll.dll"))
+        .expect("Missing ntdll");
+    let ntdll_base = ntdll.base_address();
+    let base = state
+        .modules()
+        .into_iter()
+        .map(|module| module.base_address() + module.size())
+        .max()
+        .expect("Missing modules");
+    let base = (base + 0xffff) & !0xffff;
+
+    let data = add_unloaded_modules(
+        &data,
+        &[
+            (base, 0x2000, "unloaded.dll"),
+            (ntdll_base, 0x1000, "shadow.dll"),
+        ],
+    );
+    let state = ProcessState::from_minidump(&ByteView::from_slice(&data), None)
+        .expect("Could not process minidump");
+    assert_eq!(state.unloaded_modules().len(), 2);
+
+    let unloaded = state
+        .find_unloaded_module(base + 0x1000)
+        .expect("Missing unloaded module");
+    assert_eq!(unloaded.name, "unloaded.dll");
+    assert_eq!(unloaded.base_address, base);
+    assert_eq!(unloaded.size, 0x2000);
+
+    assert_eq!(state.find_module(base + 0x1000), None);
+    assert_eq!(state.find_unloaded_module(base + 0x2000), None);
+    assert_eq!(state.find_unloaded_module(ntdll_base + 0x100), None);
+}
+
+#[test]
+fn get_thread_info_windows() {
+    let buffer = ByteView::from_path(fixture_path("windows/mini.dmp"))
+        .expect("Could not open the minidump file");
+    let state = ProcessState::from_minidump(&buffer, None).expect("Could not process minidump");
+
+    let thread = state.threads()[0];
+    let info = thread.thread_info().expect("Missing thread info");
+    assert_eq!(info.name, None);
+    assert_eq!(info.create_time, None);
+    assert_eq!(thread.thread_name(), None);
+}
+
+#[test]
+fn get_thread_name_linux() {
+    let data = read_fixture("linux/mini.dmp");
+    let state = ProcessState::from_minidump(&ByteView::from_slice(&data), None)
+        .expect("Could not process minidump");
+    let thread = state.threads()[0];
+    let thread_id = thread.thread_id();
+    assert_eq!(thread.thread_name(), None);
+
+    let key = format!("thread_name:{}", thread_id);
+    let data = add_crashpad_annotations(&data, &[(&key, "RenderThread"), ("prod", "app")]);
+    let state = ProcessState::from_minidump(&ByteView::from_slice(&data), None)
+        .expect("Could not process minidump");
+
+    let thread = state.threads()[0];
+    assert_eq!(thread.thread_id(), thread_id);
+    assert_eq!(thread.thread_name(), Some("RenderThread"));
+    let info = thread.thread_info().expect("Missing thread info");
+    assert_eq!(info.name.as_deref(), Some("RenderThread"));
+}
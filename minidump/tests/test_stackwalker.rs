@@ -0,0 +1,272 @@
+use std::fs;
+
+use symbolic_common::byteview::ByteView;
+use symbolic_debuginfo::FatObject;
+use symbolic_minidump::cfi::CfiCache;
+use symbolic_minidump::parser::Minidump;
+use symbolic_minidump::stackwalker::{CodeModuleId, FrameInfoMap, FrameTrust, RegVal, Stackwalker};
+use symbolic_testutils::fixture_path;
+use symbolic_testutils::minidump::{push_location, MinidumpBuilder};
+
+#[test]
+fn stackwalk_linux_without_cfi() -> Result<(), failure::Error> {
+    let data = fs::read(fixture_path("linux/mini.dmp"))?;
+    let minidump = Minidump::parse(&data)?;
+    let walker = Stackwalker::new(&minidump, None)?;
+
+    let threads = walker.walk();
+    assert_eq!(threads.len(), minidump.threads()?.len());
+
+    let crashed = threads.iter().find(|t| t.thread_id == 1304).unwrap();
+    let frame = &crashed.frames[0];
+    assert_eq!(frame.instruction, 4_201_842);
+    assert_eq!(frame.trust, FrameTrust::Context);
+    assert_eq!(frame.registers.get("rip"), Some(&RegVal::U64(4_201_842)));
+    assert!(frame.module.is_some());
+
+    assert!(crashed.frames.len() > 1);
+    for frame in &crashed.frames[1..] {
+        assert_ne!(frame.trust, FrameTrust::Context);
+        assert!(frame.module.is_some());
+        assert_eq!(frame.instruction, frame.return_address - 1);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn stackwalk_linux_with_cfi() -> Result<(), failure::Error> {
+    let view = ByteView::from_path(fixture_path("linux/crash"))?;
+    let fat = FatObject::parse(view)?;
+    let object = fat.get_object(0)?.unwrap();
+    let cache = CfiCache::from_object(&object)?;
+
+    let data = fs::read(fixture_path("linux/mini.dmp"))?;
+    let minidump = Minidump::parse(&data)?;
+
+    let mut frame_infos = FrameInfoMap::new();
+    frame_infos.insert(CodeModuleId::from(object.id().unwrap()), cache);
+    let walker = Stackwalker::new(&minidump, Some(&frame_infos))?;
+
+    let threads = walker.walk();
+    let crashed = threads.iter().find(|t| t.thread_id == 1304).unwrap();
+    assert_eq!(crashed.frames[0].trust, FrameTrust::Context);
+    assert_eq!(crashed.frames[1].trust, FrameTrust::CFI);
+
+    Ok(())
+}
+
+#[test]
+fn stackwalk_windows_with_frame_pointers() -> Result<(), failure::Error> {
+    let view = ByteView::from_path(fixture_path("windows/crash.sym"))?;
+    let fat = FatObject::parse(view)?;
+    let object = fat.get_object(0)?.unwrap();
+    let cache = CfiCache::from_object(&object)?;
+
+    let data = fs::read(fixture_path("windows/mini.dmp"))?;
+    let minidump = Minidump::parse(&data)?;
+
+    let mut frame_infos = FrameInfoMap::new();
+    frame_infos.insert(CodeModuleId::from(object.id().unwrap()), cache);
+    let walker = Stackwalker::new(&minidump, Some(&frame_infos))?;
+
+    let threads = walker.walk();
+    let crashed = threads.iter().find(|t| t.thread_id == 1636).unwrap();
+    assert_eq!(crashed.frames[0].instruction, 2_763_325);
+    assert_eq!(crashed.frames[0].trust, FrameTrust::Context);
+    // There is no STACK WIN record for the crashing function, so the walker uses frame pointers.
+    assert_eq!(crashed.frames[1].return_address, 2_762_960);
+    assert_eq!(crashed.frames[1].instruction, 2_762_959);
+    assert_eq!(crashed.frames[1].trust, FrameTrust::FP);
+
+    Ok(())
+}
+
+/// Base address of the module in minidumps built by `build_minidump`.
+const MODULE_BASE: u64 = 0x4000_0000;
+
+/// Base address of the thread's stack in minidumps built by `build_minidump`.
+const STACK_BASE: u64 = 0x8000;
+
+/// Builds a minidump with a single thread and a single module at `MODULE_BASE`.
+///
+/// The stack consists of the given words at `STACK_BASE`, padded with zeros to 256 bytes.
+fn build_minidump(processor_architecture: u16, context: &[u8], stack: &[u64]) -> Vec<u8> {
+    // Only ARM (5) is a 32-bit architecture in these tests
+    let word_size = if processor_architecture == 5 { 4 } else { 8 };
+    let mut stack_data = Vec::new();
+    for word in stack {
+        stack_data.extend_from_slice(&word.to_le_bytes()[..word_size]);
+    }
+    stack_data.resize(256, 0);
+
+    let mut builder = MinidumpBuilder::new();
+    let name_rva = builder.push_string("libtest.so");
+    let cv_record =
+        builder.push(b"LEpB\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10");
+    let stack = builder.push(&stack_data);
+    let context = builder.push(context);
+
+    let mut system_info = processor_architecture.to_le_bytes().to_vec();
+    system_info.resize(20, 0);
+    system_info.extend_from_slice(&0x8201u32.to_le_bytes()); // platform id
+    system_info.resize(56, 0);
+    builder.add_stream(7, &system_info);
+
+    let mut modules = 1u32.to_le_bytes().to_vec();
+    modules.extend_from_slice(&MODULE_BASE.to_le_bytes());
+    modules.extend_from_slice(&0x1000u32.to_le_bytes()); // size
+    modules.extend_from_slice(&[0; 8]); // checksum and timestamp
+    modules.extend_from_slice(&name_rva.to_le_bytes());
+    modules.extend_from_slice(&[0; 52]); // version info
+    push_location(&mut modules, cv_record);
+    modules.extend_from_slice(&[0; 24]); // misc record and reserved
+    builder.add_stream(4, &modules);
+
+    let mut threads = 1u32.to_le_bytes().to_vec();
+    threads.extend_from_slice(&0x1a2bu32.to_le_bytes());
+    threads.extend_from_slice(&[0; 20]); // suspend count, priority and teb
+    threads.extend_from_slice(&STACK_BASE.to_le_bytes());
+    push_location(&mut threads, stack);
+    push_location(&mut threads, context);
+    builder.add_stream(3, &threads);
+
+    builder.finish()
+}
+
+/// Creates a `FrameInfoMap` for the module of `build_minidump` from Breakpad STACK records.
+fn build_frame_infos(minidump: &Minidump<'_>, arch: &str, records: &str) -> FrameInfoMap<'static> {
+    let module = &minidump.modules().unwrap()[0];
+    let id = CodeModuleId::from(module.debug_id().unwrap());
+    let sym = format!("MODULE Linux {} {} libtest.so\n{}", arch, id, records);
+
+    let fat = FatObject::parse(ByteView::from_vec(sym.into_bytes())).unwrap();
+    let object = fat.get_object(0).unwrap().unwrap();
+
+    let mut frame_infos = FrameInfoMap::new();
+    frame_infos.insert(id, CfiCache::from_object(&object).unwrap());
+    frame_infos
+}
+
+#[test]
+fn stackwalk_arm() -> Result<(), failure::Error> {
+    // MDRawContextARM with r0 to r10, fp, r12, sp, lr and pc
+    let mut registers = [0u32; 16];
+    registers[11] = 0x8040; // fp
+    registers[13] = 0x8000; // sp
+    registers[15] = 0x4000_0110; // pc
+
+    let mut context = 0x4000_0002u32.to_le_bytes().to_vec();
+    for register in &registers {
+        context.extend_from_slice(&register.to_le_bytes());
+    }
+
+    // The first frame saved fp and lr on the stack, the second one is a frame pointer record
+    let mut stack = vec![0; 18];
+    stack[0] = 0x8040;
+    stack[1] = 0x4000_0205;
+    stack[17] = 0x4000_0305;
+
+    let data = build_minidump(5, &context, &stack);
+    let minidump = Minidump::parse(&data)?;
+    let frame_infos = build_frame_infos(
+        &minidump,
+        "arm",
+        "STACK CFI INIT 100 20 .cfa: sp 8 + .ra: .cfa -4 + ^ r11: .cfa -8 + ^\n",
+    );
+
+    let walker = Stackwalker::new(&minidump, Some(&frame_infos))?;
+    let frames = &walker.walk()[0].frames;
+    assert_eq!(frames.len(), 3);
+
+    assert_eq!(frames[0].instruction, 0x4000_0110);
+    assert_eq!(frames[0].trust, FrameTrust::Context);
+
+    assert_eq!(frames[1].return_address, 0x4000_0205);
+    assert_eq!(frames[1].instruction, 0x4000_0203);
+    assert_eq!(frames[1].trust, FrameTrust::CFI);
+    assert_eq!(frames[1].registers.get("sp"), Some(&RegVal::U32(0x8008)));
+    assert_eq!(frames[1].registers.get("fp"), Some(&RegVal::U32(0x8040)));
+
+    assert_eq!(frames[2].return_address, 0x4000_0305);
+    assert_eq!(frames[2].trust, FrameTrust::FP);
+    assert_eq!(frames[2].registers.get("sp"), Some(&RegVal::U32(0x8048)));
+
+    Ok(())
+}
+
+/// Creates an MDRawContextARM64 with the given frame pointer, stack pointer and pc.
+fn arm64_context(fp: u64, sp: u64, pc: u64) -> Vec<u8> {
+    // x0 to x28, fp, lr, sp and pc
+    let mut registers = [0u64; 33];
+    registers[29] = fp;
+    registers[31] = sp;
+    registers[32] = pc;
+
+    let mut context = 0x8000_0002u64.to_le_bytes().to_vec();
+    for register in &registers {
+        context.extend_from_slice(&register.to_le_bytes());
+    }
+    context
+}
+
+#[test]
+fn stackwalk_arm64() -> Result<(), failure::Error> {
+    let context = arm64_context(0x8040, 0x8000, 0x4000_0110);
+
+    // The first frame saved fp and lr on the stack, the second one is a frame pointer record
+    let mut stack = vec![0; 10];
+    stack[0] = 0x8040;
+    stack[1] = 0x4000_0204;
+    stack[9] = 0x4000_0304;
+
+    let data = build_minidump(12, &context, &stack);
+    let minidump = Minidump::parse(&data)?;
+    let frame_infos = build_frame_infos(
+        &minidump,
+        "arm64",
+        "STACK CFI INIT 100 20 .cfa: sp 16 + .ra: .cfa -8 + ^ x29: .cfa -16 + ^\n",
+    );
+
+    let walker = Stackwalker::new(&minidump, Some(&frame_infos))?;
+    let frames = &walker.walk()[0].frames;
+    assert_eq!(frames.len(), 3);
+
+    assert_eq!(frames[0].instruction, 0x4000_0110);
+    assert_eq!(frames[0].trust, FrameTrust::Context);
+
+    assert_eq!(frames[1].return_address, 0x4000_0204);
+    assert_eq!(frames[1].instruction, 0x4000_0200);
+    assert_eq!(frames[1].trust, FrameTrust::CFI);
+    assert_eq!(frames[1].registers.get("sp"), Some(&RegVal::U64(0x8010)));
+    assert_eq!(frames[1].registers.get("x29"), Some(&RegVal::U64(0x8040)));
+
+    assert_eq!(frames[2].return_address, 0x4000_0304);
+    assert_eq!(frames[2].trust, FrameTrust::FP);
+    assert_eq!(frames[2].registers.get("sp"), Some(&RegVal::U64(0x8050)));
+
+    Ok(())
+}
+
+#[test]
+fn stackwalk_scan_with_function_lookup() -> Result<(), failure::Error> {
+    // Without a valid frame pointer, the caller can only be found by scanning
+    let context = arm64_context(0, 0x8000, 0x4000_0400);
+    let data = build_minidump(12, &context, &[0x4000_0504, 0x4000_0604]);
+    let minidump = Minidump::parse(&data)?;
+
+    let walker = Stackwalker::new(&minidump, None)?;
+    let frames = &walker.walk()[0].frames;
+    assert_eq!(frames[1].return_address, 0x4000_0504);
+    assert_eq!(frames[1].trust, FrameTrust::Scan);
+
+    // There is no function at 0x500 in the module, so the first candidate is skipped
+    let mut walker = Stackwalker::new(&minidump, None)?;
+    walker.set_function_lookup(|_, address| Some(address != 0x500));
+    let frames = &walker.walk()[0].frames;
+    assert_eq!(frames[1].return_address, 0x4000_0604);
+    assert_eq!(frames[1].trust, FrameTrust::Scan);
+    assert_eq!(frames[1].registers.get("sp"), Some(&RegVal::U64(0x8010)));
+
+    Ok(())
+}
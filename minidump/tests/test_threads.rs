@@ -0,0 +1,102 @@
+use symbolic_minidump::parser::{Minidump, MinidumpErrorKind, MinidumpThreadInfo};
+use symbolic_testutils::minidump::MinidumpBuilder;
+use symbolic_testutils::read_fixture;
+
+#[test]
+fn read_thread_names() {
+    let names = ["RenderThread", "main"];
+
+    let mut builder = MinidumpBuilder::new();
+    let mut stream = (names.len() as u32).to_le_bytes().to_vec();
+    for (index, name) in names.iter().enumerate() {
+        let rva = builder.push_string(name);
+        stream.extend_from_slice(&(0x1a2b + index as u32).to_le_bytes());
+        stream.extend_from_slice(&u64::from(rva).to_le_bytes());
+    }
+
+    builder.add_stream(24, &stream);
+    let data = builder.finish();
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let names = minidump
+        .thread_names()
+        .expect("Could not read thread names");
+
+    assert_eq!(names.len(), 2);
+    assert_eq!(names[&0x1a2b], "RenderThread");
+    assert_eq!(names[&0x1a2c], "main");
+}
+
+#[test]
+fn read_thread_names_oversized() {
+    // The string claims to be 4GB long, but only contains two characters
+    let mut builder = MinidumpBuilder::new();
+    let mut string = 0xffff_fffeu32.to_le_bytes().to_vec();
+    string.extend_from_slice(&[b'a', 0, b'b', 0]);
+    let (_, rva) = builder.push(&string);
+
+    let mut stream = 1u32.to_le_bytes().to_vec();
+    stream.extend_from_slice(&0x1a2bu32.to_le_bytes());
+    stream.extend_from_slice(&u64::from(rva).to_le_bytes());
+    builder.add_stream(24, &stream);
+
+    let data = builder.finish();
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let error = minidump
+        .thread_names()
+        .expect_err("Read a truncated thread name");
+    assert_eq!(error.kind(), MinidumpErrorKind::BadStream);
+}
+
+#[test]
+fn read_thread_info_list() {
+    let mut stream = Vec::new();
+    stream.extend_from_slice(&12u32.to_le_bytes()); // header size
+    stream.extend_from_slice(&64u32.to_le_bytes()); // entry size
+    stream.extend_from_slice(&1u32.to_le_bytes()); // entry count
+
+    for &value in &[0x1a2bu32, 0, 0, 259] {
+        stream.extend_from_slice(&value.to_le_bytes());
+    }
+    for &value in &[131_000_000_000_000_000u64, 0, 1000, 2000, 0x40_1000, 0b11] {
+        stream.extend_from_slice(&value.to_le_bytes());
+    }
+
+    let mut builder = MinidumpBuilder::new();
+    builder.add_stream(17, &stream);
+    let data = builder.finish();
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+    let infos = minidump
+        .thread_info_list()
+        .expect("Could not read thread info");
+
+    assert_eq!(
+        infos,
+        vec![MinidumpThreadInfo {
+            thread_id: 0x1a2b,
+            dump_flags: 0,
+            dump_error: 0,
+            exit_status: 259,
+            create_time: 131_000_000_000_000_000,
+            exit_time: 0,
+            kernel_time: 1000,
+            user_time: 2000,
+            start_address: 0x40_1000,
+            affinity: 0b11,
+        }]
+    );
+}
+
+#[test]
+fn read_thread_names_missing() {
+    let data = read_fixture("windows/mini.dmp");
+    let minidump = Minidump::parse(&data).expect("Could not parse minidump");
+
+    assert!(minidump
+        .thread_names()
+        .expect("Could not read thread names")
+        .is_empty());
+    assert!(minidump
+        .thread_info_list()
+        .expect("Could not read thread info")
+        .is_empty());
+}
@@ -10,7 +10,7 @@ from symbolic.common import parse_addr
 from symbolic import exceptions
 
 
-__all__ = ['LineInfo', 'SymCache', 'find_best_instruction',
+__all__ = ['LineInfo', 'FunctionInfo', 'SymCache', 'find_best_instruction',
            'SYMCACHE_LATEST_VERSION']
 
 
@@ -70,6 +70,62 @@ class LineInfo(object):
         )
 
 
+@implements_to_string
+class FunctionInfo(object):
+
+    def __init__(self, id, parent_id, addr, size, lang, symbol,
+                 comp_dir=None):
+        self.id = id
+        self.parent_id = parent_id
+        self.addr = addr
+        self.size = size
+        self.lang = lang
+        self.symbol = symbol
+        self.comp_dir = comp_dir or None
+
+    @property
+    def function_name(self):
+        """The demangled function name."""
+        return demangle_name(self.symbol, lang=self.lang)
+
+    @property
+    def is_inlined(self):
+        """Returns true if this function is inlined into its parent."""
+        return self.parent_id is not None
+
+    def __str__(self):
+        return '%s (0x%x-0x%x)' % (
+            self.function_name,
+            self.addr,
+            self.addr + self.size,
+        )
+
+    def __repr__(self):
+        return 'FunctionInfo(%s)' % (
+            ', '.join('%s=%r' % x for x in sorted(self.__dict__.items()))
+        )
+
+
+def _convert_function_lookup_result(rv):
+    try:
+        matches = []
+        for idx in range(rv.len):
+            func = rv.items[idx]
+            matches.append(FunctionInfo(
+                id=func.id,
+                parent_id=None if func.parent_id == 0xffffffff
+                else func.parent_id,
+                addr=func.addr,
+                size=func.size,
+                lang=decode_str(func.lang),
+                symbol=decode_str(func.symbol),
+                comp_dir=decode_str(func.comp_dir),
+            ))
+    finally:
+        rustcall(lib.symbolic_function_lookup_result_free, ffi.addressof(rv))
+    return matches
+
+
 class SymCache(RustObject):
     __dealloc_func__ = lib.symbolic_symcache_free
 
@@ -157,6 +213,20 @@ class SymCache(RustObject):
             rustcall(lib.symbolic_lookup_result_free, ffi.addressof(rv))
         return matches
 
+    def lookup_name(self, name):
+        """Look up all functions with the given demangled name, including
+        inlined instances."""
+        rv = self._methodcall(lib.symbolic_symcache_lookup_name,
+                              encode_str(name))
+        return _convert_function_lookup_result(rv)
+
+    def lookup_range(self, start, end):
+        """Look up all functions overlapping the range from `start` to
+        `end` (exclusive)."""
+        rv = self._methodcall(lib.symbolic_symcache_lookup_range,
+                              parse_addr(start), parse_addr(end))
+        return _convert_function_lookup_result(rv)
+
 
 def find_best_instruction(addr, arch, crashing_frame=False,
                           signal=None, ip_reg=None):
@@ -69,6 +69,29 @@ def test_symbolicate_electron_darwin_sym(res_path):
     # "lang", "comp_dir" and "base_dir" are not available in .sym files
 
 
+def test_lookup_name_and_range(res_path):
+    path = os.path.join(
+        res_path, 'electron/1.8.1/Electron/CB63147AC9DC308B8CA1EE92A5042E8E0/Electron.sym')
+    fo = FatObject.from_path(path)
+    o = fo.get_object(arch='x86_64')
+    cache = o.make_symcache()
+
+    functions = cache.lookup_name('main')
+    assert len(functions) == 1
+    assert functions[0].symbol == 'main'
+    assert functions[0].addr == 0xf00
+    assert functions[0].size == 0x2b
+    assert functions[0].parent_id is None
+
+    assert cache.lookup_name('missing') == []
+
+    functions = cache.lookup_range(0xf25, 0xf26)
+    assert [f.symbol for f in functions] == ['main']
+    assert functions[0].id == cache.lookup_name('main')[0].id
+
+    assert cache.lookup_range(0xf25, 0xf25) == []
+
+
 def test_unicode_ignore_decode():
     sv = SourceView.from_bytes(u'fööbar'.encode('latin1'))
     assert sv[0] == u'f\ufffd\ufffdbar'
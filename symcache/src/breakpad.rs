@@ -41,7 +41,9 @@ impl<'input> BreakpadInfo<'input> {
     }
 
     fn parse(&mut self, object: &'input Object<'_>) -> Result<(), SymCacheError> {
-        for record in object.breakpad_records() {
+        // STACK records are not needed for symbolication and are skipped without parsing, so that
+        // malformed unwind information does not fail the conversion.
+        for record in object.breakpad_records().skip_stack_records() {
             match record.context(SymCacheErrorKind::BadDebugFile)? {
                 BreakpadRecord::Module(m) => self.module = Some(m),
                 BreakpadRecord::File(f) => self.files.push(f),
@@ -65,8 +67,10 @@ impl<'input> BreakpadInfo<'input> {
                 BreakpadRecord::Info(_) => {
                     // not relevant
                 }
-                BreakpadRecord::Stack => {
-                    // not relevant
+                BreakpadRecord::StackCfi(_)
+                | BreakpadRecord::StackCfiDelta(_)
+                | BreakpadRecord::StackWin(_) => {
+                    // skipped above
                 }
             }
         }
@@ -1,5 +1,6 @@
 use std::cell::RefCell;
 use std::fmt;
+use std::hash::Hasher;
 use std::io;
 use std::io::Write;
 use std::mem;
@@ -7,6 +8,7 @@ use std::slice;
 use std::str;
 
 use failure::ResultExt;
+use fnv::{FnvHashSet, FnvHasher};
 
 use symbolic_common::byteview::ByteView;
 use symbolic_common::types::{Arch, DebugId, Language, Name};
@@ -15,17 +17,22 @@ use symbolic_demangle::Demangle;
 
 use crate::error::{SymCacheError, SymCacheErrorKind};
 use crate::types::{
-    CacheFileHeader, CacheFileHeaderV1, CacheFileHeaderV2, CacheFilePreamble, DataSource,
-    FileRecord, FuncRecord, Seg,
+    CacheFileHeader, CacheFileHeaderV1, CacheFileHeaderV2, CacheFileHeaderV3, CacheFilePreamble,
+    DataSource, FileRecord, FileRecordV1, FileRecordV3, FuncRecord, FuncRecordV1, FuncRecordV3,
+    LineRecord, LineRecordV1, LineRecordV3, NameRecordV3, Seg,
 };
-use crate::utils::common_join_path;
+use crate::utils::{common_join_path, path_ends_with};
 use crate::writer;
 
 /// The magic file preamble to identify symcache files.
 pub const SYMCACHE_MAGIC: [u8; 4] = *b"SYMC";
 
 /// The latest version of the file format.
-pub const SYMCACHE_LATEST_VERSION: u32 = 2;
+///
+/// Version 3 widened function lengths, line numbers, file IDs and file name lengths. Line address
+/// offsets were widened from 8 to 16 bits only, since line records dominate the file size.
+/// Caches of versions 1 and 2 can still be read.
+pub const SYMCACHE_LATEST_VERSION: u32 = 3;
 
 /// Information on a matched source line.
 pub struct LineInfo<'a> {
@@ -156,11 +163,70 @@ impl<'a> fmt::Debug for LineInfo<'a> {
     }
 }
 
+/// Function records of a cache file in the layout of its version.
+enum FuncRecords<'a> {
+    V1(&'a [FuncRecordV1]),
+    V3(&'a [FuncRecordV3]),
+}
+
+impl<'a> FuncRecords<'a> {
+    fn len(&self) -> usize {
+        match *self {
+            FuncRecords::V1(records) => records.len(),
+            FuncRecords::V3(records) => records.len(),
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<FuncRecord> {
+        match *self {
+            FuncRecords::V1(records) => records.get(index).map(|r| FuncRecord::from(*r)),
+            FuncRecords::V3(records) => records.get(index).map(|r| FuncRecord::from(*r)),
+        }
+    }
+
+    fn binary_search_by_addr(&self, addr: u64) -> Result<usize, usize> {
+        match *self {
+            FuncRecords::V1(records) => {
+                records.binary_search_by_key(&addr, |r| FuncRecord::from(*r).addr_start())
+            }
+            FuncRecords::V3(records) => {
+                records.binary_search_by_key(&addr, |r| FuncRecord::from(*r).addr_start())
+            }
+        }
+    }
+}
+
+/// Line records of a function in the layout of the cache file version.
+enum LineRecords<'a> {
+    V1(&'a [LineRecordV1]),
+    V3(&'a [LineRecordV3]),
+}
+
+impl<'a> LineRecords<'a> {
+    fn is_empty(&self) -> bool {
+        match *self {
+            LineRecords::V1(records) => records.is_empty(),
+            LineRecords::V3(records) => records.is_empty(),
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<LineRecord> {
+        match *self {
+            LineRecords::V1(records) => records.get(index).map(|r| LineRecord::from(*r)),
+            LineRecords::V3(records) => records.get(index).map(|r| LineRecord::from(*r)),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = LineRecord> + '_ {
+        (0..).map_while(move |index| self.get(index))
+    }
+}
+
 /// A view of a single function in a `SymCache`.
 pub struct Function<'a> {
     cache: &'a SymCache<'a>,
     id: u32,
-    fun: &'a FuncRecord,
+    fun: FuncRecord,
 }
 
 impl<'a> Function<'a> {
@@ -179,10 +245,15 @@ impl<'a> Function<'a> {
         self.fun.addr_start()
     }
 
+    /// The size of the function in bytes.
+    pub fn size(&self) -> u64 {
+        u64::from(self.fun.len)
+    }
+
     /// The symbol of the function.
     pub fn symbol(&self) -> &str {
         self.cache
-            .get_symbol(self.fun.symbol_id())
+            .get_symbol(self.fun.symbol_id)
             .unwrap_or(None)
             .unwrap_or("?")
     }
@@ -200,6 +271,16 @@ impl<'a> Function<'a> {
         Language::from_u32(self.fun.lang.into()).unwrap_or(Language::Unknown)
     }
 
+    /// The source of the function's debug information.
+    ///
+    /// Unless the symcache was merged from multiple objects, this is the data source of the
+    /// entire symcache.
+    pub fn data_source(&self) -> DataSource {
+        self.cache
+            .function_source(self.id)
+            .unwrap_or(DataSource::Unknown)
+    }
+
     /// The compilation dir of the function.
     pub fn comp_dir(&self) -> &str {
         self.cache
@@ -211,7 +292,7 @@ impl<'a> Function<'a> {
     pub fn lines(&'a self) -> Lines<'a> {
         Lines {
             cache: self.cache,
-            fun: &self.fun,
+            fun: self.fun,
             addr: self.fun.addr_start(),
             idx: 0,
         }
@@ -221,7 +302,7 @@ impl<'a> Function<'a> {
 /// An iterator over all lines.
 pub struct Lines<'a> {
     cache: &'a SymCache<'a>,
-    fun: &'a FuncRecord,
+    fun: FuncRecord,
     addr: u64,
     idx: usize,
 }
@@ -230,8 +311,19 @@ pub struct Lines<'a> {
 pub struct Line<'a> {
     cache: &'a SymCache<'a>,
     addr: u64,
-    line: u16,
-    file_id: u16,
+    line: u32,
+    file_id: u32,
+}
+
+/// A range of instructions that maps to a single source line.
+pub struct LineRange<'a> {
+    cache: &'a SymCache<'a>,
+    func_id: u32,
+    fun: FuncRecord,
+    addr: u64,
+    len: u64,
+    line: u32,
+    file_id: u32,
 }
 
 /// An iterator over all functions in a `SymCache`.
@@ -267,7 +359,7 @@ impl<'a> Iterator for Lines<'a> {
     type Item = Result<Line<'a>, SymCacheError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let segment = match self.cache.get_segment(&self.fun.line_records) {
+        let segment = match self.cache.line_records(&self.fun) {
             Ok(segment) => segment,
             Err(e) => return Some(Err(e)),
         };
@@ -362,11 +454,79 @@ impl<'a> Line<'a> {
     }
 
     /// The line number of the line.
-    pub fn line(&self) -> u16 {
+    pub fn line(&self) -> u32 {
         self.line
     }
 }
 
+impl<'a> LineRange<'a> {
+    /// The function containing this range.
+    ///
+    /// For call sites of inlined functions, this is the function that the call was inlined into.
+    pub fn function(&self) -> Function<'a> {
+        Function {
+            cache: self.cache,
+            id: self.func_id,
+            fun: self.fun,
+        }
+    }
+
+    /// The address of the first instruction in this range.
+    pub fn addr(&self) -> u64 {
+        self.addr
+    }
+
+    /// The size of this range in bytes.
+    pub fn size(&self) -> u64 {
+        self.len
+    }
+
+    /// The line number of the range.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// The filename of the range.
+    pub fn filename(&self) -> &'a str {
+        if let Some(rec) = self.cache.get_file_record(self.file_id).unwrap_or(None) {
+            self.cache
+                .get_segment_as_string(&rec.filename)
+                .unwrap_or("")
+        } else {
+            ""
+        }
+    }
+
+    /// The base_dir of the range.
+    pub fn base_dir(&self) -> &'a str {
+        if let Some(rec) = self.cache.get_file_record(self.file_id).unwrap_or(None) {
+            self.cache
+                .get_segment_as_string(&rec.base_dir)
+                .unwrap_or("")
+        } else {
+            ""
+        }
+    }
+
+    /// The fully joined absolute path of the file.
+    pub fn full_filename(&self) -> String {
+        common_join_path(self.base_dir(), self.filename())
+    }
+}
+
+impl<'a> fmt::Debug for LineRange<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LineRange")
+            .field("addr", &self.addr())
+            .field("size", &self.size())
+            .field("line", &self.line())
+            .field("base_dir", &self.base_dir())
+            .field("filename", &self.filename())
+            .field("function", &self.function().symbol())
+            .finish()
+    }
+}
+
 impl<'a> SymCache<'a> {
     /// Load a symcache from a byteview.
     pub fn parse(byteview: ByteView<'a>) -> Result<Self, SymCacheError> {
@@ -383,12 +543,58 @@ impl<'a> SymCache<'a> {
         Ok(rv)
     }
 
+    /// Load a symcache from a byteview and verify its contents against the header checksum.
+    ///
+    /// Caches of versions 1 and 2 do not contain a checksum and are accepted without verification.
+    pub fn parse_verified(byteview: ByteView<'a>) -> Result<Self, SymCacheError> {
+        let rv = SymCache::parse(byteview)?;
+        rv.verify()?;
+        Ok(rv)
+    }
+
+    /// Verifies the contents of this symcache against the checksum in its header.
+    ///
+    /// Returns `ChecksumMismatch` if the cache has been corrupted, including its header. Caches of
+    /// versions 1 and 2 do not contain a checksum and are always considered valid.
+    pub fn verify(&self) -> Result<(), SymCacheError> {
+        if self.preamble()?.version < 3 {
+            return Ok(());
+        }
+
+        let header_size = mem::size_of::<CacheFileHeaderV3>();
+        let data = self
+            .get_data(0, header_size)
+            .context(SymCacheErrorKind::BadFileHeader)?;
+        let mut header = unsafe { *(data.as_ptr() as *const CacheFileHeaderV3) };
+        let expected = header.checksum;
+        header.checksum = 0;
+
+        // The header is hashed last, see `CacheFileHeaderV3::checksum`.
+        let mut hasher = FnvHasher::default();
+        hasher.write(&self.byteview[header_size..]);
+        hasher.write(header.as_bytes());
+        if hasher.finish() != expected {
+            return Err(SymCacheErrorKind::ChecksumMismatch.into());
+        }
+
+        Ok(())
+    }
+
     /// Constructs a symcache from an object.
     pub fn from_object(obj: &Object<'_>) -> Result<Self, SymCacheError> {
         let vec = writer::to_vec(obj)?;
         SymCache::parse(ByteView::from_vec(vec))
     }
 
+    /// Constructs a symcache by merging multiple objects of the same module.
+    ///
+    /// Objects are passed in descending order of precedence. See `to_writer_merged` for more
+    /// information.
+    pub fn from_objects(objects: &[Object<'_>]) -> Result<Self, SymCacheError> {
+        let vec = writer::to_vec_merged(objects)?;
+        SymCache::parse(ByteView::from_vec(vec))
+    }
+
     /// The total size of the cache file
     pub fn size(&self) -> usize {
         self.byteview.len()
@@ -475,6 +681,12 @@ impl<'a> SymCache<'a> {
                     .context(SymCacheErrorKind::BadFileHeader)?;
                 Ok(unsafe { &*(data.as_ptr() as *const CacheFileHeaderV2) })
             }
+            3 => {
+                let data = self
+                    .get_data(0, mem::size_of::<CacheFileHeaderV3>())
+                    .context(SymCacheErrorKind::BadFileHeader)?;
+                Ok(unsafe { &*(data.as_ptr() as *const CacheFileHeaderV3) })
+            }
             _ => Err(SymCacheErrorKind::UnsupportedVersion.into()),
         }
     }
@@ -508,6 +720,11 @@ impl<'a> SymCache<'a> {
         })
     }
 
+    /// Returns true if the cache contains an index to speed up `lookup_name`.
+    pub fn has_name_index(&self) -> Result<bool, SymCacheError> {
+        Ok(!self.name_index()?.is_empty())
+    }
+
     /// The version of the cache file.
     pub fn file_format_version(&self) -> Result<u32, SymCacheError> {
         Ok(self.preamble()?.version)
@@ -519,7 +736,7 @@ impl<'a> SymCache<'a> {
             return Ok(None);
         }
         let header = self.header()?;
-        let syms = self.get_segment(header.symbols())?;
+        let syms = self.get_segment(&header.symbols())?;
         if let Some(ref seg) = syms.get(idx as usize) {
             Ok(Some(self.get_segment_as_string(seg)?))
         } else {
@@ -527,21 +744,71 @@ impl<'a> SymCache<'a> {
         }
     }
 
-    /// Resolves a `FuncRecord` from the functions segment.
-    fn function_records(&'a self) -> Result<&'a [FuncRecord], SymCacheError> {
-        let header = self.header()?;
-        self.get_segment(header.function_records())
+    /// Resolves the `FuncRecord`s from the functions segment.
+    fn function_records(&'a self) -> Result<FuncRecords<'a>, SymCacheError> {
+        let seg = self.header()?.function_records();
+        Ok(match self.preamble()?.version {
+            1 | 2 => FuncRecords::V1(self.get_segment(&seg.cast())?),
+            _ => FuncRecords::V3(self.get_segment(&seg.cast())?),
+        })
+    }
+
+    /// Resolves the name index, which is empty if the cache does not contain one.
+    fn name_index(&self) -> Result<&[NameRecordV3], SymCacheError> {
+        self.get_segment(&self.header()?.name_index())
+    }
+
+    /// Resolves the data source of a function.
+    fn function_source(&self, id: u32) -> Result<DataSource, SymCacheError> {
+        let sources: &[u8] = self.get_segment(&self.header()?.function_sources())?;
+        match sources.get(id as usize) {
+            Some(&source) => {
+                Ok(DataSource::from_u32(source.into()).context(SymCacheErrorKind::BadCacheFile)?)
+            }
+            None => self.data_source(),
+        }
+    }
+
+    /// Returns the function with the given ID.
+    fn get_function(&'a self, id: u32) -> Result<Function<'a>, SymCacheError> {
+        let fun = self
+            .function_records()?
+            .get(id as usize)
+            .ok_or(SymCacheErrorKind::BadCacheFile)?;
+
+        Ok(Function {
+            cache: self,
+            id,
+            fun,
+        })
+    }
+
+    /// Resolves the `LineRecord`s of a function.
+    fn line_records(&self, fun: &FuncRecord) -> Result<LineRecords<'_>, SymCacheError> {
+        let seg = fun.line_records;
+        Ok(match self.preamble()?.version {
+            1 | 2 => LineRecords::V1(self.get_segment(&seg.cast())?),
+            _ => LineRecords::V3(self.get_segment(&seg.cast())?),
+        })
     }
 
     /// Resolves a `FileRecord` from the files segment.
-    fn get_file_record(&self, idx: u16) -> Result<Option<&FileRecord>, SymCacheError> {
+    fn get_file_record(&self, idx: u32) -> Result<Option<FileRecord>, SymCacheError> {
         // no match
         if idx == !0 {
             return Ok(None);
         }
-        let header = self.header()?;
-        let files = self.get_segment(header.files())?;
-        Ok(files.get(idx as usize))
+        let seg = self.header()?.files();
+        Ok(match self.preamble()?.version {
+            1 | 2 => {
+                let files: &[FileRecordV1] = self.get_segment(&seg.cast())?;
+                files.get(idx as usize).map(|r| FileRecord::from(*r))
+            }
+            _ => {
+                let files: &[FileRecordV3] = self.get_segment(&seg.cast())?;
+                files.get(idx as usize).map(|r| FileRecord::from(*r))
+            }
+        })
     }
 
     /// Locates the source line for an instruction address within a function.
@@ -559,10 +826,10 @@ impl<'a> SymCache<'a> {
     /// Returns `None` if the function does not have line records.
     fn run_to_line(
         &'a self,
-        fun: &'a FuncRecord,
+        fun: &FuncRecord,
         addr: u64,
-    ) -> Result<Option<(&FileRecord, u64, u32)>, SymCacheError> {
-        let records = self.get_segment(&fun.line_records)?;
+    ) -> Result<Option<(FileRecord, u64, u32)>, SymCacheError> {
+        let records = self.line_records(fun)?;
         if records.is_empty() {
             // A non-empty function without line records can happen in a couple
             // of cases:
@@ -584,12 +851,13 @@ impl<'a> SymCache<'a> {
         // inlines the first address might actually already be missing
         // the record.  Because of that we pick in any case the first
         // record as fallback.
-        let mut file_id = records[0].file_id;
-        let mut line = u32::from(records[0].line);
+        let first = records.get(0).unwrap_or_default();
+        let mut file_id = first.file_id;
+        let mut line = first.line;
         let mut running_addr = fun.addr_start() as u64;
         let mut line_addr = running_addr;
 
-        for rec in records {
+        for rec in records.iter() {
             // Keep running until we exceed the search address
             running_addr += u64::from(rec.addr_off);
             if running_addr > addr {
@@ -599,15 +867,15 @@ impl<'a> SymCache<'a> {
             // Remember the starting address of the current line. There might be
             // multiple line records for a single line if `addr_off` overflows.
             // So only update `line_addr` if we actually hit a new line.
-            if u32::from(rec.line) != line {
+            if rec.line != line {
                 line_addr = running_addr;
             }
 
-            line = u32::from(rec.line);
+            line = rec.line;
             file_id = rec.file_id;
         }
 
-        if let Some(ref record) = self.get_file_record(file_id)? {
+        if let Some(record) = self.get_file_record(file_id)? {
             Ok(Some((record, line_addr, line)))
         } else {
             // This should not happen and indicates an invalid symcache
@@ -631,7 +899,7 @@ impl<'a> SymCache<'a> {
     /// line information will be empty (0 or "").
     fn build_line_info(
         &'a self,
-        fun: &'a FuncRecord,
+        fun: &FuncRecord,
         addr: u64,
         inner_sym: Option<&LineInfo<'a>>,
     ) -> Result<LineInfo<'a>, SymCacheError> {
@@ -676,7 +944,7 @@ impl<'a> SymCache<'a> {
             instr_addr: addr,
             line,
             lang: Language::from_u32(fun.lang.into()).unwrap_or(Language::Unknown),
-            symbol: self.get_symbol(fun.symbol_id())?,
+            symbol: self.get_symbol(fun.symbol_id)?,
             filename,
             base_dir,
             comp_dir: self.get_segment_as_string(&fun.comp_dir)?,
@@ -702,22 +970,26 @@ impl<'a> SymCache<'a> {
         // Functions in the function segment are ordered by start address
         // primarily and by depth secondarily.  As a result we want to have
         // a secondary comparison by the item index.
-        let mut func_id = match funcs.binary_search_by_key(&addr, |x| x.addr_start()) {
+        let mut func_id = match funcs.binary_search_by_addr(addr) {
             Ok(idx) => idx,
             Err(0) => return Ok(vec![]),
             Err(next_idx) => next_idx - 1,
         };
 
+        let mut fun = match funcs.get(func_id) {
+            Some(fun) => fun,
+            None => return Ok(vec![]),
+        };
+
         // Seek forward to the deepest inlined function at the same address.
-        while let Some(fun) = funcs.get(func_id + 1) {
-            if fun.addr_start() != funcs[func_id].addr_start() {
+        while let Some(next) = funcs.get(func_id + 1) {
+            if next.addr_start() != fun.addr_start() {
                 break;
             }
+            fun = next;
             func_id += 1;
         }
 
-        let mut fun = &funcs[func_id];
-
         // The binary search matches the closest function that starts before our
         // search address. However, that function might end before that already,
         // for two reasons:
@@ -728,7 +1000,9 @@ impl<'a> SymCache<'a> {
         while !fun.addr_in_range(addr) {
             if let Some(parent_id) = fun.parent(func_id) {
                 // Parent might contain the instruction (case 1)
-                fun = &funcs[parent_id];
+                fun = funcs
+                    .get(parent_id)
+                    .ok_or(SymCacheErrorKind::BadCacheFile)?;
                 func_id = parent_id;
             } else {
                 // We missed entirely (case 2)
@@ -744,7 +1018,9 @@ impl<'a> SymCache<'a> {
         // Line infos for all inlining ancestors, if any
         while let Some(parent_id) = fun.parent(func_id) {
             let outer_addr = fun.addr_start();
-            fun = &funcs[parent_id];
+            fun = funcs
+                .get(parent_id)
+                .ok_or(SymCacheErrorKind::BadCacheFile)?;
             func_id = parent_id;
             let symbol = { self.build_line_info(&fun, outer_addr, Some(&rv[rv.len() - 1]))? };
             rv.push(symbol);
@@ -752,6 +1028,176 @@ impl<'a> SymCache<'a> {
 
         Ok(rv)
     }
+
+    /// Looks up all address ranges that map to the given source line.
+    ///
+    /// The `path` is matched as suffix against the full path of every file, consisting of its
+    /// `base_dir` and `filename`. Paths are compared by components, so `"src/main.c"` matches
+    /// `"/work/src/main.c"`, but not `"/work/mysrc/main.c"`.
+    ///
+    /// Since call sites are recorded in the parent of an inlined function, this returns the
+    /// instructions of both the inlined function's own lines and its call sites. Ranges are
+    /// sorted by address.
+    pub fn lookup_line(
+        &'a self,
+        path: &str,
+        line: u32,
+    ) -> Result<Vec<LineRange<'a>>, SymCacheError> {
+        // There are far fewer files than line records, so resolve all matching files first.
+        let mut file_ids = FnvHashSet::default();
+        let mut file_id = 0;
+        while let Some(record) = self.get_file_record(file_id)? {
+            let filename = self.get_segment_as_string(&record.filename)?;
+            let base_dir = self.get_segment_as_string(&record.base_dir)?;
+            if path_ends_with(&common_join_path(base_dir, filename), path) {
+                file_ids.insert(file_id);
+            }
+            file_id += 1;
+        }
+
+        let mut rv: Vec<LineRange<'a>> = vec![];
+        if file_ids.is_empty() {
+            return Ok(rv);
+        }
+
+        let funcs = self.function_records()?;
+        for func_id in 0..funcs.len() {
+            let fun = funcs.get(func_id).ok_or(SymCacheErrorKind::BadCacheFile)?;
+            let records = self.line_records(&fun)?;
+
+            let mut addr = fun.addr_start();
+            let mut lines = Vec::new();
+            for record in records.iter() {
+                addr += u64::from(record.addr_off);
+                lines.push((addr, record));
+            }
+
+            // Each line record spans until the next record, or the end of the function.
+            for (index, &(start, record)) in lines.iter().enumerate() {
+                if record.line != line || !file_ids.contains(&record.file_id) {
+                    continue;
+                }
+
+                let end = lines
+                    .get(index + 1)
+                    .map_or(fun.addr_end(), |&(addr, _)| addr);
+                if end <= start {
+                    continue;
+                }
+
+                // Merge with the previous range if it ends where this one starts. This happens
+                // if the address offset of a line overflows or a line is split into statements.
+                match rv.last_mut() {
+                    Some(last)
+                        if last.func_id == func_id as u32
+                            && last.file_id == record.file_id
+                            && last.addr + last.len == start =>
+                    {
+                        last.len = end - last.addr;
+                    }
+                    _ => rv.push(LineRange {
+                        cache: self,
+                        func_id: func_id as u32,
+                        fun,
+                        addr: start,
+                        len: end - start,
+                        line,
+                        file_id: record.file_id,
+                    }),
+                }
+            }
+        }
+
+        rv.sort_by_key(|range| (range.addr, range.func_id));
+        Ok(rv)
+    }
+
+    /// Looks up all functions with the given name.
+    ///
+    /// The name is compared to the demangled `Function::function_name`. This includes all inlined
+    /// instances of the function, which can be told apart by their `parent_id`. The functions are
+    /// returned in the order of their IDs.
+    ///
+    /// Caches written by older versions do not contain a name index. In this case, all functions
+    /// are searched sequentially.
+    pub fn lookup_name(&'a self, name: &str) -> Result<Vec<Function<'a>>, SymCacheError> {
+        let index = self.name_index()?;
+        if index.is_empty() {
+            let mut rv = vec![];
+            for function in self.functions() {
+                let function = function?;
+                if function.fun.symbol_id != !0 && function.function_name() == name {
+                    rv.push(function);
+                }
+            }
+            return Ok(rv);
+        }
+
+        // Find the first entry in the index that is not less than the name. The index is sorted
+        // by name primarily and by function ID secondarily.
+        let mut low = 0;
+        let mut high = index.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self
+                .get_function(index[mid].func_id)?
+                .function_name()
+                .as_str()
+                < name
+            {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        let mut rv = vec![];
+        for record in &index[low..] {
+            let function = self.get_function(record.func_id)?;
+            if function.function_name() != name {
+                break;
+            }
+            rv.push(function);
+        }
+
+        Ok(rv)
+    }
+
+    /// Looks up all functions overlapping the address range `[start, end)`.
+    ///
+    /// This includes inlined functions, which are returned after their parents. Functions are
+    /// returned in the order of their start addresses.
+    pub fn lookup_range(
+        &'a self,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<Function<'a>>, SymCacheError> {
+        let mut rv = vec![];
+        if start >= end {
+            return Ok(rv);
+        }
+
+        // Function records are only sorted by their start address, and any preceding function
+        // may extend past the functions that follow it. Therefore, every function starting before
+        // the end of the range is a candidate.
+        let funcs = self.function_records()?;
+        for func_id in 0..funcs.len() {
+            let fun = funcs.get(func_id).ok_or(SymCacheErrorKind::BadCacheFile)?;
+            if fun.addr_start() >= end {
+                break;
+            }
+
+            if fun.addr_end() > start {
+                rv.push(Function {
+                    cache: self,
+                    id: func_id as u32,
+                    fun,
+                });
+            }
+        }
+
+        Ok(rv)
+    }
 }
 
 impl<'a> fmt::Debug for SymCache<'a> {
@@ -1,17 +1,16 @@
 use std::borrow::Cow;
-use std::cell::RefCell;
 use std::cmp;
+use std::collections::HashMap;
 use std::fmt;
 use std::mem;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use symbolic_common::types::{Endianness, Language};
 use symbolic_debuginfo::{DwarfData, DwarfSection, Object, Symbols};
 
 use failure::Fail;
-use fallible_iterator::FallibleIterator;
 use fnv::FnvBuildHasher;
-use gimli::{Abbreviations, AttributeValue, CompilationUnitHeader, DebugLineOffset, DwLang, Range};
+use gimli::{AttributeValue, DwLang, DwoId, Range, SectionId, UnitHeader};
 use if_chain::if_chain;
 use lru_cache::LruCache;
 use owning_ref::OwningHandle;
@@ -20,6 +19,8 @@ use crate::error::{ConversionError, SymCacheError, SymCacheErrorKind};
 
 type Buf<'a> = gimli::EndianSlice<'a, Endianness>;
 type Die<'abbrev, 'unit, 'a> = gimli::DebuggingInformationEntry<'abbrev, 'unit, Buf<'a>>;
+type Dwarf<'a> = gimli::Dwarf<Buf<'a>>;
+type GimliUnit<'a> = gimli::Unit<Buf<'a>>;
 
 fn load_section<'a>(
     obj: &'a Object<'_>,
@@ -46,9 +47,16 @@ struct DwarfBuffers<'a> {
     debug_info: Cow<'a, [u8]>,
     debug_abbrev: Cow<'a, [u8]>,
     debug_line: Cow<'a, [u8]>,
+    debug_line_str: Cow<'a, [u8]>,
     debug_str: Cow<'a, [u8]>,
+    debug_str_offsets: Cow<'a, [u8]>,
+    debug_addr: Cow<'a, [u8]>,
     debug_ranges: Cow<'a, [u8]>,
     debug_rnglists: Cow<'a, [u8]>,
+    debug_loc: Cow<'a, [u8]>,
+    debug_loclists: Cow<'a, [u8]>,
+    debug_types: Cow<'a, [u8]>,
+    debug_cu_index: Cow<'a, [u8]>,
 }
 
 impl<'a> DwarfBuffers<'a> {
@@ -57,48 +65,194 @@ impl<'a> DwarfBuffers<'a> {
             debug_info: load_section(obj, DwarfSection::DebugInfo, true)?,
             debug_abbrev: load_section(obj, DwarfSection::DebugAbbrev, true)?,
             debug_line: load_section(obj, DwarfSection::DebugLine, true)?,
+            debug_line_str: load_section(obj, DwarfSection::DebugLineStr, false)?,
             debug_str: load_section(obj, DwarfSection::DebugStr, false)?,
+            debug_str_offsets: load_section(obj, DwarfSection::DebugStrOffsets, false)?,
+            debug_addr: load_section(obj, DwarfSection::DebugAddr, false)?,
             debug_ranges: load_section(obj, DwarfSection::DebugRanges, false)?,
             debug_rnglists: load_section(obj, DwarfSection::DebugRngLists, false)?,
+            debug_loc: load_section(obj, DwarfSection::DebugLoc, false)?,
+            debug_loclists: load_section(obj, DwarfSection::DebugLocLists, false)?,
+            debug_types: Default::default(),
+            debug_cu_index: Default::default(),
         })
     }
+
+    /// Loads the sections of a split DWARF object (`.dwo`) or package (`.dwp`).
+    ///
+    /// Split objects do not carry line programs with addresses, `.debug_addr` or `.debug_ranges`.
+    /// These are always taken from the main object containing the skeleton units. Location lists
+    /// and type units are only loaded since packages index their contributions.
+    pub fn from_split_object(obj: &'a Object<'_>) -> Result<Self, SymCacheError> {
+        Ok(DwarfBuffers {
+            debug_info: load_section(obj, DwarfSection::DebugInfo, true)?,
+            debug_abbrev: load_section(obj, DwarfSection::DebugAbbrev, true)?,
+            debug_line: load_section(obj, DwarfSection::DebugLine, false)?,
+            debug_line_str: load_section(obj, DwarfSection::DebugLineStr, false)?,
+            debug_str: load_section(obj, DwarfSection::DebugStr, false)?,
+            debug_str_offsets: load_section(obj, DwarfSection::DebugStrOffsets, false)?,
+            debug_addr: Default::default(),
+            debug_ranges: Default::default(),
+            debug_rnglists: load_section(obj, DwarfSection::DebugRngLists, false)?,
+            debug_loc: load_section(obj, DwarfSection::DebugLoc, false)?,
+            debug_loclists: load_section(obj, DwarfSection::DebugLocLists, false)?,
+            debug_types: load_section(obj, DwarfSection::DebugTypes, false)?,
+            debug_cu_index: load_section(obj, DwarfSection::DebugCuIndex, false)?,
+        })
+    }
+
+    /// Loads the sections of a supplementary object file created by `dwz`.
+    ///
+    /// Supplementary files contain partial units and strings that are shared between multiple
+    /// debug files. They do not contain any code addresses.
+    pub fn from_supplementary_object(obj: &'a Object<'_>) -> Result<Self, SymCacheError> {
+        Ok(DwarfBuffers {
+            debug_info: load_section(obj, DwarfSection::DebugInfo, true)?,
+            debug_abbrev: load_section(obj, DwarfSection::DebugAbbrev, true)?,
+            debug_line: load_section(obj, DwarfSection::DebugLine, false)?,
+            debug_line_str: load_section(obj, DwarfSection::DebugLineStr, false)?,
+            debug_str: load_section(obj, DwarfSection::DebugStr, false)?,
+            debug_str_offsets: load_section(obj, DwarfSection::DebugStrOffsets, false)?,
+            debug_addr: Default::default(),
+            debug_ranges: Default::default(),
+            debug_rnglists: Default::default(),
+            debug_loc: Default::default(),
+            debug_loclists: Default::default(),
+            debug_types: Default::default(),
+            debug_cu_index: Default::default(),
+        })
+    }
+
+    fn section(&self, id: SectionId) -> &[u8] {
+        match id {
+            SectionId::DebugInfo => &self.debug_info,
+            SectionId::DebugAbbrev => &self.debug_abbrev,
+            SectionId::DebugLine => &self.debug_line,
+            SectionId::DebugLineStr => &self.debug_line_str,
+            SectionId::DebugStr => &self.debug_str,
+            SectionId::DebugStrOffsets => &self.debug_str_offsets,
+            SectionId::DebugAddr => &self.debug_addr,
+            SectionId::DebugRanges => &self.debug_ranges,
+            SectionId::DebugRngLists => &self.debug_rnglists,
+            SectionId::DebugLoc => &self.debug_loc,
+            SectionId::DebugLocLists => &self.debug_loclists,
+            SectionId::DebugTypes => &self.debug_types,
+            SectionId::DebugCuIndex => &self.debug_cu_index,
+            _ => &[],
+        }
+    }
+
+    fn is_package(&self) -> bool {
+        !self.debug_cu_index.is_empty()
+    }
+
+    fn load_dwarf(&'a self, endianness: Endianness) -> Result<Dwarf<'a>, gimli::Error> {
+        gimli::Dwarf::load(|id| Ok(Buf::new(self.section(id), endianness)))
+    }
+
+    fn load_package(
+        &'a self,
+        endianness: Endianness,
+    ) -> Result<gimli::DwarfPackage<Buf<'a>>, gimli::Error> {
+        gimli::DwarfPackage::load(
+            |id| Ok(Buf::new(self.section(id), endianness)),
+            Buf::new(&[], endianness),
+        )
+    }
+}
+
+/// Buffers of an object file along with its split DWARF objects or packages and its
+/// supplementary object file.
+#[derive(Debug)]
+struct DwarfFiles<'a> {
+    main: DwarfBuffers<'a>,
+    split: Vec<DwarfBuffers<'a>>,
+    sup: Option<DwarfBuffers<'a>>,
 }
 
 #[derive(Debug)]
 struct DwarfSections<'a> {
-    units: Vec<CompilationUnitHeader<Buf<'a>>>,
-    debug_abbrev: gimli::DebugAbbrev<Buf<'a>>,
-    debug_line: gimli::DebugLine<Buf<'a>>,
-    debug_str: gimli::DebugStr<Buf<'a>>,
-    range_lists: gimli::RangeLists<Buf<'a>>,
+    dwarf: Dwarf<'a>,
+    units: Vec<UnitHeader<Buf<'a>>>,
+    sup_units: Vec<UnitHeader<Buf<'a>>>,
+    split: Vec<Arc<Dwarf<'a>>>,
+    split_units: HashMap<DwoId, (usize, UnitHeader<Buf<'a>>)>,
+    packages: Vec<gimli::DwarfPackage<Buf<'a>>>,
 }
 
 impl<'a> DwarfSections<'a> {
-    pub fn from_buffers(
-        buffers: &'a DwarfBuffers<'a>,
+    pub fn from_files(
+        files: &'a DwarfFiles<'a>,
         endianness: Endianness,
     ) -> Result<Self, SymCacheError> {
+        let mut dwarf = files.main.load_dwarf(endianness)?;
+
+        let mut units = Vec::new();
+        let mut headers = dwarf.units();
+        while let Some(header) = headers.next()? {
+            units.push(header);
+        }
+
+        // Alternate references (`DW_FORM_GNU_ref_alt` and `DW_FORM_GNU_strp_alt`) point into the
+        // supplementary object, which gimli resolves through `Dwarf::sup`.
+        let mut sup_units = Vec::new();
+        if let Some(ref sup) = files.sup {
+            dwarf.load_sup(|id| -> Result<_, gimli::Error> {
+                Ok(Buf::new(sup.section(id), endianness))
+            })?;
+
+            if let Some(sup_dwarf) = dwarf.sup() {
+                let mut headers = sup_dwarf.units();
+                while let Some(header) = headers.next()? {
+                    sup_units.push(header);
+                }
+            }
+        }
+
+        // Index the units of all split objects by their DWO id, so that skeleton units can be
+        // matched up quickly. The sections of each split object are loaded once and shared by
+        // all of its units. Packages carry their own index in `.debug_cu_index`.
+        let mut split = Vec::new();
+        let mut split_units = HashMap::new();
+        let mut packages = Vec::new();
+        for buffers in &files.split {
+            if buffers.is_package() {
+                packages.push(buffers.load_package(endianness)?);
+                continue;
+            }
+
+            let mut split_dwarf = buffers.load_dwarf(endianness)?;
+            split_dwarf.make_dwo(&dwarf);
+
+            let mut headers = split_dwarf.units();
+            while let Some(header) = headers.next()? {
+                let unit = gimli::Unit::new(&split_dwarf, header)?;
+                if let Some(dwo_id) = unit.dwo_id {
+                    split_units.insert(dwo_id, (split.len(), header));
+                }
+            }
+
+            split.push(Arc::new(split_dwarf));
+        }
+
         Ok(DwarfSections {
-            units: gimli::DebugInfo::new(&buffers.debug_info, endianness)
-                .units()
-                .collect()?,
-            debug_abbrev: gimli::DebugAbbrev::new(&buffers.debug_abbrev, endianness),
-            debug_line: gimli::DebugLine::new(&buffers.debug_line, endianness),
-            debug_str: gimli::DebugStr::new(&buffers.debug_str, endianness),
-            range_lists: gimli::RangeLists::new(
-                gimli::DebugRanges::new(&buffers.debug_ranges, endianness),
-                gimli::DebugRngLists::new(&buffers.debug_rnglists, endianness),
-            )?,
+            dwarf,
+            units,
+            sup_units,
+            split,
+            split_units,
+            packages,
         })
     }
 }
 
-type DwarfHandle<'a> = OwningHandle<Box<DwarfBuffers<'a>>, Box<DwarfSections<'a>>>;
-type AbbrevCache = LruCache<gimli::DebugAbbrevOffset<usize>, Arc<Abbreviations>, FnvBuildHasher>;
+type DwarfHandle<'a> = OwningHandle<Box<DwarfFiles<'a>>, Box<DwarfSections<'a>>>;
+type UnitCache<'a> = LruCache<usize, Arc<GimliUnit<'a>>, FnvBuildHasher>;
 
 pub struct DwarfInfo<'a> {
     sections: DwarfHandle<'a>,
-    abbrev_cache: RefCell<AbbrevCache>,
+    unit_cache: Mutex<UnitCache<'a>>,
+    sup_unit_cache: Mutex<UnitCache<'a>>,
     vmaddr: u64,
 }
 
@@ -109,44 +263,62 @@ impl<'a> std::fmt::Debug for DwarfInfo<'a> {
 }
 
 impl<'a> DwarfInfo<'a> {
-    pub fn from_object(obj: &'a Object<'_>) -> Result<Self, SymCacheError> {
-        let buffers = DwarfBuffers::from_object(obj)?;
-        let handle = OwningHandle::try_new(Box::new(buffers), |buffers| {
-            DwarfSections::from_buffers(unsafe { &*buffers }, obj.endianness()).map(Box::new)
+    /// Loads DWARF information from an object file.
+    ///
+    /// If the object was compiled with split DWARF, the `.dwo` objects or `.dwp` package holding
+    /// the debugging information entries of its skeleton units should be passed in `split`.
+    ///
+    /// If the object was processed with `dwz`, the supplementary object referenced by its
+    /// `.gnu_debugaltlink` section should be passed in `sup`.
+    pub fn from_object(
+        obj: &'a Object<'_>,
+        split: &'a [Object<'_>],
+        sup: Option<&'a Object<'_>>,
+    ) -> Result<Self, SymCacheError> {
+        let files = DwarfFiles {
+            main: DwarfBuffers::from_object(obj)?,
+            split: split
+                .iter()
+                .map(DwarfBuffers::from_split_object)
+                .collect::<Result<_, _>>()?,
+            sup: match sup {
+                Some(sup) => Some(DwarfBuffers::from_supplementary_object(sup)?),
+                None => None,
+            },
+        };
+
+        let handle = OwningHandle::try_new(Box::new(files), |files| {
+            DwarfSections::from_files(unsafe { &*files }, obj.endianness()).map(Box::new)
         })?;
 
         Ok(DwarfInfo {
             sections: handle,
-            abbrev_cache: RefCell::new(LruCache::with_hasher(30, Default::default())),
+            unit_cache: Mutex::new(LruCache::with_hasher(30, Default::default())),
+            sup_unit_cache: Mutex::new(LruCache::with_hasher(30, Default::default())),
             vmaddr: obj.vmaddr(),
         })
     }
 
     #[inline(always)]
-    pub fn get_unit_header(
-        &self,
-        index: usize,
-    ) -> Result<&CompilationUnitHeader<Buf<'a>>, SymCacheError> {
+    pub fn get_unit_header(&self, index: usize) -> Result<&UnitHeader<Buf<'a>>, SymCacheError> {
         self.sections
             .units
             .get(index)
             .ok_or_else(|| ConversionError::new("compilation unit does not exist").into())
     }
 
-    pub fn get_abbrev(
-        &self,
-        header: &CompilationUnitHeader<Buf<'a>>,
-    ) -> Result<Arc<Abbreviations>, SymCacheError> {
-        let offset = header.debug_abbrev_offset();
-        let mut cache = self.abbrev_cache.borrow_mut();
-        if let Some(abbrev) = cache.get_mut(&offset) {
-            return Ok(abbrev.clone());
+    /// Returns a parsed compilation unit, which is cached for subsequent lookups.
+    pub fn get_unit(&self, index: usize) -> Result<Arc<GimliUnit<'a>>, SymCacheError> {
+        if let Some(unit) = self.unit_cache.lock().unwrap().get_mut(&index) {
+            return Ok(unit.clone());
         }
 
-        let abbrev = header.abbreviations(&self.sections.debug_abbrev)?;
+        // The lock is released while parsing, so that units can be resolved concurrently.
+        let header = *self.get_unit_header(index)?;
+        let unit = Arc::new(gimli::Unit::new(&self.sections.dwarf, header)?);
 
-        cache.insert(offset, Arc::new(abbrev));
-        Ok(cache.get_mut(&offset).unwrap().clone())
+        self.unit_cache.lock().unwrap().insert(index, unit.clone());
+        Ok(unit)
     }
 
     pub fn unit_count(&self) -> usize {
@@ -161,27 +333,102 @@ impl<'a> DwarfInfo<'a> {
         &self,
         offset: gimli::DebugInfoOffset<usize>,
     ) -> Result<(usize, gimli::UnitOffset<usize>), SymCacheError> {
-        let idx = match self
+        find_unit_offset(&self.sections.units, offset)
+    }
+
+    /// Returns a parsed unit of the supplementary object, which is cached for subsequent lookups.
+    fn get_sup_unit(&self, index: usize) -> Result<Arc<GimliUnit<'a>>, SymCacheError> {
+        if let Some(unit) = self.sup_unit_cache.lock().unwrap().get_mut(&index) {
+            return Ok(unit.clone());
+        }
+
+        let sup = match self.sections.dwarf.sup() {
+            Some(sup) => sup,
+            None => return Err(ConversionError::new("missing supplementary object").into()),
+        };
+
+        let header = *self
             .sections
-            .units
-            .binary_search_by_key(&offset.0, |x| x.offset().0)
-        {
-            Ok(idx) => idx,
-            Err(0) => {
-                return Err(
-                    ConversionError::new("could not find compilation unit at address").into(),
-                )
+            .sup_units
+            .get(index)
+            .ok_or_else(|| ConversionError::new("compilation unit does not exist"))?;
+        let unit = Arc::new(gimli::Unit::new(sup, header)?);
+
+        self.sup_unit_cache
+            .lock()
+            .unwrap()
+            .insert(index, unit.clone());
+        Ok(unit)
+    }
+
+    fn find_sup_unit_offset(
+        &self,
+        offset: gimli::DebugInfoOffset<usize>,
+    ) -> Result<(usize, gimli::UnitOffset<usize>), SymCacheError> {
+        find_unit_offset(&self.sections.sup_units, offset)
+    }
+
+    /// Resolves the split compilation unit referenced by a skeleton unit.
+    ///
+    /// Returns `None` if the unit is not a skeleton or if none of the split objects and packages
+    /// contain a unit with matching DWO id.
+    fn split_unit(
+        &self,
+        skeleton: &GimliUnit<'a>,
+    ) -> Result<Option<(Arc<Dwarf<'a>>, GimliUnit<'a>)>, SymCacheError> {
+        let dwo_id = match skeleton.dwo_id {
+            Some(dwo_id) => dwo_id,
+            None => return Ok(None),
+        };
+
+        let sections = &*self.sections;
+        let (dwarf, header) = if let Some(&(index, header)) = sections.split_units.get(&dwo_id) {
+            (sections.split[index].clone(), header)
+        } else {
+            // Packages restrict the sections to the contributions of a single unit, so the
+            // sections are resolved from the package index for every unit.
+            let mut found = None;
+            for package in &sections.packages {
+                if let Some(dwarf) = package.find_cu(dwo_id, &sections.dwarf)? {
+                    if let Some(header) = dwarf.units().next()? {
+                        found = Some((Arc::new(dwarf), header));
+                        break;
+                    }
+                }
+            }
+
+            match found {
+                Some(found) => found,
+                None => return Ok(None),
             }
-            Err(next_idx) => next_idx - 1,
         };
 
-        let header = &self.sections.units[idx];
-        if let Some(unit_offset) = offset.to_unit_offset(header) {
-            return Ok((idx, unit_offset));
+        let mut unit = gimli::Unit::new(&dwarf, header)?;
+        unit.copy_relocated_attributes(skeleton);
+        Ok(Some((dwarf, unit)))
+    }
+}
+
+/// Locates the unit containing the given offset in a sorted list of unit headers.
+fn find_unit_offset<'a>(
+    units: &[UnitHeader<Buf<'a>>],
+    offset: gimli::DebugInfoOffset<usize>,
+) -> Result<(usize, gimli::UnitOffset<usize>), SymCacheError> {
+    let idx = match units.binary_search_by_key(&Some(offset), |x| x.offset().as_debug_info_offset())
+    {
+        Ok(idx) => idx,
+        Err(0) => {
+            return Err(ConversionError::new("could not find compilation unit at address").into())
         }
+        Err(next_idx) => next_idx - 1,
+    };
 
-        Err(ConversionError::new("compilation unit out of range").into())
+    let header = &units[idx];
+    if let Some(unit_offset) = offset.to_unit_offset(header) {
+        return Ok((idx, unit_offset));
     }
+
+    Err(ConversionError::new("compilation unit out of range").into())
 }
 
 pub struct Line<'a> {
@@ -189,7 +436,7 @@ pub struct Line<'a> {
     pub original_file_id: u64,
     pub filename: &'a [u8],
     pub base_dir: &'a [u8],
-    pub line: u16,
+    pub line: u32,
 }
 
 impl<'a> fmt::Debug for Line<'a> {
@@ -254,71 +501,99 @@ impl<'a> fmt::Debug for Function<'a> {
 
 #[derive(Debug)]
 pub struct Unit<'a> {
-    index: usize,
-    version: u16,
-    base_address: u64,
+    unit: GimliUnit<'a>,
+    split: Option<(Arc<Dwarf<'a>>, GimliUnit<'a>)>,
+    line_program: gimli::IncompleteLineProgram<Buf<'a>>,
     comp_dir: Option<Buf<'a>>,
-    comp_name: Option<Buf<'a>>,
     language: Option<DwLang>,
-    line_offset: DebugLineOffset,
 }
 
 impl<'a> Unit<'a> {
     pub fn parse(info: &DwarfInfo<'a>, index: usize) -> Result<Option<Unit<'a>>, SymCacheError> {
-        let header = info.get_unit_header(index)?;
-        let version = header.version();
+        let header = *info.get_unit_header(index)?;
+        let mut unit = gimli::Unit::new(&info.sections.dwarf, header)?;
 
-        // Access the compilation unit, which must be the top level DIE
-        let abbrev = info.get_abbrev(header)?;
-        let mut entries = header.entries(&*abbrev);
+        // Access the compilation unit, which must be the top level DIE. Skeleton units only
+        // carry addresses and line information, the actual DIEs live in a split unit.
+        let mut entries = unit.entries();
         let entry = match entries.next_dfs()? {
             Some((_, entry)) => entry,
             None => return Ok(None),
         };
 
-        if entry.tag() != gimli::DW_TAG_compile_unit {
-            return Err(ConversionError::new("missing compilation unit").into());
+        match entry.tag() {
+            gimli::DW_TAG_compile_unit | gimli::DW_TAG_skeleton_unit => (),
+            // Partial units created by dwz only hold entries imported by other units.
+            gimli::DW_TAG_partial_unit => return Ok(None),
+            _ => return Err(ConversionError::new("missing compilation unit").into()),
         }
 
-        let base_address = match entry.attr_value(gimli::DW_AT_low_pc)? {
-            Some(AttributeValue::Addr(addr)) => addr,
-            _ => match entry.attr_value(gimli::DW_AT_entry_pc)? {
-                Some(AttributeValue::Addr(addr)) => addr,
-                _ => 0,
-            },
-        };
-
-        let comp_dir = entry
-            .attr(gimli::DW_AT_comp_dir)?
-            .and_then(|attr| attr.string_value(&info.sections.debug_str));
-
-        let comp_name = entry
-            .attr(gimli::DW_AT_name)?
-            .and_then(|attr| attr.string_value(&info.sections.debug_str));
-
-        let language = entry
+        let mut language = entry
             .attr(gimli::DW_AT_language)?
             .and_then(|attr| match attr.value() {
                 AttributeValue::Language(lang) => Some(lang),
                 _ => None,
             });
 
-        let line_offset = match entry.attr_value(gimli::DW_AT_stmt_list)? {
-            Some(AttributeValue::DebugLineRef(offset)) => offset,
-            _ => return Ok(None),
+        let line_program = match unit.line_program.take() {
+            Some(line_program) => line_program,
+            None => return Ok(None),
         };
 
+        let split = info.split_unit(&unit)?;
+        let mut comp_dir = unit.comp_dir;
+
+        if let Some((_, ref split_unit)) = split {
+            let mut entries = split_unit.entries();
+            if let Some((_, entry)) = entries.next_dfs()? {
+                if let Some(AttributeValue::Language(lang)) =
+                    entry.attr_value(gimli::DW_AT_language)?
+                {
+                    language = Some(lang);
+                }
+            }
+
+            comp_dir = comp_dir.or(split_unit.comp_dir);
+        }
+
         Ok(Some(Unit {
-            index,
-            version,
-            base_address,
+            unit,
+            split,
+            line_program,
             comp_dir,
-            comp_name,
             language,
-            line_offset,
         }))
     }
 
+    /// Returns the unit containing the debugging information entries, along with its sections.
+    ///
+    /// For skeleton units, this is the split unit loaded from a `.dwo` object or `.dwp` package.
+    fn entries_unit<'s>(&'s self, info: &'s DwarfInfo<'a>) -> (&'s Dwarf<'a>, &'s GimliUnit<'a>) {
+        match self.split {
+            Some((ref dwarf, ref unit)) => (dwarf, unit),
+            None => (&info.sections.dwarf, &self.unit),
+        }
+    }
+
+    /// Resolves the file referenced by `DW_AT_call_file` in the given unit.
+    ///
+    /// Split units may come with their own file table, which takes precedence over the line
+    /// program of the skeleton unit.
+    fn get_call_file(
+        &self,
+        info: &DwarfInfo<'a>,
+        line_program: &DwarfLineProgram<'a>,
+        file: u64,
+    ) -> Result<(&'a [u8], &'a [u8]), SymCacheError> {
+        if let Some((ref dwarf, ref unit)) = self.split {
+            if let Some(ref program) = unit.line_program {
+                return get_filename(dwarf, unit, program.header(), file);
+            }
+        }
+
+        line_program.get_filename(info, &self.unit, file)
+    }
+
     pub fn get_functions(
         &self,
         info: &DwarfInfo<'a>,
@@ -329,17 +604,10 @@ impl<'a> Unit<'a> {
         let mut depth = 0;
         let mut skipped_depth = None;
 
-        let header = info.get_unit_header(self.index)?;
-        let abbrev = info.get_abbrev(header)?;
-        let mut entries = header.entries(&*abbrev);
+        let (dwarf, unit) = self.entries_unit(info);
+        let mut entries = unit.entries();
 
-        let line_program = DwarfLineProgram::parse(
-            info,
-            self.line_offset,
-            header.address_size(),
-            self.comp_dir,
-            self.comp_name,
-        )?;
+        let line_program = DwarfLineProgram::parse(self.line_program.clone())?;
 
         while let Some((movement, entry)) = entries.next_dfs()? {
             depth += movement;
@@ -359,7 +627,8 @@ impl<'a> Unit<'a> {
                 _ => continue,
             };
 
-            let (call_line, call_file, ranges) = self.parse_location(info, entry, range_buf)?;
+            let (call_line, call_file, ranges) =
+                self.parse_location(dwarf, unit, entry, range_buf)?;
 
             // Ranges can be empty for two reasons: (1) the function is a no-op and does not contain
             // any code, or (2) the function did contain eliminated dead code. In the latter case, a
@@ -386,7 +655,7 @@ impl<'a> Unit<'a> {
                     Some(symbol.into())
                 } else {
                     // fall back to dwarf info
-                    self.resolve_function_name(info, entry)?
+                    self.resolve_function_name(info, dwarf, unit, entry)?
                 }
             };
 
@@ -407,14 +676,15 @@ impl<'a> Unit<'a> {
             for range in ranges {
                 let rows = line_program.get_rows(range);
                 for row in rows {
-                    let (base_dir, filename) = line_program.get_filename(row.file_index)?;
+                    let (base_dir, filename) =
+                        line_program.get_filename(info, &self.unit, row.file_index)?;
 
                     let new_line = Line {
                         addr: row.address - info.vmaddr,
                         original_file_id: row.file_index as u64,
                         filename,
                         base_dir,
-                        line: cmp::min(row.line.unwrap_or(0), 0xffff) as u16,
+                        line: cmp::min(row.line.unwrap_or(0), u32::MAX.into()) as u32,
                     };
 
                     func.append_line_if_changed(new_line);
@@ -448,14 +718,14 @@ impl<'a> Unit<'a> {
             // this info is missing, the lookup might return invalid line
             // numbers.
             if let (Some(call_line), Some(call_file)) = (call_line, call_file) {
-                let (base_dir, filename) = line_program.get_filename(call_file)?;
+                let (base_dir, filename) = self.get_call_file(info, &line_program, call_file)?;
                 match node.lines.binary_search_by_key(&func.addr, |x| x.addr) {
                     Ok(idx) => {
                         // We found a line record that points to this function.
                         // This happens especially, if the function range overlaps
                         // exactly. Patch the call info with the correct location.
                         let line = &mut node.lines[idx];
-                        line.line = cmp::min(call_line, 0xffff) as u16;
+                        line.line = cmp::min(call_line, u32::MAX.into()) as u32;
                         line.base_dir = base_dir;
                         line.filename = filename;
                         line.original_file_id = call_file;
@@ -469,7 +739,7 @@ impl<'a> Unit<'a> {
                             original_file_id: call_file,
                             filename,
                             base_dir,
-                            line: cmp::min(call_line, 0xffff) as u16,
+                            line: cmp::min(call_line, u32::MAX.into()) as u32,
                         };
                         node.lines.insert(idx, line);
                     }
@@ -488,8 +758,9 @@ impl<'a> Unit<'a> {
 
     fn parse_location<'r>(
         &self,
-        info: &DwarfInfo<'a>,
-        entry: &Die<'_, '_, '_>,
+        dwarf: &Dwarf<'a>,
+        unit: &GimliUnit<'a>,
+        entry: &Die<'_, '_, 'a>,
         buf: &'r mut Vec<Range>,
     ) -> Result<FunctionLocation<'r>, SymCacheError> {
         let mut tuple = FunctionLocation::default();
@@ -502,30 +773,25 @@ impl<'a> Unit<'a> {
         let mut attrs = entry.attrs();
         while let Some(attr) = attrs.next()? {
             match attr.name() {
-                gimli::DW_AT_ranges => match attr.value() {
-                    AttributeValue::RangeListsRef(offset) => {
-                        let header = info.get_unit_header(self.index)?;
-                        let mut attrs = info.sections.range_lists.ranges(
-                            offset,
-                            self.version,
-                            header.address_size(),
-                            self.base_address,
-                        )?;
-
+                gimli::DW_AT_ranges => match dwarf.attr_ranges_offset(unit, attr.value())? {
+                    Some(offset) => {
+                        let mut attrs = dwarf.ranges(unit, offset)?;
                         while let Some(item) = attrs.next()? {
                             buf.push(item);
                         }
                     }
-                    _ => unreachable!(),
+                    None => unreachable!(),
                 },
-                gimli::DW_AT_low_pc => match attr.value() {
-                    AttributeValue::Addr(addr) => low_pc = Some(addr),
-                    _ => unreachable!(),
+                gimli::DW_AT_low_pc => match dwarf.attr_address(unit, attr.value())? {
+                    Some(addr) => low_pc = Some(addr),
+                    None => unreachable!(),
                 },
                 gimli::DW_AT_high_pc => match attr.value() {
-                    AttributeValue::Addr(addr) => high_pc = Some(addr),
                     AttributeValue::Udata(size) => high_pc_rel = Some(size),
-                    _ => unreachable!(),
+                    value => match dwarf.attr_address(unit, value)? {
+                        Some(addr) => high_pc = Some(addr),
+                        None => unreachable!(),
+                    },
                 },
                 gimli::DW_AT_call_line => match attr.value() {
                     AttributeValue::Udata(line) => tuple.0 = Some(line),
@@ -583,42 +849,65 @@ impl<'a> Unit<'a> {
     ///
     /// As this might resolve into cached information the data borrowed from
     /// abbrev can only be temporarily accessed in the callback.
-    fn resolve_reference<'info, T, F>(
+    fn resolve_reference<T, F>(
         &self,
-        info: &'info DwarfInfo<'a>,
+        info: &DwarfInfo<'a>,
+        dwarf: &Dwarf<'a>,
+        unit: &GimliUnit<'a>,
         attr_value: AttributeValue<Buf<'a>>,
         f: F,
     ) -> Result<Option<T>, SymCacheError>
     where
-        for<'abbrev> F: FnOnce(&Die<'abbrev, 'info, 'a>) -> Result<Option<T>, SymCacheError>,
+        F: FnOnce(&Dwarf<'a>, &GimliUnit<'a>, &Die<'_, '_, 'a>) -> Result<Option<T>, SymCacheError>,
     {
-        let (index, offset) = match attr_value {
-            AttributeValue::UnitRef(offset) => (self.index, offset),
-            AttributeValue::DebugInfoRef(offset) => {
+        // References across units are resolved within the file containing the referencing
+        // entry. Split units can only reference entries in their own unit.
+        let sup = info.sections.dwarf.sup();
+        let in_main = std::ptr::eq(dwarf, &info.sections.dwarf);
+        let in_sup = match sup {
+            Some(sup) => std::ptr::eq(dwarf, sup),
+            None => false,
+        };
+
+        let (dwarf, target, offset) = match attr_value {
+            AttributeValue::UnitRef(offset) => (dwarf, None, offset),
+            AttributeValue::DebugInfoRef(offset) if in_main => {
                 let (index, unit_offset) = info.find_unit_offset(offset)?;
-                (index, unit_offset)
+                (dwarf, Some(info.get_unit(index)?), unit_offset)
             }
+            AttributeValue::DebugInfoRef(offset) if in_sup => {
+                let (index, unit_offset) = info.find_sup_unit_offset(offset)?;
+                (dwarf, Some(info.get_sup_unit(index)?), unit_offset)
+            }
+            AttributeValue::DebugInfoRefSup(offset) => match sup {
+                Some(sup) => {
+                    let (index, unit_offset) = info.find_sup_unit_offset(offset)?;
+                    (sup, Some(info.get_sup_unit(index)?), unit_offset)
+                }
+                None => return Ok(None),
+            },
             // TODO: there is probably more that can come back here
             _ => return Ok(None),
         };
 
-        let header = info.get_unit_header(index)?;
-        let abbrev = info.get_abbrev(header)?;
-        let mut entries = header.entries_at_offset(&*abbrev, offset)?;
+        let unit = target.as_ref().map_or(unit, |unit| &**unit);
+        let mut entries = unit.entries_at_offset(offset)?;
 
         entries.next_entry()?;
         if let Some(entry) = entries.current() {
-            f(entry)
+            f(dwarf, unit, entry)
         } else {
             Ok(None)
         }
     }
 
     /// Resolves the function name of a debug entry.
-    fn resolve_function_name<'abbrev, 'unit>(
+    fn resolve_function_name(
         &self,
         info: &DwarfInfo<'a>,
-        entry: &Die<'abbrev, 'unit, 'a>,
+        dwarf: &Dwarf<'a>,
+        unit: &GimliUnit<'a>,
+        entry: &Die<'_, '_, 'a>,
     ) -> Result<Option<Cow<'a, str>>, SymCacheError> {
         let mut attrs = entry.attrs();
         let mut fallback_name = None;
@@ -628,8 +917,9 @@ impl<'a> Unit<'a> {
             match attr.name() {
                 // prioritize these.  If we get them, take them.
                 gimli::DW_AT_linkage_name | gimli::DW_AT_MIPS_linkage_name => {
-                    return Ok(attr
-                        .string_value(&info.sections.debug_str)
+                    return Ok(dwarf
+                        .attr_string(unit, attr.value())
+                        .ok()
                         .map(|s| s.to_string_lossy()));
                 }
                 gimli::DW_AT_name => {
@@ -643,15 +933,20 @@ impl<'a> Unit<'a> {
         }
 
         if let Some(attr) = fallback_name {
-            return Ok(attr
-                .string_value(&info.sections.debug_str)
+            return Ok(dwarf
+                .attr_string(unit, attr.value())
+                .ok()
                 .map(|s| s.to_string_lossy()));
         }
 
         if let Some(attr) = reference_target {
-            if let Some(name) = self.resolve_reference(info, attr.value(), |ref_entry| {
-                self.resolve_function_name(info, ref_entry)
-            })? {
+            if let Some(name) = self.resolve_reference(
+                info,
+                dwarf,
+                unit,
+                attr.value(),
+                |dwarf, unit, ref_entry| self.resolve_function_name(info, dwarf, unit, ref_entry),
+            )? {
                 return Ok(Some(name));
             }
         }
@@ -663,7 +958,7 @@ impl<'a> Unit<'a> {
 #[derive(Debug)]
 struct DwarfLineProgram<'a> {
     sequences: Vec<DwarfSeq>,
-    program_rows: gimli::StateMachine<Buf<'a>, gimli::IncompleteLineNumberProgram<Buf<'a>>>,
+    header: gimli::LineProgramHeader<Buf<'a>>,
 }
 
 #[derive(Debug)]
@@ -681,18 +976,7 @@ struct DwarfRow {
 }
 
 impl<'a> DwarfLineProgram<'a> {
-    fn parse<'info>(
-        info: &'info DwarfInfo<'a>,
-        line_offset: DebugLineOffset,
-        address_size: u8,
-        comp_dir: Option<Buf<'a>>,
-        comp_name: Option<Buf<'a>>,
-    ) -> Result<Self, SymCacheError> {
-        let program =
-            info.sections
-                .debug_line
-                .program(line_offset, address_size, comp_dir, comp_name)?;
-
+    fn parse(program: gimli::IncompleteLineProgram<Buf<'a>>) -> Result<Self, SymCacheError> {
         let mut sequences = vec![];
         let mut sequence_rows: Vec<DwarfRow> = vec![];
         let mut prev_address = 0;
@@ -726,7 +1010,7 @@ impl<'a> DwarfLineProgram<'a> {
                 // here, but let's wait until that is needed.
             } else {
                 let file_index = program_row.file_index();
-                let line = program_row.line();
+                let line = program_row.line().map(|line| line.get());
                 let mut duplicate = false;
                 if let Some(last_row) = sequence_rows.last_mut() {
                     if last_row.address == address {
@@ -762,20 +1046,17 @@ impl<'a> DwarfLineProgram<'a> {
 
         Ok(DwarfLineProgram {
             sequences,
-            program_rows,
+            header: program_rows.header().clone(),
         })
     }
 
-    pub fn get_filename(&self, idx: u64) -> Result<(&'a [u8], &'a [u8]), SymCacheError> {
-        let header = self.program_rows.header();
-        let file = header
-            .file(idx)
-            .ok_or_else(|| SymCacheError::from(ConversionError::new("invalid file reference")))?;
-
-        Ok((
-            file.directory(header).map(|x| x.slice()).unwrap_or(b""),
-            file.path_name().slice(),
-        ))
+    pub fn get_filename(
+        &self,
+        info: &DwarfInfo<'a>,
+        unit: &GimliUnit<'a>,
+        idx: u64,
+    ) -> Result<(&'a [u8], &'a [u8]), SymCacheError> {
+        get_filename(&info.sections.dwarf, unit, &self.header, idx)
     }
 
     pub fn get_rows(&self, rng: &Range) -> &[DwarfRow] {
@@ -798,3 +1079,25 @@ impl<'a> DwarfLineProgram<'a> {
         &[]
     }
 }
+
+/// Resolves the directory and path name of a file in a line program header.
+fn get_filename<'a>(
+    dwarf: &Dwarf<'a>,
+    unit: &GimliUnit<'a>,
+    header: &gimli::LineProgramHeader<Buf<'a>>,
+    idx: u64,
+) -> Result<(&'a [u8], &'a [u8]), SymCacheError> {
+    let file = header
+        .file(idx)
+        .ok_or_else(|| SymCacheError::from(ConversionError::new("invalid file reference")))?;
+
+    let directory = match file.directory(header) {
+        Some(attr) => dwarf.attr_string(unit, attr)?.slice(),
+        None => b"",
+    };
+
+    Ok((
+        directory,
+        dwarf.attr_string(unit, file.path_name())?.slice(),
+    ))
+}
@@ -92,6 +92,18 @@ pub enum SymCacheErrorKind {
     #[fail(display = "too many {}s for symcache", _0)]
     TooManyValues(ValueKind),
 
+    /// The objects merged into a symcache have different debug identifiers.
+    #[fail(display = "mismatching debug ids of merged objects")]
+    MismatchedDebugId,
+
+    /// The contents of the symcache do not match the checksum in its header.
+    #[fail(display = "symcache checksum mismatch")]
+    ChecksumMismatch,
+
+    /// Streaming was requested for an operation that does not support it.
+    #[fail(display = "streaming is not supported when merging objects")]
+    UnsupportedStreaming,
+
     /// Generic error when writing a symcache, most likely IO.
     #[fail(display = "failed to write symcache")]
     WriteFailed,
@@ -152,6 +164,12 @@ impl From<gimli::Error> for SymCacheError {
     }
 }
 
+impl From<pdb::Error> for SymCacheError {
+    fn from(error: pdb::Error) -> SymCacheError {
+        error.context(SymCacheErrorKind::BadDebugFile).into()
+    }
+}
+
 impl From<ConversionError> for SymCacheError {
     fn from(error: ConversionError) -> SymCacheError {
         error.context(SymCacheErrorKind::BadDebugFile).into()
@@ -19,6 +19,8 @@ pub struct InstructionInfo {
     /// This is true if the frame is the cause of the crash.
     pub crashing_frame: bool,
     /// If a signal is known that triggers the crash, it can be stored here.
+    ///
+    /// For minidumps, this is provided by `ProcessState::crash_signal` in `symbolic-minidump`.
     pub signal: Option<u32>,
     /// The optional value of the IP register.
     pub ip_reg: Option<u64>,
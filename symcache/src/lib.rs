@@ -5,6 +5,7 @@ mod cache;
 mod dwarf;
 mod error;
 mod heuristics;
+mod pdb;
 mod types;
 mod utils;
 mod writer;
@@ -0,0 +1,383 @@
+use std::borrow::Cow;
+use std::cmp;
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+use fnv::FnvHashMap;
+use pdb::{
+    AddressMap, FallibleIterator, FileIndex, IdData, IdIndex, Inlinee, ItemFinder, LineInfo,
+    LineProgram, PdbInternalSectionOffset, SourceLanguage, StringTable, SymbolData, SymbolIndex,
+    TypeIndex, PDB,
+};
+
+use symbolic_common::types::Language;
+use symbolic_debuginfo::Object;
+
+use crate::dwarf::{Function, Line};
+use crate::error::SymCacheError;
+
+/// A line record of a function or inlinee in a PDB.
+#[derive(Debug)]
+struct PdbLine {
+    addr: u64,
+    file_id: usize,
+    line: u32,
+}
+
+/// A function or inlined call site read from a PDB module.
+#[derive(Debug)]
+struct PdbFunction {
+    addr: u64,
+    len: u32,
+    name: String,
+    lang: Language,
+    lines: Vec<PdbLine>,
+    inlines: Vec<PdbFunction>,
+}
+
+impl PdbFunction {
+    /// Converts this function into the common representation used by `SymCacheWriter`.
+    fn to_function<'a>(&self, files: &'a [String], depth: u16) -> Function<'a> {
+        Function {
+            depth,
+            addr: self.addr,
+            len: self.len,
+            name: Cow::Owned(self.name.clone()),
+            inlines: self
+                .inlines
+                .iter()
+                .map(|inline| inline.to_function(files, depth + 1))
+                .collect(),
+            lines: self
+                .lines
+                .iter()
+                .map(|line| Line {
+                    addr: line.addr,
+                    original_file_id: line.file_id as u64,
+                    filename: files[line.file_id].as_bytes(),
+                    base_dir: b"",
+                    line: line.line,
+                })
+                .collect(),
+            comp_dir: b"",
+            lang: self.lang,
+        }
+    }
+}
+
+/// A procedure or inline site whose scope has not been closed yet.
+struct Scope {
+    end: SymbolIndex,
+    offset: PdbInternalSectionOffset,
+    function: PdbFunction,
+    /// Whether the function could be mapped to an address.
+    mapped: bool,
+}
+
+impl Scope {
+    /// Moves this scope into its parent on the stack, or emits it as top-level function.
+    ///
+    /// Inline sites that could not be mapped to an address are dropped. Their inlinees are moved
+    /// into the parent instead.
+    fn close(self, stack: &mut [Scope], functions: &mut Vec<PdbFunction>) {
+        let target = match stack.last_mut() {
+            Some(parent) => &mut parent.function.inlines,
+            None => functions,
+        };
+
+        if self.mapped {
+            target.push(self.function);
+        } else {
+            target.extend(self.function.inlines);
+        }
+    }
+}
+
+/// Debug information read from the DBI and module streams of a PDB.
+///
+/// All addresses are relative to the image base of the corresponding PE file.
+#[derive(Debug)]
+pub struct PdbInfo {
+    files: Vec<String>,
+    functions: Vec<PdbFunction>,
+}
+
+impl PdbInfo {
+    pub fn from_object(object: &Object<'_>) -> Result<PdbInfo, SymCacheError> {
+        let mut pdb = PDB::open(Cursor::new(object.as_bytes()))?;
+        let mut info = PdbInfo {
+            files: vec![],
+            functions: vec![],
+        };
+
+        info.parse(&mut pdb)?;
+
+        // Functions are not necessarily declared in address order across modules. The symcache
+        // needs them sorted for binary search, however.
+        info.functions.sort_by_key(|function| function.addr);
+        Ok(info)
+    }
+
+    /// Returns all functions with their inlinees, ordered by address.
+    pub fn functions(&self) -> Vec<Function<'_>> {
+        self.functions
+            .iter()
+            .map(|function| function.to_function(&self.files, 0))
+            .collect()
+    }
+
+    fn parse<'s>(&mut self, pdb: &mut PDB<'s, Cursor<&'s [u8]>>) -> Result<(), SymCacheError> {
+        let address_map = pdb.address_map()?;
+        let string_table = pdb.string_table()?;
+
+        // Inlinees reference their names in the IPI stream, and member functions their classes in
+        // the TPI stream. Build an index over all items once, so that they can be resolved in
+        // random order below.
+        let id_information = pdb.id_information()?;
+        let mut id_finder = id_information.finder();
+        let mut id_iter = id_information.iter();
+        while id_iter.next()?.is_some() {
+            id_finder.update(&id_iter);
+        }
+
+        let type_information = pdb.type_information()?;
+        let mut type_finder = type_information.finder();
+        let mut type_iter = type_information.iter();
+        while type_iter.next()?.is_some() {
+            type_finder.update(&type_iter);
+        }
+
+        let mut file_map = FnvHashMap::default();
+        let dbi = pdb.debug_information()?;
+        let mut modules = dbi.modules()?;
+        while let Some(module) = modules.next()? {
+            let module_info = match pdb.module_info(&module)? {
+                Some(module_info) => module_info,
+                None => continue,
+            };
+
+            let inlinees: BTreeMap<IdIndex, Inlinee<'_>> = module_info
+                .inlinees()?
+                .map(|inlinee| Ok((inlinee.index(), inlinee)))
+                .collect()?;
+
+            let mut context = ModuleContext {
+                address_map: &address_map,
+                string_table: &string_table,
+                id_finder: &id_finder,
+                type_finder: &type_finder,
+                program: module_info.line_program()?,
+                file_map: &mut file_map,
+                files: &mut self.files,
+                module_files: FnvHashMap::default(),
+            };
+
+            let mut lang = Language::Unknown;
+            let mut stack: Vec<Scope> = vec![];
+            let mut symbols = module_info.symbols()?;
+
+            while let Some(symbol) = symbols.next()? {
+                // Close all scopes that end at or before this symbol. Each closed function is
+                // either moved into its parent or emitted as top-level function.
+                while let Some(scope) = stack.pop() {
+                    if scope.end > symbol.index() {
+                        stack.push(scope);
+                        break;
+                    }
+
+                    scope.close(&mut stack, &mut self.functions);
+                }
+
+                // Unknown symbol kinds fail to parse. They do not carry information relevant to
+                // the symcache, so they can be skipped safely.
+                match symbol.parse() {
+                    Ok(SymbolData::CompileFlags(flags)) => {
+                        lang = match flags.language {
+                            SourceLanguage::C => Language::C,
+                            SourceLanguage::Cpp => Language::Cpp,
+                            _ => Language::Unknown,
+                        };
+                    }
+                    Ok(SymbolData::Procedure(proc)) => {
+                        let addr = match proc.offset.to_rva(&address_map) {
+                            Some(rva) => u64::from(rva.0),
+                            None => continue,
+                        };
+
+                        let line_infos = context.program.lines_at_offset(proc.offset);
+                        let lines = context.lines(line_infos.collect()?)?;
+                        stack.push(Scope {
+                            end: proc.end,
+                            offset: proc.offset,
+                            function: PdbFunction {
+                                addr,
+                                len: proc.len,
+                                name: proc.name.to_string().into_owned(),
+                                lang,
+                                lines,
+                                inlines: vec![],
+                            },
+                            mapped: true,
+                        });
+                    }
+                    Ok(SymbolData::InlineSite(site)) => {
+                        // Inline sites are only valid within a procedure. Their line records are
+                        // stored relative to the offset of the outermost procedure.
+                        let parent_offset = match stack.first() {
+                            Some(scope) => scope.offset,
+                            None => continue,
+                        };
+
+                        let line_infos = match inlinees.get(&site.inlinee) {
+                            Some(inlinee) => inlinee.lines(parent_offset, &site).collect()?,
+                            None => vec![],
+                        };
+
+                        // Inline sites without line records cannot be mapped to an address.
+                        // They still have to be tracked to close scopes in the right order.
+                        let range = context.range(&line_infos);
+                        let (addr, len) = range.unwrap_or((0, 0));
+                        let lines = context.lines(line_infos)?;
+
+                        stack.push(Scope {
+                            end: site.end,
+                            offset: parent_offset,
+                            function: PdbFunction {
+                                addr,
+                                len,
+                                name: context.inlinee_name(site.inlinee)?,
+                                lang,
+                                lines,
+                                inlines: vec![],
+                            },
+                            mapped: range.is_some(),
+                        });
+                    }
+                    _ => (),
+                }
+            }
+
+            // Flush functions left open at the end of the module stream.
+            while let Some(scope) = stack.pop() {
+                scope.close(&mut stack, &mut self.functions);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Per-module state required to resolve line records and names.
+struct ModuleContext<'a, 's, 'i> {
+    address_map: &'a AddressMap<'s>,
+    string_table: &'a StringTable<'s>,
+    id_finder: &'a ItemFinder<'i, IdIndex>,
+    type_finder: &'a ItemFinder<'i, TypeIndex>,
+    program: LineProgram<'a>,
+    file_map: &'a mut FnvHashMap<String, usize>,
+    files: &'a mut Vec<String>,
+    module_files: FnvHashMap<FileIndex, usize>,
+}
+
+impl<'a, 's, 'i> ModuleContext<'a, 's, 'i> {
+    /// Resolves a file index of this module into an index into the global file list.
+    fn file_id(&mut self, index: FileIndex) -> Result<usize, SymCacheError> {
+        if let Some(&file_id) = self.module_files.get(&index) {
+            return Ok(file_id);
+        }
+
+        let file_info = self.program.get_file_info(index)?;
+        let name = file_info.name.to_string_lossy(self.string_table)?;
+
+        let files = &mut self.files;
+        let file_id = *self.file_map.entry(name.to_string()).or_insert_with(|| {
+            files.push(name.into_owned());
+            files.len() - 1
+        });
+
+        self.module_files.insert(index, file_id);
+        Ok(file_id)
+    }
+
+    /// Converts PDB line records into `PdbLine`s ordered by address.
+    ///
+    /// Records that cannot be mapped to an address in the image are skipped.
+    fn lines(&mut self, line_infos: Vec<LineInfo>) -> Result<Vec<PdbLine>, SymCacheError> {
+        let mut lines = Vec::with_capacity(line_infos.len());
+
+        for line_info in line_infos {
+            let addr = match line_info.offset.to_rva(self.address_map) {
+                Some(rva) => u64::from(rva.0),
+                None => continue,
+            };
+
+            lines.push(PdbLine {
+                addr,
+                file_id: self.file_id(line_info.file_index)?,
+                line: line_info.line_start,
+            });
+        }
+
+        lines.sort_by_key(|line| line.addr);
+        Ok(lines)
+    }
+
+    /// Computes the address range covered by the given line records.
+    fn range(&self, line_infos: &[LineInfo]) -> Option<(u64, u32)> {
+        let mut range: Option<(u64, u64)> = None;
+
+        for line_info in line_infos {
+            let start = match line_info.offset.to_rva(self.address_map) {
+                Some(rva) => u64::from(rva.0),
+                None => continue,
+            };
+
+            let end = start + u64::from(line_info.length.unwrap_or(0));
+            range = Some(match range {
+                Some((low, high)) => (cmp::min(low, start), cmp::max(high, end)),
+                None => (start, end),
+            });
+        }
+
+        range.map(|(low, high)| (low, (high - low) as u32))
+    }
+
+    /// Resolves the name of an inlined function from the IPI stream.
+    ///
+    /// Inlinees imported from other modules are referenced through the module's cross module
+    /// imports, which cannot be resolved. Their names are left empty, like unnamed DWARF functions.
+    /// Member functions are qualified with the name of their class, like `Class::method`.
+    fn inlinee_name(&self, index: IdIndex) -> Result<String, SymCacheError> {
+        let item = match self.id_finder.find(index) {
+            Ok(item) => item,
+            Err(pdb::Error::TypeNotFound(_)) => return Ok(String::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let name = match item.parse()? {
+            IdData::Function(function) => function.name.to_string(),
+            IdData::MemberFunction(function) => match self.type_name(function.parent)? {
+                Some(class) => Cow::Owned(format!("{}::{}", class, function.name)),
+                None => function.name.to_string(),
+            },
+            _ => Cow::Borrowed(""),
+        };
+
+        Ok(name.into_owned())
+    }
+
+    /// Resolves the name of a type from the TPI stream, if it has one.
+    fn type_name(&self, index: TypeIndex) -> Result<Option<String>, SymCacheError> {
+        let item = match self.type_finder.find(index) {
+            Ok(item) => item,
+            Err(pdb::Error::TypeNotFound(_)) => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+
+        // Unknown type kinds fail to parse, just like unknown symbols.
+        Ok(match item.parse() {
+            Ok(data) => data.name().map(|name| name.to_string().into_owned()),
+            Err(_) => None,
+        })
+    }
+}
@@ -28,6 +28,18 @@ impl<T, L> Seg<T, L> {
     }
 }
 
+impl<T, L: Copy> Seg<T, L> {
+    /// Converts the length of this segment into a wider type.
+    pub fn widen<W: From<L>>(self) -> Seg<T, W> {
+        Seg::new(self.offset, self.len.into())
+    }
+
+    /// Changes the item type of this segment.
+    pub fn cast<U>(self) -> Seg<U, L> {
+        Seg::new(self.offset, self.len)
+    }
+}
+
 impl<T, L: Copy> Copy for Seg<T, L> {}
 
 impl<T, L: Copy> Clone for Seg<T, L> {
@@ -71,16 +83,18 @@ impl<T, L: fmt::Debug + Copy> fmt::Debug for Seg<T, L> {
     }
 }
 
+/// A file record as stored in version 1 and 2 caches.
 #[repr(C, packed)]
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Default, Copy, Clone, Debug)]
-pub struct FileRecord {
+pub struct FileRecordV1 {
     pub filename: Seg<u8, u8>,
     pub base_dir: Seg<u8, u8>,
 }
 
+/// A function record as stored in version 1 and 2 caches.
 #[repr(C, packed)]
 #[derive(Default, Copy, Clone, Debug)]
-pub struct FuncRecord {
+pub struct FuncRecordV1 {
     /// low bits of the address.
     pub addr_low: u32,
     /// high bits of the address
@@ -89,7 +103,7 @@ pub struct FuncRecord {
     pub len: u16,
     /// The line record of this function.  If it fully overlaps
     /// with an inline the record could be ~0
-    pub line_records: Seg<LineRecord, u16>,
+    pub line_records: Seg<LineRecordV1, u16>,
     /// The comp dir of the file record
     pub comp_dir: Seg<u8, u8>,
     /// The ID offset of the parent funciton.  Will be ~0 if the function has
@@ -103,9 +117,10 @@ pub struct FuncRecord {
     pub lang: u8,
 }
 
+/// A line record as stored in version 1 and 2 caches.
 #[repr(C, packed)]
 #[derive(Default, Copy, Clone, Debug)]
-pub struct LineRecord {
+pub struct LineRecordV1 {
     /// offset to function item or line record
     pub addr_off: u8,
     /// absolutely indexed file
@@ -114,6 +129,177 @@ pub struct LineRecord {
     pub line: u16,
 }
 
+/// A file record as stored in version 3 caches.
+#[repr(C, packed)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Default, Copy, Clone, Debug)]
+pub struct FileRecordV3 {
+    pub filename: Seg<u8, u16>,
+    pub base_dir: Seg<u8, u16>,
+}
+
+/// A function record as stored in version 3 caches.
+#[repr(C, packed)]
+#[derive(Default, Copy, Clone, Debug)]
+pub struct FuncRecordV3 {
+    /// low bits of the address.
+    pub addr_low: u32,
+    /// high bits of the address
+    pub addr_high: u16,
+    /// the length of the function.
+    pub len: u32,
+    /// The line records of this function.
+    pub line_records: Seg<LineRecordV3>,
+    /// The comp dir of the file record
+    pub comp_dir: Seg<u8, u16>,
+    /// The ID offset of the parent funciton.  Will be ~0 if the function has
+    /// no parent.
+    pub parent_offset: u32,
+    /// The ID of the symbol of this function or ~0 if no symbol.
+    pub symbol_id: u32,
+    /// The language of the func record.
+    pub lang: u8,
+}
+
+/// A line record as stored in version 3 caches.
+///
+/// Line records make up the bulk of a cache file, so only file IDs and line numbers are widened
+/// compared to version 2. At 10 bytes per record, this is twice the size of a version 2 record.
+/// Gaps of more than `u16::MAX` bytes between lines are split into multiple records with the
+/// same line, which is rare in practice.
+#[repr(C, packed)]
+#[derive(Default, Copy, Clone, Debug)]
+pub struct LineRecordV3 {
+    /// offset to function item or line record
+    pub addr_off: u16,
+    /// absolutely indexed file
+    pub file_id: u32,
+    /// the line of the line record
+    pub line: u32,
+}
+
+/// An entry of the name index as stored in version 3 caches.
+#[repr(C, packed)]
+#[derive(Default, Copy, Clone, Debug)]
+pub struct NameRecordV3 {
+    /// The ID of the function.
+    pub func_id: u32,
+}
+
+/// A file record independent of the cache file version.
+#[derive(Default, Copy, Clone, Debug)]
+pub struct FileRecord {
+    pub filename: Seg<u8, u16>,
+    pub base_dir: Seg<u8, u16>,
+}
+
+impl From<FileRecordV1> for FileRecord {
+    fn from(record: FileRecordV1) -> Self {
+        FileRecord {
+            filename: record.filename.widen(),
+            base_dir: record.base_dir.widen(),
+        }
+    }
+}
+
+impl From<FileRecordV3> for FileRecord {
+    fn from(record: FileRecordV3) -> Self {
+        FileRecord {
+            filename: record.filename,
+            base_dir: record.base_dir,
+        }
+    }
+}
+
+/// A function record independent of the cache file version.
+///
+/// The line records segment counts records in the layout of the cache file version.
+#[derive(Default, Copy, Clone, Debug)]
+pub struct FuncRecord {
+    /// The address of the function.
+    pub addr: u64,
+    /// The length of the function.
+    pub len: u32,
+    /// The line records of this function.
+    pub line_records: Seg<LineRecord>,
+    /// The comp dir of the file record
+    pub comp_dir: Seg<u8, u16>,
+    /// The ID offset of the parent funciton, or ~0 if the function has no parent.
+    pub parent_offset: u32,
+    /// The ID of the symbol of this function or ~0 if no symbol.
+    pub symbol_id: u32,
+    /// The language of the func record.
+    pub lang: u8,
+}
+
+impl From<FuncRecordV1> for FuncRecord {
+    fn from(record: FuncRecordV1) -> Self {
+        let symbol_id = (u32::from(record.symbol_id_high) << 16) | u32::from(record.symbol_id_low);
+        FuncRecord {
+            addr: (u64::from(record.addr_high) << 32) | u64::from(record.addr_low),
+            len: record.len.into(),
+            line_records: record.line_records.widen().cast(),
+            comp_dir: record.comp_dir.widen(),
+            parent_offset: match record.parent_offset {
+                0xffff => !0,
+                offset => offset.into(),
+            },
+            symbol_id: match symbol_id {
+                0x00ff_ffff => !0,
+                id => id,
+            },
+            lang: record.lang,
+        }
+    }
+}
+
+impl From<FuncRecordV3> for FuncRecord {
+    fn from(record: FuncRecordV3) -> Self {
+        FuncRecord {
+            addr: (u64::from(record.addr_high) << 32) | u64::from(record.addr_low),
+            len: record.len,
+            line_records: record.line_records.cast(),
+            comp_dir: record.comp_dir,
+            parent_offset: record.parent_offset,
+            symbol_id: record.symbol_id,
+            lang: record.lang,
+        }
+    }
+}
+
+/// A line record independent of the cache file version.
+#[derive(Default, Copy, Clone, Debug)]
+pub struct LineRecord {
+    /// offset to function item or line record
+    pub addr_off: u32,
+    /// absolutely indexed file
+    pub file_id: u32,
+    /// the line of the line record
+    pub line: u32,
+}
+
+impl From<LineRecordV1> for LineRecord {
+    fn from(record: LineRecordV1) -> Self {
+        LineRecord {
+            addr_off: record.addr_off.into(),
+            file_id: match record.file_id {
+                0xffff => !0,
+                id => id.into(),
+            },
+            line: record.line.into(),
+        }
+    }
+}
+
+impl From<LineRecordV3> for LineRecord {
+    fn from(record: LineRecordV3) -> Self {
+        LineRecord {
+            addr_off: record.addr_off.into(),
+            file_id: record.file_id,
+            line: record.line,
+        }
+    }
+}
+
 #[derive(Debug, Fail, Copy, Clone)]
 #[fail(display = "unknown symcache data source")]
 pub struct UnknownDataSourceError;
@@ -125,6 +311,7 @@ pub enum DataSource {
     Dwarf,
     SymbolTable,
     BreakpadSym,
+    Pdb,
     #[doc(hidden)]
     __Max,
 }
@@ -153,14 +340,27 @@ pub struct CacheFilePreamble {
     pub version: u32,
 }
 
+/// Common accessors of all cache file header versions.
+///
+/// Files and functions segments count records in the layout of the respective cache file version.
 pub trait CacheFileHeader {
     fn id(&self) -> DebugId;
     fn arch(&self) -> u32;
     fn data_source(&self) -> u8;
     fn has_line_records(&self) -> u8;
-    fn symbols(&self) -> &Seg<Seg<u8, u16>>;
-    fn files(&self) -> &Seg<FileRecord, u16>;
-    fn function_records(&self) -> &Seg<FuncRecord>;
+    fn symbols(&self) -> Seg<Seg<u8, u16>>;
+    fn files(&self) -> Seg<FileRecord>;
+    fn function_records(&self) -> Seg<FuncRecord>;
+
+    /// Function IDs sorted by demangled function name, if the cache contains a name index.
+    fn name_index(&self) -> Seg<NameRecordV3> {
+        Seg::default()
+    }
+
+    /// The data source of every function record, if the cache was merged from multiple objects.
+    fn function_sources(&self) -> Seg<u8> {
+        Seg::default()
+    }
 }
 
 #[repr(C, packed)]
@@ -172,8 +372,8 @@ pub struct CacheFileHeaderV1 {
     pub data_source: u8,
     pub has_line_records: u8,
     pub symbols: Seg<Seg<u8, u16>>,
-    pub files: Seg<FileRecord, u16>,
-    pub function_records: Seg<FuncRecord>,
+    pub files: Seg<FileRecordV1, u16>,
+    pub function_records: Seg<FuncRecordV1>,
 }
 
 impl CacheFileHeader for CacheFileHeaderV1 {
@@ -193,16 +393,16 @@ impl CacheFileHeader for CacheFileHeaderV1 {
         self.has_line_records
     }
 
-    fn symbols(&self) -> &Seg<Seg<u8, u16>> {
-        &self.symbols
+    fn symbols(&self) -> Seg<Seg<u8, u16>> {
+        self.symbols
     }
 
-    fn files(&self) -> &Seg<FileRecord, u16> {
-        &self.files
+    fn files(&self) -> Seg<FileRecord> {
+        self.files.widen().cast()
     }
 
-    fn function_records(&self) -> &Seg<FuncRecord> {
-        &self.function_records
+    fn function_records(&self) -> Seg<FuncRecord> {
+        self.function_records.cast()
     }
 }
 
@@ -215,20 +415,70 @@ pub struct CacheFileHeaderV2 {
     pub data_source: u8,
     pub has_line_records: u8,
     pub symbols: Seg<Seg<u8, u16>>,
-    pub files: Seg<FileRecord, u16>,
-    pub function_records: Seg<FuncRecord>,
+    pub files: Seg<FileRecordV1, u16>,
+    pub function_records: Seg<FuncRecordV1>,
+}
+
+impl CacheFileHeader for CacheFileHeaderV2 {
+    fn id(&self) -> DebugId {
+        self.id
+    }
+
+    fn arch(&self) -> u32 {
+        self.arch
+    }
+
+    fn data_source(&self) -> u8 {
+        self.data_source
+    }
+
+    fn has_line_records(&self) -> u8 {
+        self.has_line_records
+    }
+
+    fn symbols(&self) -> Seg<Seg<u8, u16>> {
+        self.symbols
+    }
+
+    fn files(&self) -> Seg<FileRecord> {
+        self.files.widen().cast()
+    }
+
+    fn function_records(&self) -> Seg<FuncRecord> {
+        self.function_records.cast()
+    }
+}
+
+#[repr(C, packed)]
+#[derive(Default, Copy, Clone, Debug)]
+pub struct CacheFileHeaderV3 {
+    pub preamble: CacheFilePreamble,
+    pub id: DebugId,
+    pub arch: u32,
+    pub data_source: u8,
+    pub has_line_records: u8,
+    pub symbols: Seg<Seg<u8, u16>>,
+    pub files: Seg<FileRecordV3>,
+    pub function_records: Seg<FuncRecordV3>,
+    pub name_index: Seg<NameRecordV3>,
+    pub function_sources: Seg<u8>,
+    /// FNV-1a checksum of the entire file, computed with this field set to zero.
+    ///
+    /// Since the header is written last, the data following the header is hashed first, followed
+    /// by the header.
+    pub checksum: u64,
 }
 
-impl CacheFileHeaderV2 {
+impl CacheFileHeaderV3 {
     pub fn as_bytes(&self) -> &[u8] {
         unsafe {
             let bytes = self as *const Self as *const u8;
-            slice::from_raw_parts(bytes, mem::size_of::<CacheFileHeaderV2>())
+            slice::from_raw_parts(bytes, mem::size_of::<CacheFileHeaderV3>())
         }
     }
 }
 
-impl CacheFileHeader for CacheFileHeaderV2 {
+impl CacheFileHeader for CacheFileHeaderV3 {
     fn id(&self) -> DebugId {
         self.id
     }
@@ -245,26 +495,30 @@ impl CacheFileHeader for CacheFileHeaderV2 {
         self.has_line_records
     }
 
-    fn symbols(&self) -> &Seg<Seg<u8, u16>> {
-        &self.symbols
+    fn symbols(&self) -> Seg<Seg<u8, u16>> {
+        self.symbols
     }
 
-    fn files(&self) -> &Seg<FileRecord, u16> {
-        &self.files
+    fn files(&self) -> Seg<FileRecord> {
+        self.files.cast()
     }
 
-    fn function_records(&self) -> &Seg<FuncRecord> {
-        &self.function_records
+    fn function_records(&self) -> Seg<FuncRecord> {
+        self.function_records.cast()
     }
-}
 
-impl FuncRecord {
-    pub fn symbol_id(&self) -> u32 {
-        (u32::from(self.symbol_id_high) << 16) | u32::from(self.symbol_id_low)
+    fn name_index(&self) -> Seg<NameRecordV3> {
+        self.name_index
     }
 
+    fn function_sources(&self) -> Seg<u8> {
+        self.function_sources
+    }
+}
+
+impl FuncRecord {
     pub fn addr_start(&self) -> u64 {
-        (u64::from(self.addr_high) << 32) | u64::from(self.addr_low)
+        self.addr
     }
 
     pub fn addr_end(&self) -> u64 {
@@ -66,6 +66,25 @@ pub fn common_join_path(base: &str, other: &str) -> String {
     }
 }
 
+/// Checks whether a path ends with the given suffix.
+///
+/// Paths are compared by their components, so `bar/baz.c` matches `/foo/bar/baz.c` but not
+/// `/foo/xbar/baz.c`. Both slashes and backslashes are treated as separators.
+pub fn path_ends_with(path: &str, suffix: &str) -> bool {
+    fn components(s: &str) -> impl DoubleEndedIterator<Item = &str> {
+        s.split(&['\\', '/'][..])
+            .filter(|c| !c.is_empty() && *c != ".")
+    }
+
+    let mut path_iter = components(path).rev();
+    let mut suffix_iter = components(suffix).rev().peekable();
+    if suffix_iter.peek().is_none() {
+        return false;
+    }
+
+    suffix_iter.all(|c| path_iter.next() == Some(c))
+}
+
 /// Trims a path to a given length.
 ///
 /// This attempts to not completely destroy the path in the process.
@@ -164,6 +183,18 @@ fn test_common_join_path() {
     assert_eq!(common_join_path("foo/bar/", "blah"), "foo/bar/blah");
 }
 
+#[test]
+fn test_path_ends_with() {
+    assert!(path_ends_with("/foo/bar/baz.c", "baz.c"));
+    assert!(path_ends_with("/foo/bar/baz.c", "bar/baz.c"));
+    assert!(path_ends_with("/foo/bar/baz.c", "/foo/bar/baz.c"));
+    assert!(path_ends_with("C:\\foo\\bar\\baz.c", "bar/baz.c"));
+    assert!(path_ends_with("/foo/./bar/baz.c", "./bar/baz.c"));
+    assert!(!path_ends_with("/foo/xbar/baz.c", "bar/baz.c"));
+    assert!(!path_ends_with("/foo/bar/baz.c", "qux/foo/bar/baz.c"));
+    assert!(!path_ends_with("/foo/bar/baz.c", ""));
+}
+
 #[test]
 fn test_shorten_filename() {
     assert_eq!(&shorten_filename("/foo/bar/baz/blah/blafasel", 6), "/fo...");
@@ -1,53 +1,550 @@
 use std::cell::RefCell;
-use std::cmp;
-use std::collections::HashMap;
-use std::io::{Cursor, Seek, SeekFrom, Write};
+use std::cmp::{self, Reverse};
+use std::collections::BinaryHeap;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::hash::Hasher;
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 use std::iter::Peekable;
 use std::mem;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::process;
 use std::slice;
-use std::u16;
+use std::str;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{u16, u32};
 
 use failure::ResultExt;
-use fnv::{FnvHashMap, FnvHashSet};
+use fnv::{FnvHashMap, FnvHashSet, FnvHasher};
 
-use symbolic_common::types::{DebugKind, Language};
+use symbolic_common::types::{DebugKind, Language, Name};
 use symbolic_debuginfo::{Object, SymbolIterator, SymbolTable, Symbols};
+use symbolic_demangle::Demangle;
 
 use crate::breakpad::BreakpadInfo;
 use crate::cache::{SYMCACHE_LATEST_VERSION, SYMCACHE_MAGIC};
 use crate::dwarf::{DwarfInfo, Function, Unit};
 use crate::error::{ConversionError, SymCacheError, SymCacheErrorKind, ValueKind};
-use crate::types::{CacheFileHeaderV2, DataSource, FileRecord, FuncRecord, LineRecord, Seg};
+use crate::pdb::PdbInfo;
+use crate::types::{
+    CacheFileHeaderV3, DataSource, FileRecordV3, FuncRecord, FuncRecordV3, LineRecordV3,
+    NameRecordV3, Seg,
+};
 use crate::utils::shorten_filename;
 
 /// Given a writer and object, dumps the object into the writer.
 ///
 /// In case a symcache is to be constructed from memory the `SymCache::from_object`
-/// method can be used instead.
+/// method can be used instead. Use `SymCacheWriter` to pass additional inputs or options.
+///
+/// The output is deterministic: Symbols and files are stored in the order of their first use,
+/// so converting the same object twice yields identical bytes. The header contains a checksum
+/// of the written data, which can be verified with `SymCache::parse_verified`.
+///
+/// This requires the writer to be seekable.
+pub fn to_writer<W: Write + Seek>(w: W, obj: &Object<'_>) -> Result<(), SymCacheError> {
+    SymCacheWriter::new().write_object(w, obj)
+}
+
+/// Given a writer and object, dumps the object into the writer.
+///
+/// The object may have been compiled with split DWARF (`-gsplit-dwarf`), in which case its
+/// skeleton units refer to debug information in separate `.dwo` objects or a `.dwp` package.
+/// These objects are passed in `split` and matched up with the skeleton units by their DWO id.
+///
+/// This requires the writer to be seekable.
+pub fn to_writer_with_split_dwarf<W: Write + Seek>(
+    w: W,
+    obj: &Object<'_>,
+    split: &[Object<'_>],
+) -> Result<(), SymCacheError> {
+    SymCacheWriter::new()
+        .split_dwarf(split)
+        .write_object(w, obj)
+}
+
+/// Given a writer and object, dumps the object into the writer.
+///
+/// In addition to split DWARF objects (see `to_writer_with_split_dwarf`), this accepts the
+/// supplementary object of debug files processed with `dwz`. Such files reference shared entries
+/// and strings in the supplementary object, which is declared in their `.gnu_debugaltlink`
+/// section.
+///
+/// This requires the writer to be seekable.
+pub fn to_writer_with_supplementary<W: Write + Seek>(
+    w: W,
+    obj: &Object<'_>,
+    split: &[Object<'_>],
+    sup: Option<&Object<'_>>,
+) -> Result<(), SymCacheError> {
+    let mut writer = SymCacheWriter::new().split_dwarf(split);
+    if let Some(sup) = sup {
+        writer = writer.supplementary(sup);
+    }
+    writer.write_object(w, obj)
+}
+
+/// Given a writer and object, dumps the object into the writer with bounded memory usage.
+///
+/// See `SymCacheWriter::streaming` for more information.
 ///
 /// This requires the writer to be seekable.
-pub fn to_writer<W: Write + Seek>(mut w: W, obj: &Object<'_>) -> Result<(), SymCacheError> {
-    SymCacheWriter::new(&mut w).write_object(obj)
+pub fn to_writer_streaming<W: Write + Seek>(w: W, obj: &Object<'_>) -> Result<(), SymCacheError> {
+    SymCacheWriter::new().streaming(true).write_object(w, obj)
+}
+
+/// Given a writer and multiple objects of the same module, merges them into the writer.
+///
+/// See `SymCacheWriter::write_objects` for more information.
+///
+/// This requires the writer to be seekable.
+pub fn to_writer_merged<W: Write + Seek>(
+    w: W,
+    objects: &[Object<'_>],
+) -> Result<(), SymCacheError> {
+    SymCacheWriter::new().write_objects(w, objects)
 }
 
 /// Converts an object into a vector of symcache data.
 pub fn to_vec(obj: &Object<'_>) -> Result<Vec<u8>, SymCacheError> {
+    SymCacheWriter::new().object_to_vec(obj)
+}
+
+/// Converts an object and its split DWARF objects into a vector of symcache data.
+///
+/// See `to_writer_with_split_dwarf` for more information.
+pub fn to_vec_with_split_dwarf(
+    obj: &Object<'_>,
+    split: &[Object<'_>],
+) -> Result<Vec<u8>, SymCacheError> {
+    SymCacheWriter::new().split_dwarf(split).object_to_vec(obj)
+}
+
+/// Converts an object, its split DWARF objects and its supplementary object into a vector of
+/// symcache data.
+///
+/// See `to_writer_with_supplementary` for more information.
+pub fn to_vec_with_supplementary(
+    obj: &Object<'_>,
+    split: &[Object<'_>],
+    sup: Option<&Object<'_>>,
+) -> Result<Vec<u8>, SymCacheError> {
     let mut cursor = Cursor::new(Vec::new());
-    SymCacheWriter::new(&mut cursor).write_object(obj)?;
+    to_writer_with_supplementary(&mut cursor, obj, split, sup)?;
     Ok(cursor.into_inner())
 }
 
+/// Merges multiple objects of the same module into a vector of symcache data.
+///
+/// See `SymCacheWriter::write_objects` for more information.
+pub fn to_vec_merged(objects: &[Object<'_>]) -> Result<Vec<u8>, SymCacheError> {
+    SymCacheWriter::new().objects_to_vec(objects)
+}
+
+/// Converts objects into symcaches with additional inputs and options.
+///
+/// All options apply to both a single object written with `write_object` and multiple objects
+/// merged with `write_objects`.
+///
+/// ```no_run
+/// # use symbolic_common::byteview::ByteView;
+/// # use symbolic_debuginfo::FatObject;
+/// # use symbolic_symcache::{SymCacheError, SymCacheWriter};
+/// # fn main() -> Result<(), SymCacheError> {
+/// let buffer = ByteView::from_path("main.debug").unwrap();
+/// let fat = FatObject::parse(buffer).unwrap();
+/// let object = fat.get_object(0).unwrap().unwrap();
+///
+/// let file = std::fs::File::create("main.symc").unwrap();
+/// SymCacheWriter::new().streaming(true).write_object(file, &object)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SymCacheWriter<'d, 'o> {
+    split: &'d [Object<'o>],
+    sup: Option<&'d Object<'o>>,
+    streaming: bool,
+}
+
+impl<'d, 'o> SymCacheWriter<'d, 'o> {
+    /// Creates a writer with default options.
+    pub fn new() -> Self {
+        SymCacheWriter {
+            split: &[],
+            sup: None,
+            streaming: false,
+        }
+    }
+
+    /// Sets the split DWARF objects of the converted objects.
+    ///
+    /// Objects compiled with split DWARF (`-gsplit-dwarf`) contain skeleton units that refer to
+    /// debug information in separate `.dwo` objects or a `.dwp` package. These objects are matched
+    /// up with the skeleton units by their DWO id.
+    pub fn split_dwarf(mut self, split: &'d [Object<'o>]) -> Self {
+        self.split = split;
+        self
+    }
+
+    /// Sets the supplementary object of the converted objects.
+    ///
+    /// Debug files processed with `dwz` reference shared entries and strings in a supplementary
+    /// object, which is declared in their `.gnu_debugaltlink` section.
+    pub fn supplementary(mut self, sup: &'d Object<'o>) -> Self {
+        self.sup = Some(sup);
+        self
+    }
+
+    /// Bounds memory usage when converting large DWARF debug information.
+    ///
+    /// Compilation units are processed one at a time. After each unit, its function records,
+    /// symbols and files are moved to temporary files and copied into the writer once all units
+    /// have been converted. Therefore, peak memory usage is proportional to the largest
+    /// compilation unit rather than the entire object.
+    ///
+    /// This comes at a cost: Symbols and files shared between compilation units are written once
+    /// per unit, and the name index for `SymCache::lookup_name` is merged from sorted runs in a
+    /// temporary file. Other debug information formats are converted as usual.
+    ///
+    /// Merging multiple objects requires all of their function records in memory, so
+    /// `write_objects` fails with `UnsupportedStreaming` if this option is set.
+    pub fn streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    /// Given a writer and object, dumps the object into the writer.
+    ///
+    /// This requires the writer to be seekable.
+    pub fn write_object<W: Write + Seek>(
+        &self,
+        mut w: W,
+        obj: &Object<'_>,
+    ) -> Result<(), SymCacheError> {
+        CacheWriter::new(&mut w, self.streaming).write_object(obj, self.split, self.sup)
+    }
+
+    /// Given a writer and multiple objects of the same module, merges them into the writer.
+    ///
+    /// This combines debug information from different sources, for instance a DWARF debug file
+    /// with partial coverage and a Breakpad symbol file, or the symbol table of an executable and
+    /// its separate debug file. All objects must have the same debug identifier.
+    ///
+    /// Objects are passed in descending order of precedence. Functions of an object are only
+    /// retained if they do not overlap with functions of a preceding object. The data source of
+    /// each function is recorded in the symcache and can be retrieved via
+    /// `Function::data_source`.
+    ///
+    /// This requires the writer to be seekable.
+    pub fn write_objects<W: Write + Seek>(
+        &self,
+        mut w: W,
+        objects: &[Object<'_>],
+    ) -> Result<(), SymCacheError> {
+        CacheWriter::new(&mut w, self.streaming).write_objects(objects, self.split, self.sup)
+    }
+
+    /// Converts an object into a vector of symcache data.
+    ///
+    /// See `write_object` for more information.
+    pub fn object_to_vec(&self, obj: &Object<'_>) -> Result<Vec<u8>, SymCacheError> {
+        let mut cursor = Cursor::new(Vec::new());
+        self.write_object(&mut cursor, obj)?;
+        Ok(cursor.into_inner())
+    }
+
+    /// Merges multiple objects of the same module into a vector of symcache data.
+    ///
+    /// See `write_objects` for more information.
+    pub fn objects_to_vec(&self, objects: &[Object<'_>]) -> Result<Vec<u8>, SymCacheError> {
+        let mut cursor = Cursor::new(Vec::new());
+        self.write_objects(&mut cursor, objects)?;
+        Ok(cursor.into_inner())
+    }
+}
+
+/// Returns the address range of a function record for overlap checks.
+///
+/// Functions from symbol tables might have an unknown size, in which case only their start
+/// address is considered.
+fn function_range(record: &FuncRecordV3) -> (u64, u64) {
+    let addr = FuncRecord::from(*record).addr_start();
+    let len = match record.len {
+        0 | u32::MAX => 1,
+        len => u64::from(len),
+    };
+
+    (addr, addr + len)
+}
+
+/// Appends a line record at the given address offset from the previous record.
+///
+/// Offsets exceeding the range of `LineRecordV3::addr_off` are split into multiple records. The
+/// leading records repeat the location of the previous record, so that lookups in the gap still
+/// resolve to the previous line.
+fn push_line_record(records: &mut Vec<LineRecordV3>, mut diff: u64, file_id: u32, line: u32) {
+    while diff > u64::from(u16::MAX) {
+        let (prev_file_id, prev_line) = records
+            .last()
+            .map_or((file_id, line), |record| (record.file_id, record.line));
+
+        records.push(LineRecordV3 {
+            addr_off: u16::MAX,
+            file_id: prev_file_id,
+            line: prev_line,
+        });
+
+        diff -= u64::from(u16::MAX);
+    }
+
+    records.push(LineRecordV3 {
+        addr_off: diff as u16,
+        file_id,
+        line,
+    });
+}
+
+/// A temporary file that is removed when dropped.
+struct TempFile {
+    path: PathBuf,
+    file: File,
+}
+
+impl TempFile {
+    fn new() -> io::Result<TempFile> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let name = format!(
+            "symcache-{}-{}.tmp",
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let path = env::temp_dir().join(name);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+
+        Ok(TempFile { path, file })
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+/// Leading records of a segment that have been moved from memory to a temporary file.
+#[derive(Default)]
+struct Spill {
+    temp: Option<TempFile>,
+    len: usize,
+}
+
+impl Spill {
+    /// Appends records to the temporary file, creating it if necessary.
+    fn append<T>(&mut self, items: &[T]) -> io::Result<()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        if self.temp.is_none() {
+            self.temp = Some(TempFile::new()?);
+        }
+
+        if let Some(ref mut temp) = self.temp {
+            let bytes = unsafe {
+                slice::from_raw_parts(items.as_ptr() as *const u8, mem::size_of_val(items))
+            };
+            temp.file.write_all(bytes)?;
+        }
+
+        self.len += items.len();
+        Ok(())
+    }
+}
+
+/// Sorted runs of name index entries that have been moved from memory to a temporary file.
+///
+/// Every spill appends one run, which are merged into the final name index once all compilation
+/// units have been written. Each entry is stored as the length of the name, the function ID and
+/// the name itself.
+#[derive(Default)]
+struct NameRuns {
+    temp: Option<TempFile>,
+    runs: Vec<Range<u64>>,
+    len: u64,
+}
+
+impl NameRuns {
+    /// Appends a sorted run of entries to the temporary file, creating it if necessary.
+    fn append(&mut self, entries: &[(String, u32)]) -> io::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        if self.temp.is_none() {
+            self.temp = Some(TempFile::new()?);
+        }
+
+        if let Some(ref mut temp) = self.temp {
+            let mut buf = vec![];
+            for (name, func_id) in entries {
+                buf.extend_from_slice(&(name.len() as u32).to_ne_bytes());
+                buf.extend_from_slice(&func_id.to_ne_bytes());
+                buf.extend_from_slice(name.as_bytes());
+            }
+
+            temp.file.seek(SeekFrom::Start(self.len))?;
+            temp.file.write_all(&buf)?;
+            self.runs.push(self.len..self.len + buf.len() as u64);
+            self.len += buf.len() as u64;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the entries of a single run in `NameRuns`.
+struct NameRunReader {
+    range: Range<u64>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl NameRunReader {
+    fn new(range: Range<u64>) -> Self {
+        NameRunReader {
+            range,
+            buf: vec![],
+            pos: 0,
+        }
+    }
+
+    /// Makes sure that at least `len` bytes are buffered, reading from the file if necessary.
+    fn fill(&mut self, mut file: &File, len: usize) -> io::Result<()> {
+        if self.buf.len() - self.pos >= len {
+            return Ok(());
+        }
+
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+
+        let remaining = (self.range.end - self.range.start) as usize;
+        let read = cmp::min(cmp::max(len - self.buf.len(), 4096), remaining);
+        let offset = self.buf.len();
+        self.buf.resize(offset + read, 0);
+
+        file.seek(SeekFrom::Start(self.range.start))?;
+        file.read_exact(&mut self.buf[offset..])?;
+        self.range.start += read as u64;
+        Ok(())
+    }
+
+    /// Reads the next entry of this run.
+    fn next(&mut self, file: &File) -> io::Result<Option<(String, u32)>> {
+        if self.pos == self.buf.len() && self.range.start == self.range.end {
+            return Ok(None);
+        }
+
+        self.fill(file, 8)?;
+        let mut len = [0; 4];
+        let mut func_id = [0; 4];
+        len.copy_from_slice(&self.buf[self.pos..self.pos + 4]);
+        func_id.copy_from_slice(&self.buf[self.pos + 4..self.pos + 8]);
+        self.pos += 8;
+
+        let len = u32::from_ne_bytes(len) as usize;
+        self.fill(file, len)?;
+        let name = String::from_utf8_lossy(&self.buf[self.pos..self.pos + len]).into_owned();
+        self.pos += len;
+
+        Ok(Some((name, u32::from_ne_bytes(func_id))))
+    }
+}
+
+/// Parses the functions of a single compilation unit.
+///
+/// Units that do not carry line information yield no functions.
+fn parse_unit<'a>(
+    info: &DwarfInfo<'a>,
+    index: usize,
+    symbols: Option<&'a Symbols<'a>>,
+) -> Result<Vec<Function<'a>>, SymCacheError> {
+    let mut funcs = vec![];
+    if let Some(unit) = Unit::parse(info, index)? {
+        unit.get_functions(info, &mut Vec::new(), symbols, &mut funcs)?;
+    }
+    Ok(funcs)
+}
+
+/// The number of units parsed at once before their functions are written.
+#[cfg(not(feature = "with_rayon"))]
+fn unit_batch_size() -> usize {
+    1
+}
+
+/// The number of units parsed at once before their functions are written.
+///
+/// Batches are kept small to bound the memory held by parsed functions.
+#[cfg(feature = "with_rayon")]
+fn unit_batch_size() -> usize {
+    rayon::current_num_threads() * 4
+}
+
+/// Parses a batch of units, returning their functions in unit order.
+#[cfg(not(feature = "with_rayon"))]
+fn parse_units<'a>(
+    info: &DwarfInfo<'a>,
+    units: Range<usize>,
+    symbols: Option<&'a Symbols<'a>>,
+) -> Result<Vec<Vec<Function<'a>>>, SymCacheError> {
+    units
+        .map(|index| parse_unit(info, index, symbols))
+        .collect()
+}
+
+/// Parses a batch of units in parallel, returning their functions in unit order.
+///
+/// Errors are reported for the first failing unit, just like in sequential parsing.
+#[cfg(feature = "with_rayon")]
+fn parse_units<'a>(
+    info: &DwarfInfo<'a>,
+    units: Range<usize>,
+    symbols: Option<&'a Symbols<'a>>,
+) -> Result<Vec<Vec<Function<'a>>>, SymCacheError> {
+    use rayon::prelude::*;
+
+    let results: Vec<_> = units
+        .into_par_iter()
+        .map(|index| parse_unit(info, index, symbols))
+        .collect();
+
+    results.into_iter().collect()
+}
+
 #[derive(Debug)]
 enum DebugInfo<'input> {
     Dwarf(DwarfInfo<'input>),
     Breakpad(BreakpadInfo<'input>),
+    Pdb(PdbInfo),
 }
 
 impl<'input> DebugInfo<'input> {
-    pub fn from_object(object: &'input Object<'_>) -> Result<DebugInfo<'input>, SymCacheError> {
+    pub fn from_object(
+        object: &'input Object<'_>,
+        split: &'input [Object<'_>],
+        sup: Option<&'input Object<'_>>,
+    ) -> Result<DebugInfo<'input>, SymCacheError> {
         Ok(match object.debug_kind() {
-            Some(DebugKind::Dwarf) => DebugInfo::Dwarf(DwarfInfo::from_object(object)?),
+            Some(DebugKind::Dwarf) => DebugInfo::Dwarf(DwarfInfo::from_object(object, split, sup)?),
             Some(DebugKind::Breakpad) => DebugInfo::Breakpad(BreakpadInfo::from_object(object)?),
+            Some(DebugKind::Pdb) => DebugInfo::Pdb(PdbInfo::from_object(object)?),
             // Add this when more object kinds are added in symbolic_debuginfo:
             // Some(_) => return Err(SymCacheErrorKind::UnsupportedDebugKind.into()),
             None => return Err(SymCacheErrorKind::MissingDebugInfo.into()),
@@ -55,30 +552,44 @@ impl<'input> DebugInfo<'input> {
     }
 }
 
-struct SymCacheWriter<W: Write> {
+struct CacheWriter<W: Write> {
     writer: RefCell<(u64, W)>,
-    header: CacheFileHeaderV2,
-    symbol_map: HashMap<Vec<u8>, u32>,
+    checksum: RefCell<FnvHasher>,
+    header: CacheFileHeaderV3,
+    symbol_map: FnvHashMap<Vec<u8>, u32>,
     symbols: Vec<Seg<u8, u16>>,
-    files: HashMap<Vec<u8>, Seg<u8, u8>>,
-    file_record_map: HashMap<FileRecord, u16>,
-    file_records: Vec<FileRecord>,
-    func_records: Vec<FuncRecord>,
+    files: FnvHashMap<Vec<u8>, Seg<u8, u16>>,
+    file_record_map: FnvHashMap<FileRecordV3, u32>,
+    file_records: Vec<FileRecordV3>,
+    func_records: Vec<FuncRecordV3>,
+    func_sources: Vec<u8>,
     line_record_bytes: RefCell<u64>,
+    streaming: bool,
+    spilled_symbols: Spill,
+    spilled_file_records: Spill,
+    spilled_func_records: Spill,
+    spilled_names: NameRuns,
 }
 
-impl<W: Write + Seek> SymCacheWriter<W> {
-    pub fn new(writer: W) -> SymCacheWriter<W> {
-        SymCacheWriter {
+impl<W: Write + Seek> CacheWriter<W> {
+    pub fn new(writer: W, streaming: bool) -> CacheWriter<W> {
+        CacheWriter {
             writer: RefCell::new((0, writer)),
+            checksum: RefCell::new(FnvHasher::default()),
             header: Default::default(),
-            symbol_map: HashMap::new(),
+            symbol_map: FnvHashMap::default(),
             symbols: vec![],
-            files: HashMap::new(),
-            file_record_map: HashMap::new(),
+            files: FnvHashMap::default(),
+            file_record_map: FnvHashMap::default(),
             file_records: vec![],
             func_records: vec![],
+            func_sources: vec![],
             line_record_bytes: RefCell::new(0),
+            streaming,
+            spilled_symbols: Spill::default(),
+            spilled_file_records: Spill::default(),
+            spilled_func_records: Spill::default(),
+            spilled_names: NameRuns::default(),
         }
     }
 
@@ -93,6 +604,7 @@ impl<W: Write + Seek> SymCacheWriter<W> {
         writer
             .write_all(bytes)
             .context(SymCacheErrorKind::WriteFailed)?;
+        self.checksum.borrow_mut().write(bytes);
 
         Ok(Seg::new(
             offset as u32,
@@ -134,6 +646,92 @@ impl<W: Write + Seek> SymCacheWriter<W> {
         ))
     }
 
+    /// Writes a segment, including leading records that have been spilled to a temporary file.
+    fn write_spilled_seg<T, L>(
+        &self,
+        spill: &Spill,
+        x: &[T],
+        kind: ValueKind,
+    ) -> Result<Seg<T, L>, SymCacheError>
+    where
+        L: Copy + num::FromPrimitive,
+    {
+        let mut file = match spill.temp {
+            Some(ref temp) => &temp.file,
+            None => return self.write_seg(x, kind),
+        };
+
+        let offset = self.writer.borrow().0;
+        file.seek(SeekFrom::Start(0))
+            .context(SymCacheErrorKind::WriteFailed)?;
+
+        let mut buf = [0; 8192];
+        loop {
+            let read = file
+                .read(&mut buf)
+                .context(SymCacheErrorKind::WriteFailed)?;
+            if read == 0 {
+                break;
+            }
+            self.write_bytes::<u32>(&buf[..read], kind)?;
+        }
+
+        self.write_seg::<T, u32>(x, kind)?;
+
+        Ok(Seg::new(
+            offset as u32,
+            num::FromPrimitive::from_usize(spill.len + x.len())
+                .ok_or(SymCacheErrorKind::TooManyValues(kind))?,
+        ))
+    }
+
+    /// The total number of symbols, including spilled ones.
+    fn symbol_count(&self) -> usize {
+        self.spilled_symbols.len + self.symbols.len()
+    }
+
+    /// The total number of file records, including spilled ones.
+    fn file_record_count(&self) -> usize {
+        self.spilled_file_records.len + self.file_records.len()
+    }
+
+    /// The total number of function records, including spilled ones.
+    fn func_record_count(&self) -> usize {
+        self.spilled_func_records.len + self.func_records.len()
+    }
+
+    /// Moves the records of all completed compilation units to temporary files.
+    ///
+    /// This also clears the maps used to deduplicate symbols and files, so that memory usage does
+    /// not grow with the number of units.
+    fn spill_records(&mut self) -> Result<(), SymCacheError> {
+        // the name index entries require the symbol map, which is cleared below
+        let names = self.name_entries();
+        self.spilled_names
+            .append(&names)
+            .context(SymCacheErrorKind::WriteFailed)?;
+
+        self.spilled_symbols
+            .append(&self.symbols)
+            .context(SymCacheErrorKind::WriteFailed)?;
+        self.symbols.clear();
+        self.symbol_map.clear();
+
+        self.spilled_file_records
+            .append(&self.file_records)
+            .context(SymCacheErrorKind::WriteFailed)?;
+        self.file_records.clear();
+        self.file_record_map.clear();
+        self.files.clear();
+
+        self.spilled_func_records
+            .append(&self.func_records)
+            .context(SymCacheErrorKind::WriteFailed)?;
+        self.func_records.clear();
+
+        Ok(())
+    }
+
     fn write_symbol_if_missing(&mut self, mut sym: &[u8]) -> Result<u32, SymCacheError> {
         if sym.len() > u16::MAX.into() {
             sym = &sym[..u16::MAX.into()];
@@ -143,11 +741,12 @@ impl<W: Write + Seek> SymCacheWriter<W> {
             return Ok(index);
         }
 
-        if self.symbols.len() >= 0x00ff_ffff {
+        // The maximum value is reserved for functions without a symbol.
+        if self.symbol_count() >= u32::MAX as usize {
             return Err(SymCacheErrorKind::TooManyValues(ValueKind::Symbol).into());
         }
 
-        let idx = self.symbols.len() as u32;
+        let idx = self.symbol_count() as u32;
         let seg = self.write_bytes(sym, ValueKind::Symbol)?;
         self.symbols.push(seg);
         self.symbol_map.insert(sym.to_owned(), idx);
@@ -156,11 +755,11 @@ impl<W: Write + Seek> SymCacheWriter<W> {
     }
 
     #[inline]
-    fn write_file_if_missing(&mut self, filename: &[u8]) -> Result<Seg<u8, u8>, SymCacheError> {
-        // since we store the filename in a u8 segment we are limited to a total
-        // length of 255 characters.
+    fn write_file_if_missing(&mut self, filename: &[u8]) -> Result<Seg<u8, u16>, SymCacheError> {
+        // since we store the filename in a u16 segment we are limited to a total
+        // length of 65535 bytes.
         let filename_unicode = String::from_utf8_lossy(filename);
-        let filename = shorten_filename(&filename_unicode, 255);
+        let filename = shorten_filename(&filename_unicode, u16::MAX.into());
         if let Some(item) = self.files.get(filename.as_bytes()) {
             return Ok(*item);
         }
@@ -170,22 +769,28 @@ impl<W: Write + Seek> SymCacheWriter<W> {
         Ok(seg)
     }
 
-    fn write_file_record_if_missing(&mut self, record: FileRecord) -> Result<u16, SymCacheError> {
+    fn write_file_record_if_missing(&mut self, record: FileRecordV3) -> Result<u32, SymCacheError> {
         if let Some(idx) = self.file_record_map.get(&record) {
             return Ok(*idx);
         }
 
-        if self.file_records.len() >= 0xffff {
+        if self.file_record_count() >= u32::MAX as usize {
             return Err(SymCacheErrorKind::TooManyValues(ValueKind::File).into());
         }
 
-        let idx = self.file_records.len() as u16;
+        let idx = self.file_record_count() as u32;
         self.file_record_map.insert(record, idx);
         self.file_records.push(record);
         Ok(idx)
     }
 
     fn write_header(&mut self) -> Result<(), SymCacheError> {
+        // the checksum covers the entire file, with the header hashed after all other data
+        self.header.checksum = 0;
+        let mut hasher = FnvHasher::with_key(self.checksum.borrow().finish());
+        hasher.write(self.header.as_bytes());
+        self.header.checksum = hasher.finish();
+
         let (ref mut pos, ref mut writer) = *self.writer.borrow_mut();
         writer
             .seek(SeekFrom::Start(0))
@@ -200,17 +805,32 @@ impl<W: Write + Seek> SymCacheWriter<W> {
         Ok(())
     }
 
-    pub fn write_debug_info(&mut self, obj: &Object<'_>) -> Result<(), SymCacheError> {
+    pub fn write_debug_info(
+        &mut self,
+        obj: &Object<'_>,
+        split: &[Object<'_>],
+        sup: Option<&Object<'_>>,
+    ) -> Result<(), SymCacheError> {
+        // the symbol table needs to outlive the debug info, since dwarf
+        // function names may be borrowed from either.
+        let symbols = obj.symbols().unwrap_or(None);
+
         // try dwarf data first.  If we cannot find the necessary dwarf sections
         // we just skip over to symbol table processing.
-        match DebugInfo::from_object(obj) {
+        match DebugInfo::from_object(obj, split, sup) {
             Ok(DebugInfo::Dwarf(ref info)) => {
-                return self.write_dwarf_info(info, obj.symbols().unwrap_or(None).as_ref());
+                return self.write_dwarf_info(info, symbols.as_ref());
             }
             Ok(DebugInfo::Breakpad(ref info)) => {
                 return self.write_breakpad_info(info);
             }
-            Err(ref e) if e.kind() == SymCacheErrorKind::MissingDebugSection => {
+            Ok(DebugInfo::Pdb(ref info)) => {
+                return self.write_pdb_info(info);
+            }
+            Err(ref e)
+                if e.kind() == SymCacheErrorKind::MissingDebugSection
+                    || e.kind() == SymCacheErrorKind::MissingDebugInfo =>
+            {
                 // ignore missing sections and try the symbol table
             }
             Err(e) => {
@@ -234,7 +854,12 @@ impl<W: Write + Seek> SymCacheWriter<W> {
         Err(SymCacheErrorKind::MissingDebugInfo.into())
     }
 
-    pub fn write_object(mut self, obj: &Object<'_>) -> Result<(), SymCacheError> {
+    pub fn write_object(
+        mut self,
+        obj: &Object<'_>,
+        split: &[Object<'_>],
+        sup: Option<&Object<'_>>,
+    ) -> Result<(), SymCacheError> {
         // reserve space for the header before writing segments
         self.write_header()?;
 
@@ -247,13 +872,265 @@ impl<W: Write + Seek> SymCacheWriter<W> {
         }
 
         // do the actual work
-        self.write_debug_info(obj)?;
+        self.write_debug_info(obj, split, sup)?;
+        self.write_segments()?;
+
+        self.write_name_index()?;
+
+        // once done, patch the header
+        self.write_header()?;
+        Ok(())
+    }
+
+    /// Writes the symbols, files and function records collected from the debug info.
+    fn write_segments(&mut self) -> Result<(), SymCacheError> {
+        self.header.symbols =
+            self.write_spilled_seg(&self.spilled_symbols, &self.symbols, ValueKind::Symbol)?;
+        self.header.files = self.write_spilled_seg(
+            &self.spilled_file_records,
+            &self.file_records,
+            ValueKind::File,
+        )?;
+        self.header.function_records = self.write_spilled_seg(
+            &self.spilled_func_records,
+            &self.func_records,
+            ValueKind::Function,
+        )?;
+        self.header.function_sources = self.write_seg(&self.func_sources, ValueKind::Function)?;
+        Ok(())
+    }
+
+    pub fn write_objects(
+        mut self,
+        objects: &[Object<'_>],
+        split: &[Object<'_>],
+        sup: Option<&Object<'_>>,
+    ) -> Result<(), SymCacheError> {
+        // merging requires all function records in memory
+        if self.streaming {
+            return Err(SymCacheErrorKind::UnsupportedStreaming.into());
+        }
+
+        let mut ids = objects.iter().filter_map(|obj| obj.id());
+        let id = ids.next();
+        if ids.any(|other| Some(other) != id) {
+            return Err(SymCacheErrorKind::MismatchedDebugId.into());
+        }
+
+        // reserve space for the header before writing segments
+        self.write_header()?;
+
+        // set up common header values
+        self.header.preamble.magic = SYMCACHE_MAGIC;
+        self.header.preamble.version = SYMCACHE_LATEST_VERSION;
+        if let Some(obj) = objects.first() {
+            self.header.arch = obj.arch().unwrap_or_default() as u32;
+        }
+        if let Some(id) = id {
+            self.header.id = id;
+        }
+
+        // collect the functions of every object, skipping objects without debug information
+        let mut sources = vec![];
+        for obj in objects {
+            let start = self.func_records.len();
+            match self.write_debug_info(obj, split, sup) {
+                Ok(()) => sources.push((start..self.func_records.len(), self.header.data_source)),
+                Err(ref e) if e.kind() == SymCacheErrorKind::MissingDebugInfo => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        // the header reports the data source of the object with the highest precedence
+        match sources.first() {
+            Some(&(_, data_source)) => self.header.data_source = data_source,
+            None => return Err(SymCacheErrorKind::MissingDebugInfo.into()),
+        }
+
+        self.merge_functions(&sources);
+        self.write_segments()?;
+        self.write_name_index()?;
 
         // once done, patch the header
         self.write_header()?;
         Ok(())
     }
 
+    /// Merges the function records collected from multiple objects.
+    ///
+    /// Each entry in `sources` contains the range of function records of one object and its data
+    /// source in descending order of precedence. A function is retained along with its inlinees
+    /// if it does not overlap with any function of a preceding object. Afterwards, all functions
+    /// are sorted by address.
+    fn merge_functions(&mut self, sources: &[(Range<usize>, u8)]) {
+        // sorted and disjoint address ranges covered by preceding objects
+        let mut covered: Vec<(u64, u64)> = vec![];
+        let mut trees = vec![];
+
+        for &(ref range, data_source) in sources {
+            let mut ranges = vec![];
+            let mut start = range.start;
+
+            while start < range.end {
+                // inlinees directly follow their top-level function
+                let mut end = start + 1;
+                while end < range.end && self.func_records[end].parent_offset != !0 {
+                    end += 1;
+                }
+
+                let (addr_start, addr_end) = function_range(&self.func_records[start]);
+                let idx = covered.partition_point(|&(_, covered_end)| covered_end <= addr_start);
+                let overlaps = match covered.get(idx) {
+                    Some(&(covered_start, _)) => covered_start < addr_end,
+                    None => false,
+                };
+
+                if !overlaps {
+                    trees.push((addr_start, start..end, data_source));
+                    ranges.push((addr_start, addr_end));
+                }
+
+                start = end;
+            }
+
+            ranges.extend_from_slice(&covered);
+            ranges.sort();
+            covered.clear();
+            for (range_start, range_end) in ranges {
+                match covered.last_mut() {
+                    Some(last) if last.1 >= range_start => last.1 = cmp::max(last.1, range_end),
+                    _ => covered.push((range_start, range_end)),
+                }
+            }
+        }
+
+        // the sort is stable, so functions at the same address retain their precedence
+        trees.sort_by_key(|&(addr, _, _)| addr);
+
+        let mut func_records = Vec::with_capacity(self.func_records.len());
+        let mut func_sources = Vec::with_capacity(self.func_records.len());
+        for (_, range, data_source) in trees {
+            func_sources.resize(func_sources.len() + range.len(), data_source);
+            func_records.extend_from_slice(&self.func_records[range]);
+        }
+
+        self.func_records = func_records;
+        self.func_sources = func_sources;
+    }
+
+    /// Returns the demangled names and IDs of all function records held in memory, sorted by
+    /// name.
+    ///
+    /// The names are demangled the same way as `Function::function_name`. Functions without a
+    /// symbol are skipped.
+    fn name_entries(&self) -> Vec<(String, u32)> {
+        // Symbols and function records held in memory follow the spilled ones. Since the symbol
+        // map is cleared on every spill, functions only refer to symbols held in memory.
+        let symbol_offset = self.spilled_symbols.len;
+        let mut symbols = vec![""; self.symbols.len()];
+        for (symbol, &index) in &self.symbol_map {
+            symbols[index as usize - symbol_offset] = str::from_utf8(symbol).unwrap_or("?");
+        }
+
+        let mut names = FnvHashMap::default();
+        let mut entries = Vec::with_capacity(self.func_records.len());
+        for (index, record) in self.func_records.iter().enumerate() {
+            let symbol_id = record.symbol_id;
+            let symbol = match (symbol_id as usize)
+                .checked_sub(symbol_offset)
+                .and_then(|index| symbols.get(index))
+            {
+                Some(symbol) => *symbol,
+                None => continue,
+            };
+
+            let lang = record.lang;
+            let name = names.entry((symbol_id, lang)).or_insert_with(|| {
+                let lang = Language::from_u32(lang.into()).unwrap_or(Language::Unknown);
+                Name::with_language(symbol, lang).try_demangle(Default::default())
+            });
+
+            let func_id = self.spilled_func_records.len + index;
+            entries.push((name.clone(), func_id as u32));
+        }
+
+        entries.sort();
+        entries
+    }
+
+    /// Writes function IDs sorted by their demangled names.
+    ///
+    /// `SymCache::lookup_name` uses this index for a binary search. When streaming, the entries
+    /// of spilled function records are merged from their sorted runs in the temporary file.
+    fn write_name_index(&mut self) -> Result<(), SymCacheError> {
+        let entries = self.name_entries();
+        if self.spilled_names.temp.is_some() {
+            // the remaining function records form the last run
+            self.spilled_names
+                .append(&entries)
+                .context(SymCacheErrorKind::WriteFailed)?;
+        }
+
+        let file = match self.spilled_names.temp {
+            Some(ref temp) => &temp.file,
+            None => {
+                let records: Vec<_> = entries
+                    .into_iter()
+                    .map(|(_, func_id)| NameRecordV3 { func_id })
+                    .collect();
+                self.header.name_index = self.write_seg(&records, ValueKind::Function)?;
+                return Ok(());
+            }
+        };
+
+        let mut readers: Vec<_> = self
+            .spilled_names
+            .runs
+            .iter()
+            .cloned()
+            .map(NameRunReader::new)
+            .collect();
+
+        // k-way merge of all runs, yielding the same order as sorting all entries at once
+        let mut heap = BinaryHeap::new();
+        for (run, reader) in readers.iter_mut().enumerate() {
+            if let Some((name, func_id)) =
+                reader.next(file).context(SymCacheErrorKind::WriteFailed)?
+            {
+                heap.push(Reverse((name, func_id, run)));
+            }
+        }
+
+        let offset = self.writer.borrow().0;
+        let mut count = 0usize;
+        let mut records = Vec::with_capacity(1024);
+        while let Some(Reverse((_, func_id, run))) = heap.pop() {
+            records.push(NameRecordV3 { func_id });
+            if records.len() == records.capacity() {
+                self.write_seg::<_, u32>(&records, ValueKind::Function)?;
+                count += records.len();
+                records.clear();
+            }
+
+            if let Some((name, func_id)) = readers[run]
+                .next(file)
+                .context(SymCacheErrorKind::WriteFailed)?
+            {
+                heap.push(Reverse((name, func_id, run)));
+            }
+        }
+
+        self.write_seg::<_, u32>(&records, ValueKind::Function)?;
+        count += records.len();
+
+        self.header.name_index = Seg::new(
+            offset as u32,
+            num::FromPrimitive::from_usize(count)
+                .ok_or(SymCacheErrorKind::TooManyValues(ValueKind::Function))?,
+        );
+        Ok(())
+    }
+
     fn write_symbol_table(
         &mut self,
         symbols: SymbolIterator<'_, '_>,
@@ -269,9 +1146,6 @@ impl<W: Write + Seek> SymCacheWriter<W> {
         }
 
         self.header.data_source = DataSource::SymbolTable as u8;
-        self.header.symbols = self.write_seg(&self.symbols, ValueKind::Symbol)?;
-        self.header.function_records = self.write_seg(&self.func_records, ValueKind::Function)?;
-
         Ok(())
     }
 
@@ -318,14 +1192,13 @@ impl<W: Write + Seek> SymCacheWriter<W> {
     {
         let symbol_id = self.write_symbol_if_missing(symbol.as_ref())?;
 
-        self.func_records.push(FuncRecord {
+        self.func_records.push(FuncRecordV3 {
             addr_low: (func_addr & 0xffff_ffff) as u32,
             addr_high: ((func_addr >> 32) & 0xffff) as u16,
             // XXX: we have not seen this yet, but in theory this should be
             // stored as multiple function records.
-            len: cmp::min(len, 0xffff) as u16,
-            symbol_id_low: (symbol_id & 0xffff) as u16,
-            symbol_id_high: ((symbol_id >> 16) & 0xff) as u8,
+            len: cmp::min(len, u32::MAX.into()) as u32,
+            symbol_id,
             parent_offset: !0,
             line_records: Seg::default(),
             comp_dir: Seg::default(),
@@ -342,7 +1215,7 @@ impl<W: Write + Seek> SymCacheWriter<W> {
                 continue;
             }
 
-            let file_record = FileRecord {
+            let file_record = FileRecordV3 {
                 filename: self.write_file_if_missing(file.name)?,
                 base_dir: self.write_file_if_missing(b"")?,
             };
@@ -375,23 +1248,16 @@ impl<W: Write + Seek> SymCacheWriter<W> {
             let mut line_records = vec![];
             let mut last_addr = function.address;
             for line in &function.lines {
-                let mut diff = line.address.saturating_sub(last_addr) as i64;
-                last_addr += diff as u64;
-
-                while diff >= 0 {
-                    let file_id = match file_cache.get(&line.file_id) {
-                        Some(id) => *id,
-                        None => return Err(ConversionError::new("invalid breakpad file id").into()),
-                    };
-
-                    line_records.push(LineRecord {
-                        addr_off: (diff & 0xff) as u8,
-                        file_id,
-                        line: cmp::min(line.line, 0xffff) as u16,
-                    });
-
-                    diff -= 0xff;
-                }
+                let diff = line.address.saturating_sub(last_addr);
+                last_addr += diff;
+
+                let file_id = match file_cache.get(&line.file_id) {
+                    Some(id) => *id,
+                    None => return Err(ConversionError::new("invalid breakpad file id").into()),
+                };
+
+                let line = cmp::min(line.line, u32::MAX.into()) as u32;
+                push_line_record(&mut line_records, diff, file_id, line);
             }
 
             self.func_records[func_id].line_records =
@@ -405,56 +1271,53 @@ impl<W: Write + Seek> SymCacheWriter<W> {
         }
 
         self.header.data_source = DataSource::BreakpadSym as u8;
-        self.header.symbols = self.write_seg(&self.symbols, ValueKind::Symbol)?;
-        self.header.files = self.write_seg(&self.file_records, ValueKind::File)?;
-        self.header.function_records = self.write_seg(&self.func_records, ValueKind::Function)?;
-
         Ok(())
     }
 
-    fn write_dwarf_info(
+    fn write_dwarf_info<'a>(
         &mut self,
-        info: &DwarfInfo<'_>,
-        symbols: Option<&Symbols<'_>>,
+        info: &DwarfInfo<'a>,
+        symbols: Option<&'a Symbols<'a>>,
     ) -> Result<(), SymCacheError> {
-        let mut range_buf = Vec::new();
         let mut symbol_iter = symbols.map(|x| x.iter().peekable());
         let mut last_addr = !0;
         let mut locations = FnvHashSet::default();
         let mut local_cache = FnvHashMap::default();
-        let mut funcs = vec![];
 
-        for index in 0..info.unit_count() {
-            // attempt to parse a single unit from the given header.
-            let unit_opt = Unit::parse(&info, index)?;
-
-            // skip units we don't care about
-            let unit = match unit_opt {
-                Some(unit) => unit,
-                None => continue,
-            };
+        // Units are parsed in batches, potentially in parallel, but always written in their
+        // original order. This keeps the output independent of the number of threads.
+        let unit_count = info.unit_count();
+        // The streaming writer favors memory usage over speed and handles one unit at a time.
+        let batch_size = if self.streaming { 1 } else { unit_batch_size() };
+        let mut start = 0;
+
+        while start < unit_count {
+            let end = cmp::min(start + batch_size, unit_count);
+            for funcs in parse_units(info, start..end, symbols)? {
+                // clear our function local caches and infos
+                locations.clear();
+                local_cache.clear();
+
+                for func in &funcs {
+                    // dedup instructions from inline functions
+                    if let Some(ref mut symbol_iter) = symbol_iter {
+                        self.write_missing_functions_from_symboltable(
+                            &mut last_addr,
+                            func.addr,
+                            info.vmaddr(),
+                            symbol_iter,
+                        )?;
+                    }
+                    self.write_dwarf_function(&func, &mut locations, &mut local_cache, !0)?;
+                    last_addr = func.addr + u64::from(func.len);
+                }
 
-            // clear our function local caches and infos
-            let locations_inner = &mut locations;
-            let local_cache_inner = &mut local_cache;
-            locations_inner.clear();
-            local_cache_inner.clear();
-            funcs.clear();
-
-            unit.get_functions(&info, &mut range_buf, symbols, &mut funcs)?;
-            for func in &funcs {
-                // dedup instructions from inline functions
-                if let Some(ref mut symbol_iter) = symbol_iter {
-                    self.write_missing_functions_from_symboltable(
-                        &mut last_addr,
-                        func.addr,
-                        info.vmaddr(),
-                        symbol_iter,
-                    )?;
+                if self.streaming {
+                    self.spill_records()?;
                 }
-                self.write_dwarf_function(&func, locations_inner, local_cache_inner, !0)?;
-                last_addr = func.addr + u64::from(func.len);
             }
+
+            start = end;
         }
 
         if let Some(ref mut symbol_iter) = symbol_iter {
@@ -467,18 +1330,27 @@ impl<W: Write + Seek> SymCacheWriter<W> {
         }
 
         self.header.data_source = DataSource::Dwarf as u8;
-        self.header.symbols = self.write_seg(&self.symbols, ValueKind::Symbol)?;
-        self.header.files = self.write_seg(&self.file_records, ValueKind::File)?;
-        self.header.function_records = self.write_seg(&self.func_records, ValueKind::Function)?;
+        Ok(())
+    }
 
+    fn write_pdb_info(&mut self, info: &PdbInfo) -> Result<(), SymCacheError> {
+        let mut locations = FnvHashSet::default();
+        let mut local_cache = FnvHashMap::default();
+
+        for func in info.functions() {
+            locations.clear();
+            self.write_dwarf_function(&func, &mut locations, &mut local_cache, !0)?;
+        }
+
+        self.header.data_source = DataSource::Pdb as u8;
         Ok(())
     }
 
     fn write_dwarf_function<'a>(
         &mut self,
         func: &Function<'a>,
-        locations: &mut FnvHashSet<(u64, u16)>,
-        local_cache: &mut FnvHashMap<u64, u16>,
+        locations: &mut FnvHashSet<(u64, u32)>,
+        local_cache: &mut FnvHashMap<u64, u32>,
         parent_id: u32,
     ) -> Result<(), SymCacheError> {
         // if we have a function without any instructions we just skip it.  This
@@ -488,18 +1360,15 @@ impl<W: Write + Seek> SymCacheWriter<W> {
             return Ok(());
         }
 
-        let func_id = self.func_records.len() as u32;
+        let func_id = self.func_record_count() as u32;
         let func_addr = func.get_addr();
 
         let symbol_id = self.write_symbol_if_missing(func.name.as_bytes())?;
-        let func_record = FuncRecord {
+        let func_record = FuncRecordV3 {
             addr_low: (func_addr & 0xffff_ffff) as u32,
             addr_high: ((func_addr >> 32) & 0xffff) as u16,
-            // XXX: we have not seen this yet, but in theory this should be
-            // stored as multiple function records.
-            len: cmp::min(func.len, 0xffff) as u16,
-            symbol_id_low: (symbol_id & 0xffff) as u16,
-            symbol_id_high: ((symbol_id >> 16) & 0xff) as u8,
+            len: func.len,
+            symbol_id,
             parent_offset: if parent_id == !0 {
                 !0
             } else {
@@ -507,7 +1376,7 @@ impl<W: Write + Seek> SymCacheWriter<W> {
                 if parent_offset == !0 {
                     return Err(SymCacheErrorKind::ValueTooLarge(ValueKind::ParentOffset).into());
                 }
-                parent_offset as u16
+                parent_offset
             },
             line_records: Seg::default(),
             comp_dir: self.write_file_if_missing(func.comp_dir)?,
@@ -517,7 +1386,7 @@ impl<W: Write + Seek> SymCacheWriter<W> {
                 func.lang as u8
             },
         };
-        let mut last_addr = func_record.addr_start();
+        let mut last_addr = func_addr;
         self.func_records.push(func_record);
 
         // recurse first.  As we recurse down the address rejection will
@@ -536,7 +1405,7 @@ impl<W: Write + Seek> SymCacheWriter<W> {
             let file_id = if let Some(&x) = local_cache.get(&line.original_file_id) {
                 x
             } else {
-                let file_record = FileRecord {
+                let file_record = FileRecordV3 {
                     filename: self.write_file_if_missing(line.filename)?,
                     base_dir: self.write_file_if_missing(line.base_dir)?,
                 };
@@ -550,25 +1419,17 @@ impl<W: Write + Seek> SymCacheWriter<W> {
             // with highly inlined function calls.  Instead of panicking we want
             // to just assume there is a single record at the address of the function
             // and in case there are more the offsets are just slightly off.
-            let mut diff = (line.addr.saturating_sub(last_addr)) as i64;
-
-            while diff >= 0 {
-                let line_record = LineRecord {
-                    addr_off: (diff & 0xff) as u8,
-                    file_id,
-                    line: line.line,
-                };
-                last_addr += u64::from(line_record.addr_off);
-                line_records.push(line_record);
-                diff -= 0xff;
-            }
+            let diff = line.addr.saturating_sub(last_addr);
+            last_addr += diff;
+            push_line_record(&mut line_records, diff, file_id, line.line);
 
             let mut counter = self.line_record_bytes.borrow_mut();
-            *counter += mem::size_of::<LineRecord>() as u64;
+            *counter += mem::size_of::<LineRecordV3>() as u64;
         }
 
         if !line_records.is_empty() {
-            self.func_records[func_id as usize].line_records =
+            let index = func_id as usize - self.spilled_func_records.len;
+            self.func_records[index].line_records =
                 self.write_seg(&line_records, ValueKind::Line)?;
             self.header.has_line_records = 1;
         }
@@ -22,3 +22,20 @@ fn test_v1() {
         .expect("No functions found for symcache");
     assert_eq!("_mh_execute_header", &function.function_name());
 }
+
+#[test]
+fn test_v2() {
+    let buffer = ByteView::from_path(fixture_path("symcache/compat/v2.symc"))
+        .expect("Could not open symcache");
+    let symcache = SymCache::parse(buffer).expect("Could not load symcache");
+    assert_eq!(symcache.file_format_version().unwrap(), 2);
+
+    // Records of version 1 and 2 are widened transparently
+    let line_infos = symcache.lookup(0x1ec0).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].symbol(), "callback");
+    assert_eq!(line_infos[0].line(), 9);
+    assert_eq!(line_infos[0].filename(), "main.cpp");
+    assert_eq!(line_infos[0].base_dir(), "../linux");
+    assert_eq!(line_infos[0].comp_dir(), "/work/linux");
+}
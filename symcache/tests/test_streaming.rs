@@ -0,0 +1,158 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{self, Cursor, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use symbolic_common::byteview::ByteView;
+use symbolic_debuginfo::FatObject;
+use symbolic_symcache::{to_writer, to_writer_streaming, SymCache};
+use symbolic_testutils::fixture_path;
+
+/// Tracks the current and peak number of bytes allocated on the heap.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// A seekable writer that discards all data, so that the output does not count towards memory.
+#[derive(Default)]
+struct Sink {
+    pos: u64,
+    len: u64,
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pos += buf.len() as u64;
+        self.len = self.len.max(self.pos);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for Sink {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.pos = match pos {
+            SeekFrom::Start(offset) => offset,
+            SeekFrom::End(offset) => (self.len as i64 + offset) as u64,
+            SeekFrom::Current(offset) => (self.pos as i64 + offset) as u64,
+        };
+        Ok(self.pos)
+    }
+}
+
+/// Converts the object and returns the peak heap usage during the conversion in bytes.
+fn measure_peak_memory(path: &str, streaming: bool) -> usize {
+    let buffer = ByteView::from_path(fixture_path(path)).expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+
+    let mut sink = Sink::default();
+    if streaming {
+        to_writer_streaming(&mut sink, &object).expect("Could not write symcache");
+    } else {
+        to_writer(&mut sink, &object).expect("Could not write symcache");
+    }
+
+    PEAK.load(Ordering::SeqCst) - baseline
+}
+
+// All assertions live in a single test, since tests running in parallel would distort the
+// memory measurements of each other.
+#[test]
+fn test_streaming() {
+    let buffer = ByteView::from_path(fixture_path("linux/units/units64.so"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let mut cursor = Cursor::new(Vec::new());
+    to_writer_streaming(&mut cursor, &object).expect("Could not write symcache");
+    let streamed =
+        SymCache::parse(ByteView::from_vec(cursor.into_inner())).expect("Could not parse symcache");
+    let regular = SymCache::from_object(&object).expect("Could not generate symcache");
+
+    // The streamed symcache contains the same functions and lines
+    let functions: Vec<_> = regular.functions().map(|f| f.unwrap().addr()).collect();
+    assert_eq!(
+        functions,
+        streamed
+            .functions()
+            .map(|f| f.unwrap().addr())
+            .collect::<Vec<_>>()
+    );
+
+    for addr in functions {
+        let expected = regular.lookup(addr).expect("Could not lookup");
+        let actual = streamed.lookup(addr).expect("Could not lookup");
+        assert_eq!(format!("{:?}", expected), format!("{:?}", actual));
+    }
+
+    // The name index is merged from the runs of all units
+    assert!(streamed.has_name_index().expect("Could not read header"));
+    for name in &[
+        "unit00_function00",
+        "unit42_function07",
+        "unit63_function15",
+        "missing",
+    ] {
+        let expected: Vec<_> = regular
+            .lookup_name(name)
+            .expect("Could not lookup")
+            .iter()
+            .map(|function| function.id())
+            .collect();
+        let actual: Vec<_> = streamed
+            .lookup_name(name)
+            .expect("Could not lookup")
+            .iter()
+            .map(|function| function.id())
+            .collect();
+        assert_eq!(expected, actual);
+    }
+
+    let functions = streamed
+        .lookup_name("unit42_function07")
+        .expect("Could not lookup");
+    assert_eq!(functions.len(), 1);
+
+    // Both fixtures contain compilation units of the same size, but eight times as many units
+    // in the large one. Only the unit headers and the symbol table index grow with the number of
+    // units, which are a small fraction of the unit contents.
+    let small = measure_peak_memory("linux/units/units8.so", true);
+    let large = measure_peak_memory("linux/units/units64.so", true);
+    assert!(large < small * 3, "{} >= 3 * {}", large, small);
+
+    let regular = measure_peak_memory("linux/units/units64.so", false);
+    assert!(large * 4 < regular, "4 * {} >= {}", large, regular);
+}
@@ -1,8 +1,9 @@
 use std::fmt::Write;
+use std::slice;
 
 use symbolic_common::byteview::ByteView;
 use symbolic_debuginfo::FatObject;
-use symbolic_symcache::SymCache;
+use symbolic_symcache::{DataSource, SymCache, SymCacheErrorKind, SymCacheWriter};
 use symbolic_testutils::{assert_snapshot, assert_snapshot_plain, fixture_path};
 
 fn get_functions(symcache: &SymCache<'_>) -> String {
@@ -43,6 +44,29 @@ fn test_write_functions_linux() {
     assert_snapshot_plain("functions_linux.txt", &functions);
 }
 
+#[test]
+fn test_write_symbols_linux() {
+    let buffer =
+        ByteView::from_path(fixture_path("linux/crash")).expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+    assert_eq!(
+        symcache.data_source().expect("Could not read data source"),
+        DataSource::SymbolTable
+    );
+    assert_eq!(symcache.functions().count(), 167);
+
+    let line_infos = symcache.lookup(0x1_3f00).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].symbol(), "ConvertUTF16toUTF32");
+    assert_eq!(line_infos[0].sym_addr(), 0x1_3eb0);
+}
+
 #[test]
 fn test_write_header_macos() {
     let buffer = ByteView::from_path(fixture_path(
@@ -88,3 +112,752 @@ fn test_write_large_symbol_names() {
 
     SymCache::from_object(&object).expect("Failed to process large symbol name");
 }
+
+#[test]
+fn test_write_breakpad_malformed_stack() {
+    // STACK records are not used for symbolication, so malformed unwind information must not fail
+    // the conversion.
+    let data = b"MODULE Linux x86_64 D3096ED481217FD4C16B29CD9BC208BA0 test
+FILE 0 main.c
+FUNC 1000 20 0 main
+1000 10 3 0
+1010 10 4 0
+STACK CFI 1000 .cfa: $rsp 8 +
+STACK CFI INIT
+STACK WIN 4 1000 20 0 0 0 0 0 0 1
+PUBLIC 2000 0 other
+";
+    let fat = FatObject::parse(ByteView::from_slice(data)).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+    let line_infos = symcache.lookup(0x1010).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].symbol(), "main");
+    assert_eq!(line_infos[0].line(), 4);
+
+    let line_infos = symcache.lookup(0x2000).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].symbol(), "other");
+}
+
+#[test]
+fn test_write_pdb() {
+    let buffer =
+        ByteView::from_path(fixture_path("windows/crash.pdb")).expect("Could not open the PDB");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+    assert_eq!(
+        symcache.data_source().expect("Could not read data source"),
+        DataSource::Pdb
+    );
+
+    let line_infos = symcache.lookup(0x1033).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(
+        line_infos[0].symbol(),
+        "google_breakpad::CrashGenerationClient::RequestDump"
+    );
+    assert_eq!(line_infos[0].sym_addr(), 0x1000);
+    assert_eq!(line_infos[0].line(), 329);
+
+    // Inlined member functions are qualified with their class name
+    let line_infos = symcache.lookup(0x1011).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 2);
+    assert_eq!(
+        line_infos[0].function_name(),
+        "google_breakpad::CrashGenerationClient::IsRegistered"
+    );
+    assert_eq!(
+        line_infos[1].function_name(),
+        "google_breakpad::CrashGenerationClient::RequestDump"
+    );
+
+    // Inline sites without line records are not emitted
+    for function in symcache.functions() {
+        let function = function.expect("Could not read function");
+        assert_ne!(function.addr(), 0);
+    }
+}
+
+#[test]
+fn test_write_large_values() {
+    let long_path = format!("/{}/large.c", "directory".repeat(40));
+    let sym = format!(
+        "MODULE Linux x86_64 C0BCC3F19827FE653058404B2831D9E60 large\n\
+         FILE 0 {}\n\
+         FUNC 1000 30000 0 large_function\n\
+         1000 10 70000 0\n\
+         1010 20000 123456 0\n\
+         21010 fff0 5 0\n\
+         PUBLIC 40000 0 after_large_function\n",
+        long_path
+    );
+
+    let buffer = ByteView::from_slice(sym.as_bytes());
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+    let buffer = ByteView::from_vec(symcache.as_bytes().to_vec());
+    let symcache = SymCache::parse(buffer).expect("Could not parse symcache");
+
+    // Line numbers beyond 65535 are preserved
+    let line_infos = symcache.lookup(0x1004).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].symbol(), "large_function");
+    assert_eq!(line_infos[0].line(), 70000);
+    assert_eq!(line_infos[0].filename(), long_path);
+
+    // Functions longer than 65535 bytes cover their entire range
+    let line_infos = symcache.lookup(0x2_0000).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].symbol(), "large_function");
+    assert_eq!(line_infos[0].line(), 123_456);
+    assert_eq!(line_infos[0].line_addr(), 0x1010);
+
+    // Address offsets beyond 65535 are split without affecting the lines in between
+    let line_infos = symcache.lookup(0x2_1010).expect("Could not lookup");
+    assert_eq!(line_infos[0].line(), 5);
+    assert_eq!(line_infos[0].line_addr(), 0x2_1010);
+
+    let line_infos = symcache.lookup(0x4_0000).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].symbol(), "after_large_function");
+
+    let function = symcache
+        .functions()
+        .next()
+        .expect("Missing function")
+        .expect("Could not read function");
+    let lines: Vec<_> = function
+        .lines()
+        .map(|line| line.expect("Could not read line").line())
+        .collect();
+    assert_eq!(lines, vec![70000, 123_456, 123_456, 123_456, 5]);
+}
+
+fn lookup_split_dwarf(dir: &str, split_files: &[&str]) -> Vec<(String, String, u32)> {
+    let buffer = ByteView::from_path(fixture_path(format!("{}/split", dir)))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let split_fats: Vec<_> = split_files
+        .iter()
+        .map(|name| {
+            let path = fixture_path(format!("{}/{}", dir, name));
+            let buffer = ByteView::from_path(path).expect("Could not open the split file");
+            FatObject::parse(buffer).expect("Could not create a split object")
+        })
+        .collect();
+    let split_objects: Vec<_> = split_fats
+        .iter()
+        .map(|fat| {
+            fat.get_object(0)
+                .expect("Could not get the split object")
+                .expect("Missing split object")
+        })
+        .collect();
+
+    let buffer = symbolic_symcache::to_vec_with_split_dwarf(&object, &split_objects)
+        .expect("Could not generate symcache");
+    let symcache = SymCache::parse(ByteView::from_vec(buffer)).expect("Could not parse symcache");
+
+    symcache
+        .lookup(0x1180)
+        .expect("Could not lookup")
+        .iter()
+        .map(|info| {
+            (
+                info.function_name(),
+                info.filename().to_string(),
+                info.line(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_write_split_dwarf_objects() {
+    let line_infos = lookup_split_dwarf("linux/split", &["main.dwo", "compute.dwo"]);
+    assert_eq!(
+        line_infos,
+        vec![
+            ("square".to_string(), "compute.c".to_string(), 2),
+            ("compute".to_string(), "compute.c".to_string(), 8),
+        ]
+    );
+}
+
+#[test]
+fn test_write_split_dwarf_package() {
+    let line_infos = lookup_split_dwarf("linux/split", &["split.dwp"]);
+    assert_eq!(
+        line_infos,
+        vec![
+            ("square".to_string(), "compute.c".to_string(), 2),
+            ("compute".to_string(), "compute.c".to_string(), 8),
+        ]
+    );
+}
+
+#[test]
+fn test_write_split_dwarf_missing() {
+    let line_infos = lookup_split_dwarf("linux/split", &[]);
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].0, "compute");
+    assert_eq!(line_infos[0].2, 0);
+}
+
+#[test]
+fn test_write_dwarf5() {
+    let buffer = ByteView::from_path(fixture_path("linux/dwarf5/dwarf5"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+    assert_eq!(
+        symcache.data_source().expect("Could not read data source"),
+        DataSource::Dwarf
+    );
+
+    let line_infos = symcache.lookup(0x1054).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 2);
+    assert_eq!(line_infos[0].function_name(), "twice");
+    assert_eq!(line_infos[0].base_dir(), "/work/dwarf5");
+    assert_eq!(line_infos[0].filename(), "main.c");
+    assert_eq!(line_infos[0].line(), 6);
+    assert_eq!(line_infos[1].function_name(), "main");
+    assert_eq!(line_infos[1].sym_addr(), 0x1050);
+
+    let line_infos = symcache.lookup(0x1180).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 2);
+    assert_eq!(line_infos[0].function_name(), "square");
+    assert_eq!(line_infos[0].filename(), "compute.c");
+    assert_eq!(line_infos[0].line(), 2);
+    assert_eq!(line_infos[1].function_name(), "compute");
+    assert_eq!(line_infos[1].line(), 8);
+}
+
+#[test]
+fn test_write_dwarf5_macos() {
+    let buffer = ByteView::from_path(fixture_path(
+        "macos/dwarf5.dSYM/Contents/Resources/DWARF/dwarf5",
+    ))
+    .expect("Could not open the dSYM file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+    assert_eq!(
+        symcache.data_source().expect("Could not read data source"),
+        DataSource::Dwarf
+    );
+
+    // File and directory names are resolved from `__debug_line_str`
+    let line_infos = symcache.lookup(0xf82).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].function_name(), "square");
+    assert_eq!(line_infos[0].sym_addr(), 0xf80);
+    assert_eq!(line_infos[0].base_dir(), "/work/macho");
+    assert_eq!(line_infos[0].filename(), "dwarf5.c");
+    assert_eq!(line_infos[0].line(), 2);
+
+    let line_infos = symcache.lookup(0xf93).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 2);
+    assert_eq!(line_infos[0].function_name(), "square");
+    assert_eq!(line_infos[0].line(), 2);
+    assert_eq!(line_infos[1].function_name(), "main");
+    assert_eq!(line_infos[1].sym_addr(), 0xf90);
+    assert_eq!(line_infos[1].line(), 6);
+
+    let line_infos = symcache.lookup(0xf97).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].function_name(), "main");
+    assert_eq!(line_infos[0].line(), 7);
+}
+
+#[test]
+fn test_write_dwarf5_split_objects() {
+    let line_infos = lookup_split_dwarf("linux/dwarf5", &["main.dwo", "compute.dwo"]);
+    assert_eq!(
+        line_infos,
+        vec![
+            ("square".to_string(), "compute.c".to_string(), 2),
+            ("compute".to_string(), "compute.c".to_string(), 8),
+        ]
+    );
+}
+
+#[test]
+fn test_write_dwz_supplementary() {
+    let buffer = ByteView::from_path(fixture_path("linux/dwz/main.debug"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let sup_buffer = ByteView::from_path(fixture_path("linux/dwz/sup.debug"))
+        .expect("Could not open the supplementary file");
+    let sup_fat = FatObject::parse(sup_buffer).expect("Could not create an object");
+    let sup_object = sup_fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let alt_link = object.debug_alt_link().expect("Missing debug alt link");
+    assert_eq!(alt_link.build_id(), sup_object.build_id().unwrap());
+
+    let buffer = symbolic_symcache::to_vec_with_supplementary(&object, &[], Some(&sup_object))
+        .expect("Could not generate symcache");
+    let symcache = SymCache::parse(ByteView::from_vec(buffer)).expect("Could not parse symcache");
+
+    // The inlined function is named by a `DW_FORM_GNU_ref_alt` abstract origin
+    let line_infos = symcache.lookup(0x1003).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 2);
+    assert_eq!(line_infos[0].function_name(), "inlined_helper");
+    assert_eq!(line_infos[0].line(), 3);
+    assert_eq!(line_infos[1].function_name(), "main");
+    assert_eq!(line_infos[1].line(), 10);
+
+    // The compilation directory is a `DW_FORM_GNU_strp_alt` string
+    assert_eq!(line_infos[0].base_dir(), "/work/dwz");
+
+    let line_infos = symcache.lookup(0x100e).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].function_name(), "shared_function");
+    assert_eq!(line_infos[0].line(), 20);
+}
+
+#[test]
+fn test_write_with_options() {
+    let buffer = ByteView::from_path(fixture_path("linux/dwz/main.debug"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let sup_buffer = ByteView::from_path(fixture_path("linux/dwz/sup.debug"))
+        .expect("Could not open the supplementary file");
+    let sup_fat = FatObject::parse(sup_buffer).expect("Could not create an object");
+    let sup_object = sup_fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    // The supplementary object is used by all output modes
+    let writer = SymCacheWriter::new().supplementary(&sup_object);
+    let buffers = vec![
+        writer.object_to_vec(&object),
+        writer.streaming(true).object_to_vec(&object),
+        writer.objects_to_vec(slice::from_ref(&object)),
+    ];
+
+    let mut results = buffers.into_iter().map(|buffer| {
+        let buffer = buffer.expect("Could not generate symcache");
+        let symcache =
+            SymCache::parse(ByteView::from_vec(buffer)).expect("Could not parse symcache");
+        let line_infos = symcache.lookup(0x1003).expect("Could not lookup");
+        format!("{:?}", line_infos)
+    });
+
+    let line_infos = results.next().expect("Missing line infos");
+    assert!(line_infos.contains("inlined_helper"));
+    for other in results {
+        assert_eq!(line_infos, other);
+    }
+
+    // Merging requires all functions in memory and cannot stream
+    let error = writer
+        .streaming(true)
+        .objects_to_vec(slice::from_ref(&object))
+        .expect_err("Merged objects with streaming");
+    assert_eq!(error.kind(), SymCacheErrorKind::UnsupportedStreaming);
+}
+
+#[test]
+fn test_write_lookup_name() {
+    let buffer = ByteView::from_path(fixture_path("linux/dwarf5/dwarf5"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+
+    let functions = symcache.lookup_name("square").expect("Could not lookup");
+    assert_eq!(functions.len(), 1);
+    assert_eq!(functions[0].addr(), 0x1180);
+    assert_eq!(functions[0].size(), 5);
+    assert!(functions[0].parent_id().is_some());
+
+    let functions = symcache.lookup_name("compute").expect("Could not lookup");
+    assert_eq!(functions.len(), 1);
+    assert_eq!(functions[0].addr(), 0x1170);
+    assert_eq!(functions[0].parent_id(), None);
+
+    let functions = symcache.lookup_name("missing").expect("Could not lookup");
+    assert!(functions.is_empty());
+}
+
+#[test]
+fn test_write_lookup_range() {
+    let buffer = ByteView::from_path(fixture_path("linux/dwarf5/dwarf5"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+
+    let names: Vec<_> = symcache
+        .lookup_range(0x1184, 0x1190)
+        .expect("Could not lookup")
+        .iter()
+        .map(|function| function.function_name())
+        .collect();
+    assert_eq!(names, vec!["compute", "square"]);
+
+    let names: Vec<_> = symcache
+        .lookup_range(0x1000, 0x1175)
+        .expect("Could not lookup")
+        .iter()
+        .map(|function| function.function_name())
+        .collect();
+    assert!(names.contains(&"main".to_string()));
+    assert!(names.contains(&"twice".to_string()));
+    assert!(names.contains(&"compute".to_string()));
+    assert!(!names.contains(&"square".to_string()));
+
+    assert!(symcache
+        .lookup_range(0x1180, 0x1180)
+        .expect("Could not lookup")
+        .is_empty());
+}
+
+#[test]
+fn test_write_lookup_range_overlapping() {
+    // The first function extends past the second one, which does not cover the range
+    let sym = "MODULE Linux x86_64 C0BCC3F19827FE653058404B2831D9E60 overlapping\n\
+               FUNC 1000 100 0 outer\n\
+               FUNC 1010 10 0 inner\n\
+               FUNC 1200 10 0 after\n";
+
+    let buffer = ByteView::from_slice(sym.as_bytes());
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+
+    let names: Vec<_> = symcache
+        .lookup_range(0x1080, 0x1090)
+        .expect("Could not lookup")
+        .iter()
+        .map(|function| function.function_name())
+        .collect();
+    assert_eq!(names, vec!["outer"]);
+
+    let names: Vec<_> = symcache
+        .lookup_range(0x1018, 0x1208)
+        .expect("Could not lookup")
+        .iter()
+        .map(|function| function.function_name())
+        .collect();
+    assert_eq!(names, vec!["outer", "inner", "after"]);
+}
+
+#[test]
+fn test_write_lookup_line() {
+    let buffer = ByteView::from_path(fixture_path("linux/dwarf5/dwarf5"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+
+    // Lines of an inlined function
+    let ranges = symcache
+        .lookup_line("compute.c", 2)
+        .expect("Could not lookup");
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0].addr(), 0x1180);
+    assert_eq!(ranges[0].size(), 5);
+    assert_eq!(ranges[0].function().function_name(), "square");
+    assert_eq!(ranges[0].full_filename(), "/work/dwarf5/compute.c");
+
+    // Call sites of an inlined function
+    let ranges = symcache
+        .lookup_line("dwarf5/compute.c", 8)
+        .expect("Could not lookup");
+    let ranges: Vec<_> = ranges
+        .iter()
+        .map(|range| (range.addr(), range.size(), range.function().function_name()))
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![
+            (0x1180, 5, "compute".to_string()),
+            (0x1188, 2, "compute".to_string())
+        ]
+    );
+
+    // Consecutive records of the same line are merged
+    let ranges = symcache
+        .lookup_line("/work/dwarf5/main.c", 10)
+        .expect("Could not lookup");
+    assert_eq!(ranges.len(), 2);
+    assert_eq!(ranges[0].addr(), 0x1050);
+    assert_eq!(ranges[0].size(), 11);
+
+    // Suffixes must match entire path components
+    assert!(symcache
+        .lookup_line("5/main.c", 10)
+        .expect("Could not lookup")
+        .is_empty());
+}
+
+#[test]
+fn test_write_merged() {
+    let dwarf_buffer = ByteView::from_path(fixture_path("linux/dwarf5/dwarf5"))
+        .expect("Could not open the ELF file");
+    let dwarf_fat = FatObject::parse(dwarf_buffer).expect("Could not create an object");
+    let dwarf_object = dwarf_fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let breakpad_buffer = ByteView::from_path(fixture_path("linux/dwarf5/dwarf5.sym"))
+        .expect("Could not open the Breakpad file");
+    let breakpad_fat = FatObject::parse(breakpad_buffer).expect("Could not create an object");
+    let breakpad_object = breakpad_fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    // DWARF takes precedence over Breakpad
+    let symcache = SymCache::from_objects(&[dwarf_object, breakpad_object])
+        .expect("Could not generate symcache");
+    assert_eq!(symcache.data_source().unwrap(), DataSource::Dwarf);
+
+    let line_infos = symcache.lookup(0x1176).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].function_name(), "compute");
+
+    let functions = symcache.lookup_name("compute").expect("Could not lookup");
+    assert_eq!(functions.len(), 1);
+    assert_eq!(functions[0].data_source(), DataSource::Dwarf);
+    assert!(symcache
+        .lookup_name("compute_breakpad")
+        .expect("Could not lookup")
+        .is_empty());
+
+    let functions = symcache
+        .lookup_name("breakpad_only")
+        .expect("Could not lookup");
+    assert_eq!(functions.len(), 1);
+    assert_eq!(functions[0].addr(), 0x2000);
+    assert_eq!(functions[0].data_source(), DataSource::BreakpadSym);
+
+    // Functions remain sorted by address
+    let addrs: Vec<_> = symcache.functions().map(|f| f.unwrap().addr()).collect();
+    let mut sorted = addrs.clone();
+    sorted.sort();
+    assert_eq!(addrs, sorted);
+}
+
+#[test]
+fn test_write_merged_precedence() {
+    let dwarf_buffer = ByteView::from_path(fixture_path("linux/dwarf5/dwarf5"))
+        .expect("Could not open the ELF file");
+    let dwarf_fat = FatObject::parse(dwarf_buffer).expect("Could not create an object");
+    let dwarf_object = dwarf_fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let breakpad_buffer = ByteView::from_path(fixture_path("linux/dwarf5/dwarf5.sym"))
+        .expect("Could not open the Breakpad file");
+    let breakpad_fat = FatObject::parse(breakpad_buffer).expect("Could not create an object");
+    let breakpad_object = breakpad_fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    // Breakpad takes precedence over DWARF, which only fills the gaps
+    let symcache = SymCache::from_objects(&[breakpad_object, dwarf_object])
+        .expect("Could not generate symcache");
+    assert_eq!(symcache.data_source().unwrap(), DataSource::BreakpadSym);
+
+    let line_infos = symcache.lookup(0x1185).expect("Could not lookup");
+    assert_eq!(line_infos.len(), 1);
+    assert_eq!(line_infos[0].function_name(), "compute_breakpad");
+    assert_eq!(line_infos[0].line(), 8);
+
+    // The inlinees of overlapping functions are dropped as well
+    assert!(symcache
+        .lookup_name("square")
+        .expect("Could not lookup")
+        .is_empty());
+
+    let functions = symcache.lookup_name("main").expect("Could not lookup");
+    assert_eq!(functions.len(), 1);
+    assert_eq!(functions[0].data_source(), DataSource::Dwarf);
+}
+
+#[test]
+fn test_write_merged_mismatched_id() {
+    let dwarf_buffer = ByteView::from_path(fixture_path("linux/dwarf5/dwarf5"))
+        .expect("Could not open the ELF file");
+    let dwarf_fat = FatObject::parse(dwarf_buffer).expect("Could not create an object");
+    let dwarf_object = dwarf_fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let crash_buffer =
+        ByteView::from_path(fixture_path("linux/crash")).expect("Could not open the ELF file");
+    let crash_fat = FatObject::parse(crash_buffer).expect("Could not create an object");
+    let crash_object = crash_fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let error =
+        SymCache::from_objects(&[dwarf_object, crash_object]).expect_err("Merging should fail");
+    assert_eq!(error.kind(), SymCacheErrorKind::MismatchedDebugId);
+}
+
+#[cfg(feature = "with_rayon")]
+#[test]
+fn test_write_parallel() {
+    let buffer = ByteView::from_path(fixture_path("linux/units/units64.so"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let to_vec_with_threads = |num_threads| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .expect("Could not create a thread pool")
+            .install(|| symbolic_symcache::to_vec(&object))
+            .expect("Could not write symcache")
+    };
+
+    // The output must not depend on the number of threads
+    let sequential = to_vec_with_threads(1);
+    assert_eq!(sequential, to_vec_with_threads(4));
+    assert_eq!(sequential, to_vec_with_threads(16));
+
+    // Nor on the parallel writer, see `test_write_units`
+    let fixture = ByteView::from_path(fixture_path("symcache/current/units64.symc"))
+        .expect("Could not open the symcache fixture");
+    assert_eq!(sequential, &fixture[..]);
+}
+
+#[test]
+fn test_write_units() {
+    let buffer = ByteView::from_path(fixture_path("linux/units/units64.so"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    // The fixture has been written without the `with_rayon` feature, so that the parallel writer
+    // is compared against the sequential one in `test_write_parallel`.
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+    let fixture = ByteView::from_path(fixture_path("symcache/current/units64.symc"))
+        .expect("Could not open the symcache fixture");
+    assert_eq!(symcache.as_bytes(), &fixture[..]);
+}
+
+#[test]
+fn test_write_deterministic() {
+    let buffer = ByteView::from_path(fixture_path("linux/crash.debug"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let first = SymCache::from_object(&object).expect("Could not generate symcache");
+    let second = SymCache::from_object(&object).expect("Could not generate symcache");
+    assert_eq!(first.as_bytes(), second.as_bytes());
+
+    // The output must also be stable across processes, which rules out randomly seeded hashers
+    let fixture = ByteView::from_path(fixture_path("symcache/current/linux.symc"))
+        .expect("Could not open the symcache fixture");
+    assert_eq!(first.as_bytes(), &fixture[..]);
+}
+
+#[test]
+fn test_write_checksum() {
+    let buffer = ByteView::from_path(fixture_path("linux/crash.debug"))
+        .expect("Could not open the ELF file");
+    let fat = FatObject::parse(buffer).expect("Could not create an object");
+    let object = fat
+        .get_object(0)
+        .expect("Could not get the first object")
+        .expect("Missing object");
+
+    let symcache = SymCache::from_object(&object).expect("Could not generate symcache");
+    let bytes = symcache.as_bytes().to_vec();
+    SymCache::parse_verified(ByteView::from_slice(&bytes)).expect("Could not verify symcache");
+
+    let assert_corrupted = |offset: usize| {
+        let mut corrupted = bytes.clone();
+        corrupted[offset] ^= 0xff;
+        let error = SymCache::parse_verified(ByteView::from_slice(&corrupted))
+            .expect_err("Corrupted symcache was verified");
+        assert_eq!(error.kind(), SymCacheErrorKind::ChecksumMismatch);
+    };
+
+    // A byte of the debug id in the header
+    assert_corrupted(8);
+    // A byte in the middle of the data following the header
+    assert_corrupted(bytes.len() / 2);
+    // The last byte, which belongs to the name index
+    assert_corrupted(bytes.len() - 1);
+}
@@ -5,6 +5,8 @@ use std::{fmt, io};
 
 use difference::Changeset;
 
+pub mod minidump;
+
 /// Loads the file at the given location and returns its contents as string.
 fn load_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
     let mut file = File::open(path)?;
@@ -27,6 +29,14 @@ pub fn load_fixture<S: AsRef<str>>(file_name: S) -> io::Result<String> {
     load_file(fixture_path(file_name))
 }
 
+/// Reads the fixture file with the given name and returns its raw contents.
+///
+/// Panics if the file cannot be read.
+pub fn read_fixture<S: AsRef<str>>(file_name: S) -> Vec<u8> {
+    let path = fixture_path(file_name);
+    std::fs::read(&path).unwrap_or_else(|_| panic!("Could not read fixture {}", path.display()))
+}
+
 /// Asserts that the given object matches the snapshot saved in the snapshot
 /// file. The object is serialized using the Debug trait.
 ///
@@ -0,0 +1,117 @@
+//! Helpers to build minidump files for tests.
+
+/// Size of the minidump header, which is followed by the first stream's data.
+const HEADER_SIZE: usize = 32;
+
+/// Appends a location descriptor of the given size and RVA to a buffer.
+pub fn push_location(buffer: &mut Vec<u8>, (size, rva): (u32, u32)) {
+    buffer.extend_from_slice(&size.to_le_bytes());
+    buffer.extend_from_slice(&rva.to_le_bytes());
+}
+
+/// Builds minidump files from raw streams.
+///
+/// Data referenced by a stream is pushed first, which returns its location in the file. The
+/// stream is then added with these locations. The stream directory is written by `finish`.
+#[derive(Clone, Debug)]
+pub struct MinidumpBuilder {
+    data: Vec<u8>,
+    directory: Vec<u8>,
+}
+
+impl MinidumpBuilder {
+    /// Creates a builder for an empty minidump.
+    pub fn new() -> Self {
+        MinidumpBuilder {
+            data: vec![0; HEADER_SIZE],
+            directory: Vec::new(),
+        }
+    }
+
+    /// Creates a builder that appends streams to an existing minidump.
+    ///
+    /// The data and streams of the minidump are retained.
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let read_u32 = |offset: usize| {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(&data[offset..offset + 4]);
+            u32::from_le_bytes(bytes) as usize
+        };
+
+        let stream_count = read_u32(8);
+        let directory_rva = read_u32(12);
+        MinidumpBuilder {
+            data: data.to_vec(),
+            directory: data[directory_rva..directory_rva + stream_count * 12].to_vec(),
+        }
+    }
+
+    /// Creates a builder that appends streams to the minidump fixture with the given name.
+    pub fn from_fixture<S: AsRef<str>>(file_name: S) -> Self {
+        Self::from_bytes(&crate::read_fixture(file_name))
+    }
+
+    /// Returns the RVA of the data pushed next.
+    pub fn next_rva(&self) -> u32 {
+        align(self.data.len()) as u32
+    }
+
+    /// Appends data at the next 4-byte boundary and returns its location descriptor.
+    pub fn push(&mut self, data: &[u8]) -> (u32, u32) {
+        let rva = self.next_rva();
+        self.data.resize(rva as usize, 0);
+        self.data.extend_from_slice(data);
+        (data.len() as u32, rva)
+    }
+
+    /// Appends a byte array prefixed with its 32-bit length and returns its RVA.
+    ///
+    /// This is the format of Crashpad's `MinidumpUTF8String` and `MinidumpByteArray`.
+    pub fn push_bytes(&mut self, data: &[u8]) -> u32 {
+        let mut bytes = (data.len() as u32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(data);
+        self.push(&bytes).1
+    }
+
+    /// Appends a UTF-16 `MINIDUMP_STRING` and returns its RVA.
+    pub fn push_string(&mut self, string: &str) -> u32 {
+        let units: Vec<u16> = string.encode_utf16().collect();
+        let mut bytes = (units.len() as u32 * 2).to_le_bytes().to_vec();
+        for unit in units.into_iter().chain(Some(0)) {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        self.push(&bytes).1
+    }
+
+    /// Appends a stream and adds it to the stream directory. Returns the stream's RVA.
+    pub fn add_stream(&mut self, stream_type: u32, stream: &[u8]) -> u32 {
+        let location = self.push(stream);
+        self.directory.extend_from_slice(&stream_type.to_le_bytes());
+        push_location(&mut self.directory, location);
+        location.1
+    }
+
+    /// Writes the stream directory and the header, and returns the minidump.
+    pub fn finish(mut self) -> Vec<u8> {
+        let directory = std::mem::take(&mut self.directory);
+        let stream_count = directory.len() as u32 / 12;
+        let (_, directory_rva) = self.push(&directory);
+
+        self.data[..4].copy_from_slice(b"MDMP");
+        self.data[4..8].copy_from_slice(&0xa793u32.to_le_bytes());
+        self.data[8..12].copy_from_slice(&stream_count.to_le_bytes());
+        self.data[12..16].copy_from_slice(&directory_rva.to_le_bytes());
+        self.data
+    }
+}
+
+impl Default for MinidumpBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rounds an offset up to the next multiple of four.
+fn align(offset: usize) -> usize {
+    (offset + 3) & !3
+}